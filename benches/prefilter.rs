@@ -0,0 +1,126 @@
+//! 原始字节预过滤的单笔交易开销对比
+//!
+//! 运行：`cargo bench --bench prefilter`
+//!
+//! - before：每笔交易都交给 `TransactionProcessor` 完整解码（base58 + 构建 VersionedTransaction + IDL 解码）
+//! - after：先用 `InstructionPrefilter` 在字节切片上判断，只有候选交易才完整解码
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use solana_sdk::pubkey::Pubkey;
+use solana_spining::constant::accounts::{PUMPFUN, SYSTEM_PROGRAM};
+use solana_spining::processors::{InstructionPrefilter, TransactionEventDetector, TransactionProcessor};
+use yellowstone_grpc_proto::geyser::SubscribeUpdateTransactionInfo;
+use yellowstone_grpc_proto::prelude::{CompiledInstruction, Message, MessageHeader, Transaction, TransactionStatusMeta};
+
+const WARM_UP: Duration = Duration::from_millis(300);
+const MEASUREMENT: Duration = Duration::from_secs(2);
+
+/// ComputeBudget 之类与代币事件无关的程序
+const COMPUTE_BUDGET: Pubkey = Pubkey::from_str_const("ComputeBudget111111111111111111111111111111");
+
+/// 含 PumpFun 程序账户的交易；`discriminator` 决定是否为候选指令
+fn pumpfun_transaction(discriminator: [u8; 8]) -> SubscribeUpdateTransactionInfo {
+    let mut account_keys: Vec<Vec<u8>> = (0..16u8).map(|i| vec![i + 1; 32]).collect();
+    account_keys.push(SYSTEM_PROGRAM.to_bytes().to_vec());
+    account_keys.push(COMPUTE_BUDGET.to_bytes().to_vec());
+    account_keys.push(PUMPFUN.to_bytes().to_vec());
+    let pumpfun_index = account_keys.len() as u32 - 1;
+
+    let instructions = vec![
+        CompiledInstruction { program_id_index: pumpfun_index - 1, accounts: vec![], data: vec![2, 0, 0, 1, 0] },
+        CompiledInstruction {
+            program_id_index: pumpfun_index,
+            accounts: (0..16).collect(),
+            data: [discriminator.as_slice(), &1_000_000u64.to_le_bytes(), &10_000_000u64.to_le_bytes()].concat(),
+        },
+    ];
+
+    SubscribeUpdateTransactionInfo {
+        signature: vec![7; 64],
+        is_vote: false,
+        transaction: Some(Transaction {
+            signatures: vec![vec![7; 64]],
+            message: Some(Message {
+                header: Some(MessageHeader {
+                    num_required_signatures: 1,
+                    num_readonly_signed_accounts: 0,
+                    num_readonly_unsigned_accounts: 3,
+                }),
+                account_keys,
+                recent_blockhash: vec![9; 32],
+                instructions,
+                versioned: false,
+                address_table_lookups: vec![],
+            }),
+        }),
+        meta: Some(TransactionStatusMeta {
+            log_messages: vec!["Program 6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P invoke [1]".to_string()],
+            ..Default::default()
+        }),
+        index: 0,
+    }
+}
+
+/// 预热后在固定时长内批量运行，报告每次迭代的平均耗时
+fn bench(name: &str, mut routine: impl FnMut()) -> Duration {
+    let warm_up_end = Instant::now() + WARM_UP;
+    let mut warm_up_iterations = 0u64;
+    while Instant::now() < warm_up_end {
+        routine();
+        warm_up_iterations += 1;
+    }
+
+    // 每批约 10ms，减少计时本身的开销
+    let batch = (warm_up_iterations * 10 / WARM_UP.as_millis() as u64).max(1);
+    let mut iterations = 0u64;
+    let mut elapsed = Duration::ZERO;
+    while elapsed < MEASUREMENT {
+        let start = Instant::now();
+        for _ in 0..batch {
+            routine();
+        }
+        elapsed += start.elapsed();
+        iterations += batch;
+    }
+
+    let per_iteration = elapsed / iterations as u32;
+    println!("{:<44} time: {:>12?}   ({} iterations)", name, per_iteration, iterations);
+    per_iteration
+}
+
+fn main() {
+    let processor = TransactionProcessor::default();
+    let prefilter = InstructionPrefilter::pumpfun();
+
+    // 服务端按账户过滤后，大部分推送的交易只是引用了程序而不含 create/buy/sell
+    let irrelevant = pumpfun_transaction([27, 234, 178, 52, 147, 2, 187, 141]);
+    let candidate = pumpfun_transaction([102, 6, 61, 18, 1, 218, 235, 234]);
+
+    let detect = |txn: &SubscribeUpdateTransactionInfo| futures::executor::block_on(processor.detect(txn, 1));
+
+    println!("prefilter/irrelevant");
+    let before = bench("  before (full decode)", || {
+        black_box(detect(black_box(&irrelevant)));
+    });
+    let after = bench("  after (prefilter)", || {
+        let txn = black_box(&irrelevant);
+        if prefilter.matches(txn) {
+            black_box(detect(txn));
+        }
+    });
+    println!("  speedup: {:.1}x\n", before.as_secs_f64() / after.as_secs_f64());
+
+    println!("prefilter/candidate");
+    let before = bench("  before (full decode)", || {
+        black_box(detect(black_box(&candidate)));
+    });
+    let after = bench("  after (prefilter + full decode)", || {
+        let txn = black_box(&candidate);
+        if prefilter.matches(txn) {
+            black_box(detect(txn));
+        }
+    });
+    println!("  overhead: {:?}", after.saturating_sub(before));
+}
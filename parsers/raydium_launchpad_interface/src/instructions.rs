@@ -1,7 +1,7 @@
 use crate::*;
 use borsh::{BorshDeserialize, BorshSerialize};
 
-use typedefs::{MintParams, CurveParams, VestingParams};
+use typedefs::{AmmCreatorFeeOn, MintParams, CurveParams, TransferFeeExtensionParams, VestingParams};
 use std::io::Read;
 use strum_macros::{Display, EnumString};
 
@@ -11,6 +11,8 @@ pub enum RaydiumLaunchpadProgramIx {
     BuyExactIn(BuyExactInIxArgs),
     BuyExactOut(BuyExactOutIxArgs),
     Initialize(InitializeIxArgs),
+    InitializeV2(InitializeV2IxArgs),
+    InitializeWithToken2022(InitializeWithToken2022IxArgs),
     SellExactIn(SellExactInIxArgs),
     SellExactOut(SellExactOutIxArgs),
 }
@@ -21,6 +23,8 @@ impl RaydiumLaunchpadProgramIx {
             Self::BuyExactIn(_) => "BuyExactIn",
             Self::BuyExactOut(_) => "BuyExactOut", 
             Self::Initialize(_) => "Initialize",
+            Self::InitializeV2(_) => "InitializeV2",
+            Self::InitializeWithToken2022(_) => "InitializeWithToken2022",
             Self::SellExactIn(_) => "SellExactIn",
             Self::SellExactOut(_) => "SellExactOut",
         }
@@ -40,6 +44,12 @@ impl RaydiumLaunchpadProgramIx {
             INITIALIZE_IX_DISCM => {
                 Ok(Self::Initialize(InitializeIxArgs::deserialize(&mut reader)?))
             }
+            INITIALIZE_V2_IX_DISCM => {
+                Ok(Self::InitializeV2(InitializeV2IxArgs::deserialize(&mut reader)?))
+            }
+            INITIALIZE_WITH_TOKEN_2022_IX_DISCM => {
+                Ok(Self::InitializeWithToken2022(InitializeWithToken2022IxArgs::deserialize(&mut reader)?))
+            }
             SELL_EXACT_IN_IX_DISCM => {
                 Ok(Self::SellExactIn(SellExactInIxArgs::deserialize(&mut reader)?))
             }
@@ -69,6 +79,14 @@ impl RaydiumLaunchpadProgramIx {
                 writer.write_all(&INITIALIZE_IX_DISCM)?;
                 args.serialize(&mut writer)
             }
+            Self::InitializeV2(args) => {
+                writer.write_all(&INITIALIZE_V2_IX_DISCM)?;
+                args.serialize(&mut writer)
+            }
+            Self::InitializeWithToken2022(args) => {
+                writer.write_all(&INITIALIZE_WITH_TOKEN_2022_IX_DISCM)?;
+                args.serialize(&mut writer)
+            }
             Self::SellExactIn(args) => {
                 writer.write_all(&SELL_EXACT_IN_IX_DISCM)?;
                 args.serialize(&mut writer)
@@ -91,6 +109,8 @@ impl RaydiumLaunchpadProgramIx {
 pub const BUY_EXACT_IN_IX_DISCM: [u8; 8] = [250, 234, 13, 123, 213, 156, 19, 236];
 pub const BUY_EXACT_OUT_IX_DISCM: [u8; 8] = [24, 211, 116, 40, 105, 3, 153, 56];
 pub const INITIALIZE_IX_DISCM: [u8; 8] = [175, 175, 109, 31, 13, 152, 155, 237];
+pub const INITIALIZE_V2_IX_DISCM: [u8; 8] = [67, 153, 175, 39, 218, 16, 38, 32];
+pub const INITIALIZE_WITH_TOKEN_2022_IX_DISCM: [u8; 8] = [37, 190, 126, 222, 44, 154, 171, 17];
 pub const SELL_EXACT_IN_IX_DISCM: [u8; 8] = [149, 39, 222, 155, 211, 124, 152, 26];
 pub const SELL_EXACT_OUT_IX_DISCM: [u8; 8] = [95, 200, 71, 34, 8, 9, 11, 166];
 
@@ -105,6 +125,27 @@ pub struct InitializeIxArgs {
     pub vesting_param: VestingParams,
 }
 
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InitializeV2IxArgs {
+    pub base_mint_param: MintParams,
+    pub curve_param: CurveParams,
+    pub vesting_param: VestingParams,
+    pub amm_fee_on: AmmCreatorFeeOn,
+}
+
+/// Creates a Token-2022 base mint; the account list has no metadata account,
+/// so `base_token_program` sits at index 10 instead of 11.
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InitializeWithToken2022IxArgs {
+    pub base_mint_param: MintParams,
+    pub curve_param: CurveParams,
+    pub vesting_param: VestingParams,
+    pub amm_fee_on: AmmCreatorFeeOn,
+    pub transfer_fee_extension_param: Option<TransferFeeExtensionParams>,
+}
+
 // ========== TRADING INSTRUCTIONS ==========
 
 #[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq, Default)]
@@ -144,7 +185,7 @@ pub struct SellExactOutIxArgs {
 impl RaydiumLaunchpadProgramIx {
     /// Returns true if this instruction creates a new pool (new token launch)
     pub fn is_pool_creation(&self) -> bool {
-        matches!(self, Self::Initialize(_))
+        matches!(self, Self::Initialize(_) | Self::InitializeV2(_) | Self::InitializeWithToken2022(_))
     }
 
     /// Returns true if this is a trading instruction
@@ -155,10 +196,12 @@ impl RaydiumLaunchpadProgramIx {
         )
     }
 
-    /// Extracts token information from Initialize instruction
+    /// Extracts token information from any pool creation instruction
     pub fn get_token_info(&self) -> Option<&MintParams> {
         match self {
             Self::Initialize(args) => Some(&args.base_mint_param),
+            Self::InitializeV2(args) => Some(&args.base_mint_param),
+            Self::InitializeWithToken2022(args) => Some(&args.base_mint_param),
             _ => None,
         }
     }
@@ -1,6 +1,14 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::pubkey::Pubkey;
 
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AmmCreatorFeeOn {
+    #[default]
+    QuoteToken,
+    BothToken,
+}
+
 #[derive(Clone, Debug, BorshDeserialize, BorshSerialize, PartialEq, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ClaimVestedEvent {
@@ -215,6 +223,13 @@ pub struct TradeEvent {
     pub pool_status: PoolStatus,
 }
 
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TransferFeeExtensionParams {
+    pub transfer_fee_basis_points: u16,
+    pub maximum_fee: u64,
+}
+
 #[derive(Clone, Debug, BorshDeserialize, BorshSerialize, PartialEq, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VestingParams {
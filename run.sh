@@ -119,6 +119,11 @@ case "$COMMAND" in
         echo -e "${BLUE}🚀 启动 PumpFun 监控模式...${NC}"
         echo "  - 配置通过 config.toml 加载"
         ;;
+    "all")
+        echo -e "${BLUE}🌐 启动 PumpFun + LetsBonk 多协议监控模式...${NC}"
+        echo "  - 单一订阅，共享策略管理器与持仓上限"
+        echo "  - 配置通过 config.toml 加载"
+        ;;
     "--help"|"-h"|"help")
         echo -e "${BLUE}📖 显示帮助信息...${NC}"
         ;;
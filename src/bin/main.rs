@@ -1,18 +1,24 @@
 use anyhow::Result;
 use clap::Parser;
+use futures::StreamExt;
 use log::{info, warn, error};
 use std::sync::Arc;
 
 // Import from library - 优化版组件
 use solana_spining::{
-    TokenEvent, TransactionType,
-    ShyftStream, LetsbonkStream,
+    TokenEvent, TradeDirection, StreamShyftConfig, StreamPubsubConfig,
+    ShyftStream, LetsbonkStream, MultiProtocolStream, RacingTokenStream, ReplayStream, RpcPubsubStream, AccountPriceStream, TokenEventSource,
+    streams::{geyser::TransactionFilter, shyft::pumpfun_filter, letsbonk::letsbonk_filter, ReplayPace, UpdateRecorder},
     // 优化后的组件 - 只保留新方案
-    OptimizedStrategyManager, OptimizedTokenFilter, OptimizedExecutorManager,
+    OptimizedStrategyManager, OptimizedTokenFilter, OptimizedExecutorManager, EventPipeline, PushOutcome,
     // 新配置系统
     config::{ConfigManager, AppConfig},
     // 区块哈希缓存
     BlockhashCache,
+    // IDL 注册表
+    idl::IdlRegistry,
+    // Launchpad 平台白名单
+    processors::LaunchpadPlatforms,
     // 🆕 计算预算管理
     executor::compute_budget::{DynamicComputeBudgetManager, ComputeBudgetConfig},
 };
@@ -40,6 +46,26 @@ struct Args {
     #[arg(long)]
     trading_enabled: bool,
     
+    /// 同时订阅所有 [shyft.grpc_regions] 端点，按签名去重取最先到达的交易
+    #[arg(long)]
+    race: bool,
+    
+    /// 录制收到的原始 gRPC 订阅更新到文件，用于离线复现
+    #[arg(long)]
+    record: Option<String>,
+    
+    /// 重放录制文件代替实时订阅
+    #[arg(long)]
+    replay: Option<String>,
+    
+    /// 重放时不按录制间隔等待，尽可能快地处理
+    #[arg(long)]
+    replay_fast: bool,
+    
+    /// 使用标准 RPC WebSocket (logsSubscribe) 代替 gRPC，例如 wss://api.mainnet-beta.solana.com
+    #[arg(long)]
+    websocket: Option<String>,
+    
     /// 显示配置摘要
     #[arg(long)]
     show_config: bool,
@@ -57,6 +83,8 @@ struct Args {
 enum StreamType {
     PumpFun,
     Letsbonk,
+    /// 单进程同时监听 PumpFun 与 LetsBonk，共享同一个策略管理器和持仓上限
+    All,
 }
 
 #[derive(clap::ValueEnum, Debug, Clone)]
@@ -137,6 +165,11 @@ async fn main() -> Result<()> {
 
     let app_config = &config_manager.app_config;
 
+    // 事件流内部创建的处理器共用 IDL 注册表和平台白名单，须在创建事件流之前设置
+    let config_dir = std::path::Path::new(&args.config).parent().unwrap_or(std::path::Path::new("."));
+    IdlRegistry::from_config(&app_config.idl, config_dir)?.install()?;
+    LaunchpadPlatforms::from_config(&app_config.launchpad)?.install()?;
+
     // 初始化区块哈希缓存
     let blockhash_cache = {
         // 构建带API key的Shyft RPC端点
@@ -149,7 +182,15 @@ async fn main() -> Result<()> {
             shyft_rpc_endpoint.clone()
         };
 
-        let mut cache = BlockhashCache::new(rpc_endpoint_with_key.clone());
+        let mut cache = BlockhashCache::new(rpc_endpoint_with_key.clone())
+            .with_config(&app_config.blockhash_cache);
+        // 有 gRPC 密钥时由 slot + blocks_meta 订阅驱动，RPC 轮询只作为回退
+        if let Ok(grpc_api_key) = config_manager.get_shyft_api_key() {
+            cache = cache.with_grpc(
+                StreamShyftConfig::new(app_config.get_shyft_grpc_endpoint(None), grpc_api_key.to_string())
+                    .with_timeout(app_config.shyft.timeout_seconds),
+            );
+        }
         match cache.start() {
             Ok(()) => {
                 info!("✅ 区块哈希缓存已启动，RPC端点: {}", 
@@ -219,9 +260,50 @@ async fn main() -> Result<()> {
         None
     };
 
-    let result = match stream_type {
-        StreamType::PumpFun => run_pumpfun_stream(args, &config_manager, executor_manager, blockhash_cache.as_ref(), shared_compute_budget_manager).await,
-        StreamType::Letsbonk => run_letsbonk_stream(args, &config_manager, executor_manager, blockhash_cache.as_ref(), shared_compute_budget_manager).await,
+    let profile = stream_type.profile();
+    let result = if let Some(path) = args.replay.clone() {
+        // 离线重放录制文件，无需 gRPC 连接
+        let pace = if args.replay_fast { ReplayPace::AsFastAsPossible } else { ReplayPace::Recorded };
+        let stream = stream_type.filters().into_iter().fold(ReplayStream::new(path, pace), ReplayStream::with_filter);
+        run_event_source(stream, profile, args, &config_manager, executor_manager, blockhash_cache.as_ref(), shared_compute_budget_manager).await
+    } else if let Some(ws_endpoint) = args.websocket.clone() {
+        // gRPC 不可用时的降级方案，延迟更高
+        info!("🔌 使用 RPC WebSocket 事件源: {}", ws_endpoint);
+        let pubsub_config = StreamPubsubConfig::from_ws_endpoint(ws_endpoint)
+            .with_creations_only(app_config.pubsub.creations_only);
+        let stream = stream_type.filters().into_iter().fold(RpcPubsubStream::new(pubsub_config), RpcPubsubStream::with_filter);
+        run_event_source(stream, profile, args, &config_manager, executor_manager, blockhash_cache.as_ref(), shared_compute_budget_manager).await
+    } else {
+        let stream_config = StreamShyftConfig::new(
+            app_config.get_shyft_grpc_endpoint(None), 
+            config_manager.get_shyft_api_key()?.to_string()
+        ).with_timeout(app_config.shyft.timeout_seconds)
+            .with_stall_timeout(app_config.shyft.stall_timeout_seconds);
+
+        let recorder = match &args.record {
+            Some(path) => {
+                info!("📼 录制原始订阅更新到: {}", path);
+                Some(Arc::new(UpdateRecorder::create(path).await?))
+            }
+            None => None,
+        };
+
+        if args.race {
+            let endpoints = app_config
+                .get_shyft_grpc_endpoints()
+                .into_iter()
+                .map(|(region, endpoint)| (region, StreamShyftConfig { endpoint, ..stream_config.clone() }))
+                .collect();
+            let stream = RacingTokenStream::new(profile.label, endpoints, stream_type.filters());
+            let stream = with_recorder(stream, &recorder, RacingTokenStream::with_recorder);
+            run_event_source(stream, profile, args, &config_manager, executor_manager, blockhash_cache.as_ref(), shared_compute_budget_manager).await
+        } else {
+            match stream_type {
+                StreamType::PumpFun => run_event_source(with_recorder(ShyftStream::new(stream_config), &recorder, ShyftStream::with_recorder), profile, args, &config_manager, executor_manager, blockhash_cache.as_ref(), shared_compute_budget_manager).await,
+                StreamType::Letsbonk => run_event_source(with_recorder(LetsbonkStream::new(stream_config), &recorder, LetsbonkStream::with_recorder), profile, args, &config_manager, executor_manager, blockhash_cache.as_ref(), shared_compute_budget_manager).await,
+                StreamType::All => run_event_source(with_recorder(MultiProtocolStream::new(stream_config), &recorder, MultiProtocolStream::with_recorder), profile, args, &config_manager, executor_manager, blockhash_cache.as_ref(), shared_compute_budget_manager).await,
+            }
+        }
     };
 
     // 停止区块哈希缓存
@@ -383,7 +465,7 @@ fn create_optimized_token_filter(strategy: &StrategyType, app_config: &AppConfig
         }
     };
 
-    Ok(OptimizedTokenFilter::new(criteria))
+    OptimizedTokenFilter::new(criteria)
 }
 
 /// 创建计算预算管理器
@@ -455,113 +537,68 @@ async fn create_compute_budget_manager(
     }
 }
 
-async fn run_pumpfun_stream(
-    args: Args, 
-    config_manager: &ConfigManager,
-    executor_manager: Option<Arc<OptimizedExecutorManager>>,
-    _blockhash_cache: Option<&Arc<BlockhashCache>>,
-    shared_compute_budget_manager: Option<Arc<DynamicComputeBudgetManager>>,
-) -> Result<()> {
-    info!("启动 Shyft gRPC 监听");
-    
-    let app_config = &config_manager.app_config;
-    
-    // 使用共享的计算预算管理器，如果没有则创建新的
-    let compute_budget_manager = if let Some(shared_manager) = shared_compute_budget_manager {
-        info!("🔗 使用共享的计算预算管理器");
-        Some(shared_manager)
-    } else {
-        info!("📋 创建新的计算预算管理器");
-        create_compute_budget_manager(app_config, config_manager).await?
-    };
-    
-    // 创建优化版策略管理器
-    let strategy_config = solana_spining::StrategyConfig {
-        buy_amount_lamports: app_config.strategy.trading.buy_amount_lamports,
-        max_slippage_bps: app_config.general.default_slippage_bps,
-        holding_duration_seconds: 3,
-        stop_loss_percentage: Some(app_config.strategy.trading.stop_loss_percent),
-        take_profit_percentage: Some(app_config.strategy.trading.take_profit_percent),
-        enable_emergency_sell: true,
-    };
-    
-    let strategy_type = args.strategy.unwrap_or(StrategyType::Default);
-    let optimized_filter = create_optimized_token_filter(&strategy_type, app_config)?;
-    let strategy_manager = OptimizedStrategyManager::new(
-        executor_manager.clone(),
-        Some(strategy_config.clone()),
-        Some(app_config.strategy.trading.max_positions as usize),
-        optimized_filter,
-        compute_budget_manager, // 🆕 传递计算预算管理器
-        _blockhash_cache.cloned(), // 传递区块哈希缓存用于区块对齐
-    );
-    
-    info!("最大并发策略数: {}", app_config.strategy.trading.max_positions);
-    
-    let config = solana_spining::StreamShyftConfig::new(
-        app_config.get_shyft_grpc_endpoint(None), 
-        config_manager.get_shyft_api_key()?.to_string()
-    ).with_timeout(app_config.shyft.timeout_seconds);
+/// 事件源运行参数
+struct StreamProfile {
+    /// 日志前缀
+    label: &'static str,
+    holding_duration_seconds: u64,
+    default_strategy: StrategyType,
+}
 
-    let stream = ShyftStream::new(config);
-    
-    info!("已连接 Shyft gRPC");
-    if executor_manager.is_some() {
-        info!("交易模式: 自动策略管理, 交易金额: {:.4} SOL", strategy_config.buy_amount_lamports as f64 / 1_000_000_000.0);
-    } else {
-        info!("交易模式: 只读监控");
+impl StreamType {
+    fn profile(&self) -> StreamProfile {
+        match self {
+            StreamType::PumpFun => StreamProfile {
+                label: "Shyft",
+                holding_duration_seconds: 3,
+                default_strategy: StrategyType::Default,
+            },
+            StreamType::Letsbonk => StreamProfile {
+                label: "BONK",
+                holding_duration_seconds: 60,
+                default_strategy: StrategyType::Conservative,
+            },
+            StreamType::All => StreamProfile {
+                label: "ALL",
+                holding_duration_seconds: 3,
+                default_strategy: StrategyType::Default,
+            },
+        }
     }
 
-    // 创建事件处理回调
-    let strategy_manager_clone = strategy_manager.clone();
-    
-    stream.start_streaming(move |event: TokenEvent| {
-        let strategy_manager_for_event = strategy_manager_clone.clone();
-        info!("创建者/交易者钱包地址：{}", event.creator_wallet.as_deref().unwrap_or("Unknown"));
-        // 记录关键事件
-        match event.transaction_type {
-            TransactionType::TokenCreation => {
-                info!("[Shyft] 新代币创建: {}", event.mint.as_deref().unwrap_or("Unknown"));
-            }
-            TransactionType::Buy => {
-                if let Some(sol_amount) = event.sol_amount {
-                    info!("[Shyft] 买入 {:.4} SOL: {}", sol_amount as f64 / 1_000_000_000.0, event.mint.as_deref().unwrap_or("Unknown"));
-                }
-            }
-            TransactionType::Sell => {
-                if let Some(sol_amount) = event.sol_amount {
-                    info!("[Shyft] 卖出 {:.4} SOL: {}", sol_amount as f64 / 1_000_000_000.0, event.mint.as_deref().unwrap_or("Unknown"));
-                }
-            }
-            _ => {}
+    /// 竞速模式下每个端点使用的过滤器
+    fn filters(&self) -> Vec<TransactionFilter> {
+        match self {
+            StreamType::PumpFun => vec![pumpfun_filter()],
+            StreamType::Letsbonk => vec![letsbonk_filter()],
+            StreamType::All => vec![pumpfun_filter(), letsbonk_filter()],
         }
-                
-        // 所有事件类型都传递给策略管理器处理
-        
-        tokio::spawn(async move {
-            let _start_time = std::time::Instant::now();
-            
-            // 传递给优化版策略管理器处理
-            if let Err(e) = strategy_manager_for_event.handle_token_event(&event).await {
-                error!("处理代币时出错: {}", e);
-            }
-            
-        });
-        
-        Ok(())
-    }).await?;
+    }
+}
 
-    Ok(())
+/// 配置了录制时为事件源挂上录制器
+fn with_recorder<S>(
+    stream: S,
+    recorder: &Option<Arc<UpdateRecorder>>,
+    attach: fn(S, Arc<UpdateRecorder>) -> S,
+) -> S {
+    match recorder {
+        Some(recorder) => attach(stream, Arc::clone(recorder)),
+        None => stream,
+    }
 }
 
-async fn run_letsbonk_stream(
+/// 运行任意事件源，并把事件交给策略管理器
+async fn run_event_source<S: TokenEventSource>(
+    source: S,
+    profile: StreamProfile,
     args: Args, 
     config_manager: &ConfigManager,
     executor_manager: Option<Arc<OptimizedExecutorManager>>,
     _blockhash_cache: Option<&Arc<BlockhashCache>>,
     shared_compute_budget_manager: Option<Arc<DynamicComputeBudgetManager>>,
 ) -> Result<()> {
-    info!("启动 Letsbonk 监听");
+    info!("启动 {} 监听", source.name());
     
     let app_config = &config_manager.app_config;
     
@@ -578,13 +615,13 @@ async fn run_letsbonk_stream(
     let strategy_config = solana_spining::StrategyConfig {
         buy_amount_lamports: app_config.strategy.trading.buy_amount_lamports,
         max_slippage_bps: app_config.general.default_slippage_bps,
-        holding_duration_seconds: 60,
+        holding_duration_seconds: profile.holding_duration_seconds,
         stop_loss_percentage: Some(app_config.strategy.trading.stop_loss_percent),
         take_profit_percentage: Some(app_config.strategy.trading.take_profit_percent),
         enable_emergency_sell: true,
     };
     
-    let strategy_type = args.strategy.unwrap_or(StrategyType::Conservative);
+    let strategy_type = args.strategy.unwrap_or(profile.default_strategy);
     let optimized_filter = create_optimized_token_filter(&strategy_type, app_config)?;
     let strategy_manager = OptimizedStrategyManager::new(
         executor_manager.clone(),
@@ -593,68 +630,99 @@ async fn run_letsbonk_stream(
         optimized_filter,
         compute_budget_manager, // 🆕 传递计算预算管理器
         _blockhash_cache.cloned(), // 传递区块哈希缓存用于区块对齐
+        Some(app_config.pipeline.signal_queue_capacity),
     );
+    strategy_manager.attach_feed_metrics(source.metrics()).await;
+
+    // 持仓价格来自曲线账户订阅；离线重放和 WebSocket 降级模式下没有 gRPC 连接
+    let live_grpc = args.replay.is_none() && args.websocket.is_none();
+    if let (true, Ok(api_key)) = (live_grpc, config_manager.get_shyft_api_key()) {
+        let feed_config = StreamShyftConfig::new(app_config.get_shyft_grpc_endpoint(None), api_key.to_string())
+            .with_timeout(app_config.shyft.timeout_seconds);
+        strategy_manager.attach_price_feed(Arc::new(AccountPriceStream::new(feed_config))).await;
+        info!("📈 持仓价格: 曲线账户实时订阅");
+    }
     
     info!("最大并发策略数: {}", app_config.strategy.trading.max_positions);
-    
-    let config = solana_spining::StreamShyftConfig::new(
-        app_config.get_shyft_grpc_endpoint(None), 
-        config_manager.get_shyft_api_key()?.to_string()
-    ).with_timeout(app_config.shyft.timeout_seconds);
-
-    let stream = LetsbonkStream::new(config);
-    
-    info!("已连接 Shyft gRPC，监听 BONK 代币");
     if executor_manager.is_some() {
         info!("交易模式: 自动策略管理, 交易金额: {:.4} SOL", strategy_config.buy_amount_lamports as f64 / 1_000_000_000.0);
     } else {
         info!("交易模式: 只读监控");
     }
 
-    // 创建优化版事件处理回调
-    let strategy_manager_clone = strategy_manager.clone();
-    
-    stream.start_streaming(move |event: TokenEvent| {
-        let strategy_manager_for_event = strategy_manager_clone.clone();
-        
-        // 记录关键事件
-        match event.transaction_type {
-            TransactionType::TokenCreation => {
-                let has_buy_info = event.sol_amount.is_some() && event.token_amount.is_some() && 
-                    event.detection_method.contains("含买入");
-                
-                if has_buy_info {
-                    info!("[BONK] 新代币+买入: {}", event.mint.as_deref().unwrap_or("Unknown"));
-                } else {
-                    info!("[BONK] 新代币创建: {}", event.mint.as_deref().unwrap_or("Unknown"));
-                }
-            }
-            TransactionType::Buy => {
-                if let Some(sol_amount) = event.sol_amount {
-                    info!("[BONK] 买入 {:.4} SOL: {}", sol_amount as f64 / 1_000_000_000.0, event.mint.as_deref().unwrap_or("Unknown"));
-                }
-            }
-            TransactionType::Sell => {
-                if let Some(sol_amount) = event.sol_amount {
-                    info!("[BONK] 卖出 {:.4} SOL: {}", sol_amount as f64 / 1_000_000_000.0, event.mint.as_deref().unwrap_or("Unknown"));
-                }
-            }
-            _ => {}
-        }
-        
-        // 所有事件类型都传递给策略管理器处理
+    let label = profile.label;
+    let mut events = Box::pin(source.event_stream());
+    // 按 mint 分车道的有界队列，同一 mint 的事件顺序处理，不同 mint 并行
+    let pipeline = EventPipeline::start(&app_config.pipeline, strategy_manager.clone());
+
+    let stats_reporter = app_config.monitoring.metrics_enabled.then(|| {
+        let interval = std::time::Duration::from_secs(app_config.monitoring.metrics_interval_seconds.max(1));
+        let strategy_manager = strategy_manager.clone();
+        let event_lanes = pipeline.lanes();
         tokio::spawn(async move {
-            let _start_time = std::time::Instant::now();
-            
-            // 传递给策略管理器处理
-            if let Err(e) = strategy_manager_for_event.handle_token_event(&event).await {
-                error!("策略管理器处理事件失败: {}", e);
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                info!("📊 事件管道状态:");
+                event_lanes.stats().print();
+                strategy_manager.signal_queue_stats().print();
             }
-            
-        });
-        
-        Ok(())
-    }).await?;
+        })
+    });
+
+    while let Some(event) = events.next().await {
+        log_token_event(label, &event);
 
+        // 所有事件类型都传递给策略管理器处理
+        if pipeline.submit(event).await == PushOutcome::Closed {
+            break;
+        }
+    }
+
+    warn!("{} 事件流已结束", source.name());
+    if let Some(reporter) = stats_reporter {
+        reporter.abort();
+    }
+    pipeline.shutdown().await;
     Ok(())
-}
\ No newline at end of file
+}
+
+/// 记录关键事件
+fn log_token_event(label: &str, event: &TokenEvent) {
+    let mint = event.mint();
+    if event.is_creation() {
+        if event.dev_buy().is_some() {
+            info!("[{}] 新代币+买入: {}", label, mint);
+        } else {
+            info!("[{}] 新代币创建: {}", label, mint);
+        }
+        if let Some(metadata) = event.kind.metadata() {
+            info!("代币名称：{} ({})", metadata.name, metadata.symbol);
+        }
+        if let Some(creator) = event.wallet() {
+            info!("创建者钱包地址：{}", creator);
+        }
+        return;
+    }
+    if event.kind.is_migration() {
+        match event.kind.migration_pool() {
+            Some(pool) => info!("[{}] 曲线迁移: {} -> {}", label, mint, pool),
+            None => info!("[{}] 曲线迁移: {}", label, mint),
+        }
+        return;
+    }
+
+    let Some(sol_amount) = event.sol_amount() else {
+        return;
+    };
+    match event.kind.direction() {
+        Some(TradeDirection::Buy) => {
+            info!("[{}] 买入 {:.4} SOL: {}", label, sol_amount as f64 / 1_000_000_000.0, mint);
+        }
+        Some(TradeDirection::Sell) => {
+            info!("[{}] 卖出 {:.4} SOL: {}", label, sol_amount as f64 / 1_000_000_000.0, mint);
+        }
+        None => {}
+    }
+}
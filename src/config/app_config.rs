@@ -8,6 +8,8 @@ pub struct StreamShyftConfig {
     pub x_token: String,
     pub timeout_seconds: u64,
     pub commitment_level: String,
+    /// 订阅保持打开但超过该时长没有任何消息时强制重连
+    pub stall_timeout_seconds: u64,
 }
 
 impl StreamShyftConfig {
@@ -17,6 +19,7 @@ impl StreamShyftConfig {
             x_token: token,
             timeout_seconds: 10,
             commitment_level: "processed".to_string(),
+            stall_timeout_seconds: DEFAULT_STALL_TIMEOUT_SECONDS,
         }
     }
 
@@ -29,6 +32,101 @@ impl StreamShyftConfig {
         self.commitment_level = commitment;
         self
     }
+
+    pub fn with_stall_timeout(mut self, stall_timeout_seconds: u64) -> Self {
+        self.stall_timeout_seconds = stall_timeout_seconds;
+        self
+    }
+}
+
+/// Jito ShredStream 代理连接配置
+#[derive(Debug, Clone)]
+pub struct StreamShredConfig {
+    pub endpoint: String,
+    pub timeout_seconds: u64,
+    /// 超过该时长没有收到 Entry 时强制重连
+    pub stall_timeout_seconds: u64,
+}
+
+impl StreamShredConfig {
+    pub fn new(endpoint: String) -> Self {
+        Self {
+            endpoint,
+            timeout_seconds: 10,
+            stall_timeout_seconds: DEFAULT_STALL_TIMEOUT_SECONDS,
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout_seconds: u64) -> Self {
+        self.timeout_seconds = timeout_seconds;
+        self
+    }
+
+    pub fn with_stall_timeout(mut self, stall_timeout_seconds: u64) -> Self {
+        self.stall_timeout_seconds = stall_timeout_seconds;
+        self
+    }
+}
+
+/// 标准 Solana RPC WebSocket (PubSub) 事件源配置
+#[derive(Debug, Clone)]
+pub struct StreamPubsubConfig {
+    pub ws_endpoint: String,
+    /// 用于 getTransaction 的 HTTP RPC 地址
+    pub rpc_endpoint: String,
+    pub commitment_level: String,
+    /// 只拉取日志中包含创建/迁移指令的交易，省去每笔买卖的 getTransaction，但策略收不到买卖事件
+    pub creations_only: bool,
+    /// getTransaction 查询不到交易时的重试次数
+    pub fetch_retries: u32,
+    pub stall_timeout_seconds: u64,
+}
+
+impl StreamPubsubConfig {
+    pub fn new(ws_endpoint: String, rpc_endpoint: String) -> Self {
+        Self {
+            ws_endpoint,
+            rpc_endpoint,
+            commitment_level: "confirmed".to_string(),
+            creations_only: false,
+            fetch_retries: 5,
+            stall_timeout_seconds: DEFAULT_STALL_TIMEOUT_SECONDS,
+        }
+    }
+
+    /// 由 WebSocket 地址推导同主机的 HTTP RPC 地址（ws -> http, wss -> https）
+    pub fn from_ws_endpoint(ws_endpoint: String) -> Self {
+        let rpc_endpoint = if let Some(rest) = ws_endpoint.strip_prefix("wss://") {
+            format!("https://{}", rest)
+        } else if let Some(rest) = ws_endpoint.strip_prefix("ws://") {
+            format!("http://{}", rest)
+        } else {
+            ws_endpoint.clone()
+        };
+        Self::new(ws_endpoint, rpc_endpoint)
+    }
+
+    pub fn with_commitment(mut self, commitment: String) -> Self {
+        self.commitment_level = commitment;
+        self
+    }
+
+    pub fn with_creations_only(mut self, creations_only: bool) -> Self {
+        self.creations_only = creations_only;
+        self
+    }
+
+    pub fn with_stall_timeout(mut self, stall_timeout_seconds: u64) -> Self {
+        self.stall_timeout_seconds = stall_timeout_seconds;
+        self
+    }
+}
+
+/// 默认的静默重连阈值（秒）
+pub const DEFAULT_STALL_TIMEOUT_SECONDS: u64 = 30;
+
+fn default_stall_timeout_seconds() -> u64 {
+    DEFAULT_STALL_TIMEOUT_SECONDS
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,6 +143,14 @@ pub struct AppConfig {
     pub logging: LoggingConfig,
     #[serde(default)]
     pub compute_budget: Option<ComputeBudgetConfigFromFile>,
+    #[serde(default)]
+    pub pipeline: PipelineConfig,
+    #[serde(default)]
+    pub idl: IdlConfig,
+    #[serde(default)]
+    pub launchpad: LaunchpadConfig,
+    #[serde(default)]
+    pub pubsub: PubsubConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -81,6 +187,9 @@ pub struct ShyftConfig {
     pub max_priority_fee: u64,
     pub default_commitment: String,
     pub timeout_seconds: u64,
+    /// gRPC 订阅静默超过该秒数时强制重连
+    #[serde(default = "default_stall_timeout_seconds")]
+    pub stall_timeout_seconds: u64,
     pub enabled: bool,
 }
 
@@ -167,6 +276,90 @@ pub struct TradingConfig {
     pub max_positions: u32,
 }
 
+/// 事件流到策略之间的有界队列配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PipelineConfig {
+    /// 事件队列总容量（平均分给各车道），满时丢弃最旧的买卖事件，创建和迁移事件等待空位
+    pub event_queue_capacity: usize,
+    /// 策略发往管理器的交易信号队列容量，满时发送方等待
+    pub signal_queue_capacity: usize,
+    /// 事件车道数：同一 mint 的事件在同一车道内顺序处理，每条车道一个工作任务
+    pub workers: usize,
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        Self {
+            event_queue_capacity: 4096,
+            signal_queue_capacity: 256,
+            workers: 4,
+        }
+    }
+}
+
+/// IDL 注册表配置
+///
+/// 未列出的程序使用编译时内嵌的默认 IDL；列出的条目按程序 id 覆盖或新增。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IdlConfig {
+    /// 为 true 时所有条目都按 `required` 处理
+    pub require_all: bool,
+    pub programs: Vec<IdlProgramConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdlProgramConfig {
+    pub program_id: String,
+    /// IDL 文件路径，相对路径以配置文件所在目录为基准；不填时使用内嵌 IDL
+    #[serde(default)]
+    pub path: Option<String>,
+    /// 期望的 IDL 版本（`metadata.version`），用于在内嵌的多个版本中选择或校验文件
+    #[serde(default)]
+    pub version: Option<String>,
+    /// 无法加载时启动失败，而不是退回内嵌 IDL
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// Raydium Launchpad 平台白名单
+///
+/// 同一个 Launchpad 程序上有多个平台（LetsBonk 等），按池的 `platform_config` 账户区分。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LaunchpadConfig {
+    /// 为 true 时也输出白名单之外平台的事件（平台名为空）
+    pub allow_unknown: bool,
+    pub platforms: Vec<LaunchpadPlatformConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchpadPlatformConfig {
+    pub name: String,
+    pub platform_config: String,
+}
+
+impl Default for LaunchpadConfig {
+    fn default() -> Self {
+        Self {
+            allow_unknown: false,
+            platforms: vec![LaunchpadPlatformConfig {
+                name: "LetsBonk".to_string(),
+                platform_config: "FfYek5vEz23cMkWsdJwG2oa6EphsvXSHrGpdALN4g6W1".to_string(),
+            }],
+        }
+    }
+}
+
+/// RPC WebSocket 降级事件源配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PubsubConfig {
+    /// 只拉取创建/迁移交易：getTransaction 调用大幅减少，但止盈止损等依赖买卖事件的策略不再工作
+    pub creations_only: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MonitoringConfig {
     pub metrics_enabled: bool,
@@ -279,6 +472,34 @@ impl AppConfig {
             ));
         }
 
+        if self.pipeline.event_queue_capacity == 0 || self.pipeline.signal_queue_capacity == 0 {
+            return Err(ExecutionError::Configuration(
+                "Pipeline queue capacities must be greater than 0".to_string()
+            ));
+        }
+
+        if self.pipeline.workers == 0 {
+            return Err(ExecutionError::Configuration(
+                "Pipeline worker count must be greater than 0".to_string()
+            ));
+        }
+
+        for program in &self.idl.programs {
+            if program.program_id.parse::<solana_sdk::pubkey::Pubkey>().is_err() {
+                return Err(ExecutionError::Configuration(
+                    format!("Invalid IDL program id: {}", program.program_id)
+                ));
+            }
+        }
+
+        for platform in &self.launchpad.platforms {
+            if platform.platform_config.parse::<solana_sdk::pubkey::Pubkey>().is_err() {
+                return Err(ExecutionError::Configuration(
+                    format!("Invalid launchpad platform config for {}: {}", platform.name, platform.platform_config)
+                ));
+            }
+        }
+
         if self.zeroshot.tip_accounts.accounts.is_empty() {
             return Err(ExecutionError::Configuration(
                 "ZeroSlot tip accounts cannot be empty".to_string()
@@ -328,6 +549,28 @@ impl AppConfig {
         }
     }
 
+    /// 所有配置的 Shyft GRPC 区域端点（区域名, 地址），重复地址只保留一个
+    pub fn get_shyft_grpc_endpoints(&self) -> Vec<(String, String)> {
+        let regions = &self.shyft.grpc_regions;
+        let mut endpoints: Vec<(String, String)> = Vec::new();
+
+        for (region, endpoint) in [
+            ("ny", &regions.ny),
+            ("va", &regions.va),
+            ("us", &regions.us),
+            ("eu", &regions.eu),
+            ("ams", &regions.ams),
+            ("fra", &regions.fra),
+        ] {
+            if endpoint.is_empty() || endpoints.iter().any(|(_, existing)| existing == endpoint) {
+                continue;
+            }
+            endpoints.push((region.to_string(), endpoint.clone()));
+        }
+
+        endpoints
+    }
+
     pub fn get_zeroshot_endpoint(&self, region: Option<&str>) -> String {
         let region = region.unwrap_or(&self.regions.zeroshot);
         
@@ -397,6 +640,7 @@ impl Default for AppConfig {
                 max_priority_fee: 10000000,
                 default_commitment: "processed".to_string(),
                 timeout_seconds: 30,
+                stall_timeout_seconds: DEFAULT_STALL_TIMEOUT_SECONDS,
                 enabled: true,
             },
             zeroshot: ZeroShotConfig {
@@ -467,6 +711,10 @@ impl Default for AppConfig {
                 max_files: 10,
             },
             compute_budget: None,
+            pipeline: PipelineConfig::default(),
+            idl: IdlConfig::default(),
+            launchpad: LaunchpadConfig::default(),
+            pubsub: PubsubConfig::default(),
         }
     }
 }
\ No newline at end of file
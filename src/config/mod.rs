@@ -3,8 +3,8 @@ pub mod config_manager;
 
 pub use app_config::{
     AppConfig, GeneralConfig, RegionsConfig, BlockhashCacheConfig, 
-    ShyftConfig, ShyftRpcRegions, ShyftGrpcRegions, StreamShyftConfig,
+    ShyftConfig, ShyftRpcRegions, ShyftGrpcRegions, StreamShyftConfig, StreamShredConfig, StreamPubsubConfig,
     ZeroShotConfig, PumpFunConfig,
-    RaydiumConfig, StrategyConfig, MonitoringConfig, LoggingConfig, TipAccounts
+    RaydiumConfig, StrategyConfig, PipelineConfig, IdlConfig, IdlProgramConfig, LaunchpadConfig, LaunchpadPlatformConfig, PubsubConfig, MonitoringConfig, LoggingConfig, TipAccounts
 };
 pub use config_manager::ConfigManager;
\ No newline at end of file
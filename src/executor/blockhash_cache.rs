@@ -1,22 +1,119 @@
+use std::collections::HashMap;
+use std::str::FromStr;
 use std::sync::{Arc, RwLock, atomic::{AtomicBool, Ordering}};
 use std::time::{Duration, Instant};
+use backoff::future::retry;
+use backoff::ExponentialBackoff;
+use futures_util::{SinkExt, StreamExt};
+use solana_sdk::clock::MAX_PROCESSING_AGE;
+use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::hash::Hash;
 use solana_client::rpc_client::RpcClient;
+use solana_client::nonblocking::rpc_client::RpcClient as NonblockingRpcClient;
 use tokio::time::sleep;
 use log::{info, warn, error, debug};
+use yellowstone_grpc_proto::geyser::{
+    SubscribeRequest, SubscribeRequestFilterBlocksMeta, SubscribeRequestFilterSlots, SubscribeRequestPing,
+    SubscribeUpdateBlockMeta,
+};
+use yellowstone_grpc_proto::prelude::{subscribe_update::UpdateOneof, CommitmentLevel};
 
+use crate::config::{BlockhashCacheConfig, StreamShyftConfig};
 use crate::executor::errors::ExecutionError;
+use crate::streams::geyser::connect_client;
+
+/// 默认的 RPC 轮询间隔
+const DEFAULT_UPDATE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 默认的缓存过期时长
+const DEFAULT_MAX_AGE: Duration = Duration::from_secs(10);
+
+/// gRPC 超过该时长没有更新时启用 RPC 轮询
+const GRPC_FALLBACK_AFTER: Duration = Duration::from_secs(2);
+
+/// 缓存的区块哈希
+#[derive(Debug, Clone, Copy)]
+struct CachedBlockhash {
+    blockhash: Hash,
+    /// 该哈希可被交易引用的最后区块高度
+    last_valid_block_height: u64,
+    updated_at: Instant,
+}
+
+/// 缓存内容：区块哈希与当前slot分别更新
+#[derive(Debug, Default)]
+struct CacheState {
+    blockhash: Option<CachedBlockhash>,
+    /// 观察到的最高slot及更新时间
+    slot: Option<(u64, Instant)>,
+    /// 最近一次收到 gRPC 更新的时间
+    grpc_updated_at: Option<Instant>,
+}
+
+impl CacheState {
+    /// 处理 gRPC slot 更新，只接受更高的slot
+    fn apply_slot(&mut self, slot: u64, now: Instant) {
+        if self.slot.is_none_or(|(current, _)| slot >= current) {
+            self.slot = Some((slot, now));
+        }
+        self.grpc_updated_at = Some(now);
+    }
+
+    /// 处理 gRPC 区块元数据：哈希的有效期为区块高度之后的 `MAX_PROCESSING_AGE` 个区块
+    fn apply_block_meta(&mut self, meta: &SubscribeUpdateBlockMeta, now: Instant) -> Result<(), ExecutionError> {
+        let blockhash = Hash::from_str(&meta.blockhash)
+            .map_err(|e| ExecutionError::Serialization(format!("Invalid blockhash {}: {}", meta.blockhash, e)))?;
+        let block_height = meta.block_height
+            .as_ref()
+            .map(|height| height.block_height)
+            .ok_or_else(|| ExecutionError::Serialization(format!("Block meta for slot {} has no block height", meta.slot)))?;
+
+        self.set_blockhash(blockhash, block_height + MAX_PROCESSING_AGE as u64, now);
+        self.apply_slot(meta.slot, now);
+        Ok(())
+    }
+
+    /// 处理 RPC 轮询结果
+    fn apply_rpc(&mut self, blockhash: Hash, last_valid_block_height: u64, slot: u64, now: Instant) {
+        self.set_blockhash(blockhash, last_valid_block_height, now);
+        if self.slot.is_none_or(|(current, _)| slot >= current) {
+            self.slot = Some((slot, now));
+        }
+    }
+
+    fn set_blockhash(&mut self, blockhash: Hash, last_valid_block_height: u64, now: Instant) {
+        // 乱序到达的旧区块不覆盖新哈希
+        if self.blockhash.is_some_and(|cached| cached.last_valid_block_height > last_valid_block_height) {
+            return;
+        }
+        self.blockhash = Some(CachedBlockhash { blockhash, last_valid_block_height, updated_at: now });
+    }
+
+    /// gRPC 更新中断时需要 RPC 轮询
+    fn needs_rpc_poll(&self, now: Instant) -> bool {
+        self.grpc_updated_at.is_none_or(|at| now.saturating_duration_since(at) > GRPC_FALLBACK_AFTER)
+    }
+}
 
 /// 区块哈希缓存，用于后台获取最新区块哈希和slot高度
+///
+/// 配置了 gRPC 时由 slot + blocks_meta 订阅实时更新；gRPC 未配置或中断时
+/// 按 `update_interval` 轮询 RPC。slot 查询直接读取内存。
 pub struct BlockhashCache {
-    /// 缓存的区块哈希、slot高度和获取时间
-    cached_data: Arc<RwLock<Option<(Hash, u64, Instant)>>>,
-    /// RPC客户端
+    /// 缓存的区块哈希和slot
+    state: Arc<RwLock<CacheState>>,
+    /// RPC客户端（同步回退查询使用）
     rpc_client: RpcClient,
+    /// 区块哈希/slot 的 gRPC 订阅配置
+    grpc_config: Option<StreamShyftConfig>,
+    /// RPC 轮询间隔
+    update_interval: Duration,
+    /// 超过该时长的缓存视为过期
+    max_age: Duration,
     /// 运行状态标志
     running: Arc<AtomicBool>,
     /// 后台任务句柄
-    task_handle: Option<tokio::task::JoinHandle<()>>,
+    task_handles: Vec<tokio::task::JoinHandle<()>>,
 }
 
 impl BlockhashCache {
@@ -24,15 +121,31 @@ impl BlockhashCache {
     /// rpc_endpoint_with_auth: 包含认证信息的完整RPC端点URL
     pub fn new(rpc_endpoint_with_auth: String) -> Self {
         let rpc_client = RpcClient::new(rpc_endpoint_with_auth);
-        
+
         Self {
-            cached_data: Arc::new(RwLock::new(None)),
+            state: Arc::new(RwLock::new(CacheState::default())),
             rpc_client,
+            grpc_config: None,
+            update_interval: DEFAULT_UPDATE_INTERVAL,
+            max_age: DEFAULT_MAX_AGE,
             running: Arc::new(AtomicBool::new(false)),
-            task_handle: None,
+            task_handles: Vec::new(),
         }
     }
 
+    /// 应用 `[blockhash_cache]` 配置
+    pub fn with_config(mut self, config: &BlockhashCacheConfig) -> Self {
+        self.update_interval = Duration::from_millis(config.update_interval_ms);
+        self.max_age = Duration::from_secs(config.max_age_seconds);
+        self
+    }
+
+    /// 使用 gRPC slot + blocks_meta 订阅作为主要数据源
+    pub fn with_grpc(mut self, config: StreamShyftConfig) -> Self {
+        self.grpc_config = Some(config);
+        self
+    }
+
     /// 启动后台更新任务
     pub fn start(&mut self) -> Result<(), ExecutionError> {
         if self.running.load(Ordering::Relaxed) {
@@ -41,73 +154,158 @@ impl BlockhashCache {
         }
 
         self.running.store(true, Ordering::Relaxed);
-        
-        let cached_data = Arc::clone(&self.cached_data);
+
+        if let Some(grpc_config) = self.grpc_config.clone() {
+            let state = Arc::clone(&self.state);
+            self.task_handles.push(tokio::spawn(async move {
+                if let Err(e) = Self::run_grpc(grpc_config, state).await {
+                    error!("❌ BlockhashCache gRPC subscription stopped: {}", e);
+                }
+            }));
+        }
+
+        let state = Arc::clone(&self.state);
         let rpc_endpoint = self.rpc_client.url();
         let running = Arc::clone(&self.running);
+        let update_interval = self.update_interval;
 
-        let handle = tokio::spawn(async move {
+        self.task_handles.push(tokio::spawn(async move {
             info!("🚀 BlockhashCache background task started");
-            
-            // 在异步任务中创建RPC客户端
-            let rpc_client = RpcClient::new(rpc_endpoint);
-            
+
+            // 在异步任务中创建非阻塞RPC客户端
+            let rpc_client = NonblockingRpcClient::new(rpc_endpoint);
+
             while running.load(Ordering::Relaxed) {
-                match Self::fetch_latest_data(&rpc_client).await {
-                    Ok((blockhash, slot)) => {
-                        let now = Instant::now();
-                        
-                        // 更新缓存
-                        if let Ok(mut cache) = cached_data.write() {
-                            *cache = Some((blockhash, slot, now));
-                            debug!("✅ Updated cached data: blockhash={}, slot={}", blockhash, slot);
-                        } else {
-                            warn!("⚠️ Failed to acquire write lock for cache");
+                let needs_poll = state.read().map(|state| state.needs_rpc_poll(Instant::now())).unwrap_or(true);
+                if needs_poll {
+                    match Self::fetch_latest_data(&rpc_client).await {
+                        Ok((blockhash, last_valid_block_height, slot)) => {
+                            if let Ok(mut state) = state.write() {
+                                state.apply_rpc(blockhash, last_valid_block_height, slot, Instant::now());
+                                debug!("✅ Updated cached data from RPC: blockhash={}, slot={}", blockhash, slot);
+                            } else {
+                                warn!("⚠️ Failed to acquire write lock for cache");
+                            }
+                        }
+                        Err(e) => {
+                            error!("❌ Failed to fetch latest blockhash and slot: {}", e);
+                            // 获取失败时不更新缓存，继续使用旧的（如果有的话）
                         }
-                    }   
-                    Err(e) => {
-                        error!("❌ Failed to fetch latest blockhash and slot: {}", e);
-                        // 获取失败时不更新缓存，继续使用旧的（如果有的话）
                     }
                 }
 
-                // 等待100ms后再次尝试
-                sleep(Duration::from_millis(100)).await;
+                sleep(update_interval).await;
             }
-            
+
             info!("🛑 BlockhashCache background task stopped");
-        });
+        }));
 
-        self.task_handle = Some(handle);
         Ok(())
     }
 
     /// 停止后台更新任务
+    ///
+    /// 订阅和进行中的RPC请求可能长时间挂起，直接取消任务而不是等待其退出。
     pub async fn stop(&mut self) {
         self.running.store(false, Ordering::Relaxed);
-        
-        if let Some(handle) = self.task_handle.take() {
-            if let Err(e) = handle.await {
-                error!("Failed to stop blockhash cache task: {}", e);
+
+        for handle in self.task_handles.drain(..) {
+            handle.abort();
+            match handle.await {
+                Err(e) if !e.is_cancelled() => error!("Failed to stop blockhash cache task: {}", e),
+                _ => {}
             }
         }
-        
+
         info!("BlockhashCache stopped");
     }
 
+    /// gRPC slot + blocks_meta 订阅，断线后指数退避重连
+    async fn run_grpc(config: StreamShyftConfig, state: Arc<RwLock<CacheState>>) -> anyhow::Result<()> {
+        // 缓存需要与进程同生命周期，不限制总重试时长
+        let backoff = ExponentialBackoff { max_elapsed_time: None, ..Default::default() };
+
+        retry(backoff, || {
+            let config = &config;
+            let state = &state;
+            async move {
+                let mut client = connect_client(config).await.map_err(backoff::Error::transient)?;
+                let (mut subscribe_tx, mut stream) = client
+                    .subscribe_with_request(Some(Self::build_subscribe_request()))
+                    .await
+                    .map_err(|e| backoff::Error::transient(e.into()))?;
+                info!("✅ BlockhashCache gRPC subscription opened");
+
+                while let Some(message) = stream.next().await {
+                    let msg = message.map_err(|e| backoff::Error::transient(anyhow::anyhow!("Stream error: {:?}", e)))?;
+                    let now = Instant::now();
+
+                    match msg.update_oneof {
+                        Some(UpdateOneof::Slot(slot_update)) => {
+                            if let Ok(mut state) = state.write() {
+                                state.apply_slot(slot_update.slot, now);
+                            }
+                        }
+                        Some(UpdateOneof::BlockMeta(meta)) => {
+                            if let Ok(mut state) = state.write() {
+                                if let Err(e) = state.apply_block_meta(&meta, now) {
+                                    warn!("⚠️ Ignoring block meta: {}", e);
+                                }
+                            }
+                        }
+                        Some(UpdateOneof::Ping(_)) => {
+                            let pong = SubscribeRequest {
+                                ping: Some(SubscribeRequestPing { id: 1 }),
+                                ..Default::default()
+                            };
+                            if let Err(e) = subscribe_tx.send(pong).await {
+                                error!("Failed to send pong: {}", e);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+
+                Err(backoff::Error::transient(anyhow::anyhow!("BlockhashCache gRPC stream closed")))
+            }
+        })
+        .await
+    }
+
+    /// 所有状态的 slot 更新（用于当前slot）+ confirmed 区块元数据（用于区块哈希）
+    fn build_subscribe_request() -> SubscribeRequest {
+        SubscribeRequest {
+            slots: HashMap::from([(
+                "slots".to_string(),
+                SubscribeRequestFilterSlots {
+                    filter_by_commitment: Some(false),
+                    ..Default::default()
+                },
+            )]),
+            blocks_meta: HashMap::from([("blocks_meta".to_string(), SubscribeRequestFilterBlocksMeta {})]),
+            commitment: Some(CommitmentLevel::Confirmed as i32),
+            ..Default::default()
+        }
+    }
+
     /// 获取缓存的区块哈希
     pub fn get_cached_blockhash(&self) -> Result<Hash, ExecutionError> {
-        let cache = self.cached_data.read()
+        self.get_cached_blockhash_with_height().map(|(blockhash, _)| blockhash)
+    }
+
+    /// 获取缓存的区块哈希及其最后有效区块高度
+    pub fn get_cached_blockhash_with_height(&self) -> Result<(Hash, u64), ExecutionError> {
+        let state = self.state.read()
             .map_err(|_| ExecutionError::Configuration("Failed to read cache".to_string()))?;
 
-        match cache.as_ref() {
-            Some((blockhash, _slot, timestamp)) => {
-                // 检查缓存是否过期（超过10秒）
-                if timestamp.elapsed().as_secs() > 10 {
-                    warn!("⚠️ Cached blockhash is stale ({:.1}s old), but using it anyway", 
-                          timestamp.elapsed().as_secs_f64());
+        match state.blockhash {
+            Some(cached) => {
+                // 检查缓存是否过期
+                if cached.updated_at.elapsed() > self.max_age {
+                    warn!("⚠️ Cached blockhash is stale ({:.1}s old), but using it anyway",
+                          cached.updated_at.elapsed().as_secs_f64());
                 }
-                Ok(*blockhash)
+                Ok((cached.blockhash, cached.last_valid_block_height))
             }
             None => {
                 // 如果缓存为空，尝试同步获取一次
@@ -122,27 +320,29 @@ impl BlockhashCache {
 
     /// 强制同步获取最新区块哈希（备用方案）
     pub async fn get_fresh_blockhash(&self) -> Result<Hash, ExecutionError> {
-        let (blockhash, _slot) = Self::fetch_latest_data(&self.rpc_client).await?;
-        Ok(blockhash)
+        self.rpc_client.get_latest_blockhash()
+            .map_err(|e| ExecutionError::Network(format!("Failed to fetch latest blockhash: {}", e)))
     }
 
     /// 获取缓存状态信息
     pub fn get_cache_info(&self) -> Result<CacheInfo, ExecutionError> {
-        let cache = self.cached_data.read()
+        let state = self.state.read()
             .map_err(|_| ExecutionError::Configuration("Failed to read cache".to_string()))?;
 
-        let info = match cache.as_ref() {
-            Some((blockhash, slot, timestamp)) => CacheInfo {
+        let info = match state.blockhash {
+            Some(cached) => CacheInfo {
                 has_cache: true,
-                blockhash: Some(*blockhash),
-                slot: Some(*slot),
-                age_seconds: timestamp.elapsed().as_secs_f64(),
-                is_stale: timestamp.elapsed().as_secs() > 10,
+                blockhash: Some(cached.blockhash),
+                last_valid_block_height: Some(cached.last_valid_block_height),
+                slot: state.slot.map(|(slot, _)| slot),
+                age_seconds: cached.updated_at.elapsed().as_secs_f64(),
+                is_stale: cached.updated_at.elapsed() > self.max_age,
             },
             None => CacheInfo {
                 has_cache: false,
                 blockhash: None,
-                slot: None,
+                last_valid_block_height: None,
+                slot: state.slot.map(|(slot, _)| slot),
                 age_seconds: 0.0,
                 is_stale: true,
             },
@@ -151,20 +351,20 @@ impl BlockhashCache {
         Ok(info)
     }
 
-    /// 内部方法：获取最新区块哈希和slot
-    async fn fetch_latest_data(rpc_client: &RpcClient) -> Result<(Hash, u64), ExecutionError> {
+    /// 内部方法：获取最新区块哈希、最后有效区块高度和slot
+    async fn fetch_latest_data(rpc_client: &NonblockingRpcClient) -> Result<(Hash, u64, u64), ExecutionError> {
         // 并发获取blockhash和slot以提高效率
         let (blockhash_result, slot_result) = tokio::join!(
-            async { rpc_client.get_latest_blockhash() },
-            async { rpc_client.get_slot() }
+            rpc_client.get_latest_blockhash_with_commitment(CommitmentConfig::confirmed()),
+            rpc_client.get_slot_with_commitment(CommitmentConfig::processed())
         );
 
-        let blockhash = blockhash_result
+        let (blockhash, last_valid_block_height) = blockhash_result
             .map_err(|e| ExecutionError::Network(format!("Failed to fetch latest blockhash: {}", e)))?;
         let slot = slot_result
             .map_err(|e| ExecutionError::Network(format!("Failed to fetch current slot: {}", e)))?;
 
-        Ok((blockhash, slot))
+        Ok((blockhash, last_valid_block_height, slot))
     }
 
     /// 检查是否正在运行
@@ -175,11 +375,11 @@ impl BlockhashCache {
     /// 获取当前slot高度（优先使用缓存）
     pub async fn get_current_slot(&self) -> Result<u64, ExecutionError> {
         // 优先从缓存获取
-        if let Ok(cache) = self.cached_data.read() {
-            if let Some((_blockhash, slot, timestamp)) = cache.as_ref() {
-                // 如果缓存未过期（小于10秒），直接返回缓存的slot
-                if timestamp.elapsed().as_secs() <= 10 {
-                    return Ok(*slot);
+        if let Ok(state) = self.state.read() {
+            if let Some((slot, updated_at)) = state.slot {
+                // 如果缓存未过期，直接返回缓存的slot
+                if updated_at.elapsed() <= self.max_age {
+                    return Ok(slot);
                 }
             }
         }
@@ -192,17 +392,17 @@ impl BlockhashCache {
 
     /// 获取缓存的slot高度（不进行实时查询）
     pub fn get_cached_slot(&self) -> Result<u64, ExecutionError> {
-        let cache = self.cached_data.read()
+        let state = self.state.read()
             .map_err(|_| ExecutionError::Configuration("Failed to read cache".to_string()))?;
 
-        match cache.as_ref() {
-            Some((_blockhash, slot, timestamp)) => {
-                // 检查缓存是否过期（超过10秒）
-                if timestamp.elapsed().as_secs() > 10 {
-                    warn!("⚠️ Cached slot is stale ({:.1}s old), but using it anyway", 
-                          timestamp.elapsed().as_secs_f64());
+        match state.slot {
+            Some((slot, updated_at)) => {
+                // 检查缓存是否过期
+                if updated_at.elapsed() > self.max_age {
+                    warn!("⚠️ Cached slot is stale ({:.1}s old), but using it anyway",
+                          updated_at.elapsed().as_secs_f64());
                 }
-                Ok(*slot)
+                Ok(slot)
             }
             None => {
                 Err(ExecutionError::ServiceUnavailable {
@@ -217,6 +417,9 @@ impl BlockhashCache {
 impl Drop for BlockhashCache {
     fn drop(&mut self) {
         self.running.store(false, Ordering::Relaxed);
+        for handle in &self.task_handles {
+            handle.abort();
+        }
     }
 }
 
@@ -225,6 +428,7 @@ impl Drop for BlockhashCache {
 pub struct CacheInfo {
     pub has_cache: bool,
     pub blockhash: Option<Hash>,
+    pub last_valid_block_height: Option<u64>,
     pub slot: Option<u64>,
     pub age_seconds: f64,
     pub is_stale: bool,
@@ -233,12 +437,13 @@ pub struct CacheInfo {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use yellowstone_grpc_proto::solana::storage::confirmed_block::BlockHeight;
 
     #[tokio::test]
     async fn test_cache_creation() {
         let cache = BlockhashCache::new("https://api.mainnet-beta.solana.com".to_string());
         assert!(!cache.is_running());
-        
+
         let info = cache.get_cache_info().unwrap();
         assert!(!info.has_cache);
         assert!(info.is_stale);
@@ -247,16 +452,46 @@ mod tests {
     #[tokio::test]
     async fn test_cache_start_stop() {
         let mut cache = BlockhashCache::new("https://api.mainnet-beta.solana.com".to_string());
-        
+
         // 启动
         cache.start().unwrap();
         assert!(cache.is_running());
-        
+
         // 等待一小段时间让后台任务运行
         tokio::time::sleep(Duration::from_millis(200)).await;
-        
+
         // 停止
         cache.stop().await;
         assert!(!cache.is_running());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_grpc_updates_drive_cache_and_suppress_rpc_poll() {
+        let mut state = CacheState::default();
+        let start = Instant::now();
+        assert!(state.needs_rpc_poll(start));
+
+        let newer = Hash::new_unique();
+        let meta = SubscribeUpdateBlockMeta {
+            slot: 1_000,
+            blockhash: newer.to_string(),
+            block_height: Some(BlockHeight { block_height: 900 }),
+            ..Default::default()
+        };
+        state.apply_block_meta(&meta, start).unwrap();
+        state.apply_slot(1_002, start);
+        // 较旧的slot和区块不覆盖缓存
+        state.apply_slot(1_001, start);
+        state.set_blockhash(Hash::new_unique(), 1_000, start);
+
+        let cached = state.blockhash.unwrap();
+        assert_eq!(cached.blockhash, newer);
+        assert_eq!(cached.last_valid_block_height, 900 + MAX_PROCESSING_AGE as u64);
+        assert_eq!(state.slot.map(|(slot, _)| slot), Some(1_002));
+        assert!(!state.needs_rpc_poll(start + Duration::from_secs(1)));
+        assert!(state.needs_rpc_poll(start + Duration::from_secs(3)));
+
+        let missing_height = SubscribeUpdateBlockMeta { blockhash: newer.to_string(), ..Default::default() };
+        assert!(state.apply_block_meta(&missing_height, start).is_err());
+    }
+}
@@ -422,10 +422,10 @@ impl OptimizedExecutorManager {
 
     /// 🆕 获取用户的代币账户地址（基于种子派生，与交易构建使用相同逻辑）
     /// 这个方法确保余额查询使用与买入交易完全相同的账户地址
-    pub async fn get_user_token_account_for_mint(&self, mint: &Pubkey, user: &Pubkey) -> Result<Pubkey, crate::executor::errors::ExecutionError> {
+    pub async fn get_user_token_account_for_mint(&self, mint: &Pubkey, user: &Pubkey, token_program: &Pubkey) -> Result<Pubkey, crate::executor::errors::ExecutionError> {
         // 创建临时的TransactionBuilder来访问账户派生方法
         let transaction_builder = crate::executor::transaction_builder::TransactionBuilder::new();
-        transaction_builder.get_user_token_account_address(mint, user)
+        transaction_builder.get_user_token_account_address(mint, user, token_program)
     }
 }
 
@@ -65,6 +65,8 @@ pub struct TradeParams {
     pub is_buy: bool,
     /// 🔧 新增：代币创建者地址 (PumpFun 必需)
     pub creator: Option<Pubkey>,
+    /// 代币 mint 所属的代币程序，None 时按 SPL Token 处理
+    pub token_program: Option<Pubkey>,
 }
 
 /// 统一的交易执行器trait
@@ -94,6 +96,7 @@ pub trait TransactionBuilder {
         sol_amount: u64,
         min_tokens_out: u64,
         creator: &Pubkey,
+        token_program: &Pubkey,
     ) -> Result<solana_sdk::instruction::Instruction, ExecutionError>;
 
     /// 构建PumpFun卖出交易 (带 creator 参数)
@@ -104,6 +107,7 @@ pub trait TransactionBuilder {
         token_amount: u64,
         min_sol_out: u64,
         creator: &Pubkey,
+        token_program: &Pubkey,
     ) -> Result<solana_sdk::instruction::Instruction, ExecutionError>;
 
     /// 构建优先费用指令
@@ -7,11 +7,13 @@ use solana_sdk::{
     compute_budget::ComputeBudgetInstruction,
     signature::{Keypair, Signer},
 };
-use spl_associated_token_account::get_associated_token_address;
-use spl_token::instruction::close_account;
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id, instruction::create_associated_token_account_idempotent,
+};
+use spl_token_2022::instruction::close_account;
 use std::str::FromStr;
 use log::info;
-use crate::constant::accounts::{PUMPFUN, SYSTEM_PROGRAM, TOKEN_PROGRAM};
+use crate::constant::accounts::{PUMPFUN, SYSTEM_PROGRAM, TOKEN_2022_PROGRAM, TOKEN_PROGRAM};
 use crate::constant::seeds::{GLOBAL_SEED, BONDING_CURVE_SEED, EVENT_AUTHORITY_SEED, CREATOR_VAULT_SEED};
 use crate::executor::{
     errors::ExecutionError, 
@@ -108,6 +110,7 @@ impl TransactionBuilder {
         token_amount: u64,
         min_sol_out: u64,
         creator: &Pubkey,
+        token_program: &Pubkey,
         tip_instruction: solana_sdk::instruction::Instruction,
         recent_blockhash: Hash,
     ) -> Result<VersionedTransaction, ExecutionError> {
@@ -117,7 +120,7 @@ impl TransactionBuilder {
         instructions.extend(self.build_sell_compute_budget_instructions());
         
         // 2. 添加 PumpFun 卖出指令
-        let pumpfun_instruction = self.build_pumpfun_sell_with_creator(mint, &seller.pubkey(), token_amount, min_sol_out, creator)?;
+        let pumpfun_instruction = self.build_pumpfun_sell_with_creator(mint, &seller.pubkey(), token_amount, min_sol_out, creator, token_program)?;
         instructions.push(pumpfun_instruction);
         
         // 3. 添加 tip 指令 (在流程最后执行)
@@ -135,6 +138,7 @@ impl TransactionBuilder {
         token_amount: u64,
         min_sol_out: u64,
         creator: &Pubkey,
+        token_program: &Pubkey,
         tip_instruction: solana_sdk::instruction::Instruction,
         recent_blockhash: Hash,
         should_close_ata: bool,
@@ -145,14 +149,14 @@ impl TransactionBuilder {
         instructions.extend(self.build_sell_compute_budget_instructions());
         
         // 2. 添加 PumpFun 卖出指令
-        let pumpfun_instruction = self.build_pumpfun_sell_with_creator(mint, &seller.pubkey(), token_amount, min_sol_out, creator)?;
+        let pumpfun_instruction = self.build_pumpfun_sell_with_creator(mint, &seller.pubkey(), token_amount, min_sol_out, creator, token_program)?;
         instructions.push(pumpfun_instruction);
         
         // 3. 如果需要，添加 ATA 关闭指令
         if should_close_ata {
-            let ata = get_associated_token_address(&seller.pubkey(), mint);
+            let ata = get_associated_token_address_with_program_id(&seller.pubkey(), mint, token_program);
             let close_instruction = close_account(
-                &TOKEN_PROGRAM,
+                token_program,
                 &ata,
                 &seller.pubkey(),
                 &seller.pubkey(),
@@ -169,6 +173,8 @@ impl TransactionBuilder {
     }
 
     /// 构建带 tip 的完整 PumpFun 买入交易 (基于种子的账户创建方式)
+    ///
+    /// Token-2022 代币账户的大小取决于 mint 启用的扩展，改用 ATA 程序幂等创建。
     pub fn build_complete_pumpfun_buy_transaction_with_tip_and_manual_account(
         &self,
         mint: &Pubkey,
@@ -176,6 +182,7 @@ impl TransactionBuilder {
         sol_amount: u64,
         min_tokens_out: u64,
         creator: &Pubkey,
+        token_program: &Pubkey,
         tip_instruction: solana_sdk::instruction::Instruction,
         recent_blockhash: Hash,
     ) -> Result<VersionedTransaction, ExecutionError> {
@@ -184,33 +191,43 @@ impl TransactionBuilder {
         // 1. 添加计算预算指令 (必须在最前面)
         instructions.extend(self.build_compute_budget_instructions());
         
-        // 2. 使用基于种子的账户创建方式 (模拟成功交易)
-        let seed = self.generate_token_account_seed(mint, &buyer.pubkey())?;
-        let token_account = self.derive_token_account_with_seed(&buyer.pubkey(), &seed)?;
-        
-        // 2.1 创建带种子的账户
-        let create_account_instruction = solana_sdk::system_instruction::create_account_with_seed(
-            &buyer.pubkey(),        // 付款人
-            &token_account,         // 新账户地址
-            &buyer.pubkey(),        // 基地址
-            &seed,                  // 种子
-            2039280,                // rent-exempt lamports (固定值，基于成功交易)
-            165,                    // 空间大小 (token账户标准大小)
-            &TOKEN_PROGRAM,         // 所有者程序
-        );
-        instructions.push(create_account_instruction);
-        
-        // 2.2 初始化Token账户
-        let init_account_instruction = spl_token::instruction::initialize_account3(
-            &TOKEN_PROGRAM,
-            &token_account,
-            mint,
-            &buyer.pubkey(),
-        ).map_err(|e| ExecutionError::Internal(format!("Failed to create initialize_account3 instruction: {}", e)))?;
-        instructions.push(init_account_instruction);
+        if *token_program == TOKEN_2022_PROGRAM {
+            // 2. 幂等创建 Token-2022 ATA
+            instructions.push(create_associated_token_account_idempotent(
+                &buyer.pubkey(),
+                &buyer.pubkey(),
+                mint,
+                token_program,
+            ));
+        } else {
+            // 2. 使用基于种子的账户创建方式 (模拟成功交易)
+            let seed = self.generate_token_account_seed(mint, &buyer.pubkey())?;
+            let token_account = self.derive_token_account_with_seed(&buyer.pubkey(), &seed)?;
+            
+            // 2.1 创建带种子的账户
+            let create_account_instruction = solana_sdk::system_instruction::create_account_with_seed(
+                &buyer.pubkey(),        // 付款人
+                &token_account,         // 新账户地址
+                &buyer.pubkey(),        // 基地址
+                &seed,                  // 种子
+                2039280,                // rent-exempt lamports (固定值，基于成功交易)
+                165,                    // 空间大小 (token账户标准大小)
+                token_program,          // 所有者程序
+            );
+            instructions.push(create_account_instruction);
+            
+            // 2.2 初始化Token账户
+            let init_account_instruction = spl_token_2022::instruction::initialize_account3(
+                token_program,
+                &token_account,
+                mint,
+                &buyer.pubkey(),
+            ).map_err(|e| ExecutionError::Internal(format!("Failed to create initialize_account3 instruction: {}", e)))?;
+            instructions.push(init_account_instruction);
+        }
         
         // 3. 添加 PumpFun 买入指令
-        let pumpfun_instruction = self.build_pumpfun_buy_with_creator(mint, &buyer.pubkey(), sol_amount, min_tokens_out, creator)?;
+        let pumpfun_instruction = self.build_pumpfun_buy_with_creator(mint, &buyer.pubkey(), sol_amount, min_tokens_out, creator, token_program)?;
         instructions.push(pumpfun_instruction);
         
         // 4. 添加 tip 指令 (在流程最后执行)
@@ -249,13 +266,14 @@ impl TransactionBuilder {
         trade: &PumpFunTrade,
         user: &Pubkey,
         creator: Option<&Pubkey>,
+        token_program: &Pubkey,
     ) -> Result<Vec<AccountMeta>, ExecutionError> {
         match trade {
             PumpFunTrade::Buy { mint, .. } => {
-                self.get_pumpfun_buy_accounts(mint, user, creator)
+                self.get_pumpfun_buy_accounts(mint, user, creator, token_program)
             }
             PumpFunTrade::Sell { mint, .. } => {
-                self.get_pumpfun_sell_accounts(mint, user, creator)
+                self.get_pumpfun_sell_accounts(mint, user, creator, token_program)
             }
         }
     }
@@ -266,12 +284,13 @@ impl TransactionBuilder {
         mint: &Pubkey,
         user: &Pubkey,
         creator: Option<&Pubkey>,
+        token_program: &Pubkey,
     ) -> Result<Vec<AccountMeta>, ExecutionError> {
         // 系统程序
         let system_program = SYSTEM_PROGRAM;
         
-        // Token程序
-        let token_program = TOKEN_PROGRAM;
+        // Token程序 (SPL Token 或 Token-2022，取决于 mint)
+        let token_program = *token_program;
         
         // 1. global PDA - 根据 IDL seeds: ["global"]
         let (global, _) = Pubkey::find_program_address(
@@ -289,11 +308,10 @@ impl TransactionBuilder {
         );
         
         // 5. associated_bonding_curve - ATA of bonding_curve for mint
-        let associated_bonding_curve = get_associated_token_address(&bonding_curve, mint);
+        let associated_bonding_curve = get_associated_token_address_with_program_id(&bonding_curve, mint, &token_program);
         
-        // 6. associated_user - 使用基于种子的账户地址而不是ATA
-        let seed = self.generate_token_account_seed(mint, user)?;
-        let associated_user = self.derive_token_account_with_seed(user, &seed)?;
+        // 6. associated_user - 使用基于种子的账户地址而不是ATA (Token-2022 使用ATA)
+        let associated_user = self.get_user_token_account_address(mint, user, &token_program)?;
         
         // 10. creator_vault PDA - 使用传入的真实 creator 地址
         let creator_vault = if let Some(creator_addr) = creator {
@@ -359,12 +377,13 @@ impl TransactionBuilder {
         mint: &Pubkey,
         user: &Pubkey,
         creator: Option<&Pubkey>,
+        token_program: &Pubkey,
     ) -> Result<Vec<AccountMeta>, ExecutionError> {
         // 系统程序
         let system_program = SYSTEM_PROGRAM;
         
-        // Token程序
-        let token_program = TOKEN_PROGRAM;
+        // Token程序 (SPL Token 或 Token-2022，取决于 mint)
+        let token_program = *token_program;
         
         // 1. global PDA
         let (global, _) = Pubkey::find_program_address(
@@ -382,15 +401,11 @@ impl TransactionBuilder {
         );
         
         // 5. associated_bonding_curve - ATA of bonding_curve for mint
-        let associated_bonding_curve = get_associated_token_address(&bonding_curve, mint);
+        let associated_bonding_curve = get_associated_token_address_with_program_id(&bonding_curve, mint, &token_program);
         
         // 6. associated_user - 卖出时使用基于种子的账户地址 (如果存在)，否则使用ATA
-        let associated_user = if let Ok(seed) = self.generate_token_account_seed(mint, user) {
-            self.derive_token_account_with_seed(user, &seed)?
-        } else {
-            // 如果种子生成失败，回退到ATA
-            get_associated_token_address(user, mint)
-        };
+        let associated_user = self.get_user_token_account_address(mint, user, &token_program)
+            .unwrap_or_else(|_| get_associated_token_address_with_program_id(user, mint, &token_program));
         
         // 9. creator_vault PDA
         let creator_vault = if let Some(creator_addr) = creator {
@@ -459,6 +474,7 @@ impl TransactionBuilderTrait for TransactionBuilder {
         sol_amount: u64,
         min_tokens_out: u64,
         creator: &Pubkey,
+        token_program: &Pubkey,
     ) -> Result<Instruction, ExecutionError> {
         let trade = PumpFunTrade::Buy {
             mint: *mint,
@@ -467,7 +483,7 @@ impl TransactionBuilderTrait for TransactionBuilder {
         };
 
         let instruction_data = Self::build_pumpfun_instruction_data(&trade);
-        let accounts = self.get_pumpfun_accounts(&trade, buyer, Some(creator))?;
+        let accounts = self.get_pumpfun_accounts(&trade, buyer, Some(creator), token_program)?;
 
         Ok(Instruction {
             program_id: PUMPFUN,
@@ -484,6 +500,7 @@ impl TransactionBuilderTrait for TransactionBuilder {
         token_amount: u64,
         min_sol_out: u64,
         creator: &Pubkey,
+        token_program: &Pubkey,
     ) -> Result<Instruction, ExecutionError> {
         let trade = PumpFunTrade::Sell {
             mint: *mint,
@@ -492,7 +509,7 @@ impl TransactionBuilderTrait for TransactionBuilder {
         };
 
         let instruction_data = Self::build_pumpfun_instruction_data(&trade);
-        let accounts = self.get_pumpfun_accounts(&trade, seller, Some(creator))?;
+        let accounts = self.get_pumpfun_accounts(&trade, seller, Some(creator), token_program)?;
 
         Ok(Instruction {
             program_id: PUMPFUN,
@@ -589,9 +606,12 @@ impl TransactionBuilderTrait for TransactionBuilder {
 }
 
 impl TransactionBuilder {
-    /// 🆕 公开方法：获取用户在特定mint的代币账户地址（基于种子派生）
+    /// 🆕 公开方法：获取用户在特定mint的代币账户地址（基于种子派生，Token-2022 为ATA）
     /// 这个方法确保余额查询和交易构建使用相同的账户地址
-    pub fn get_user_token_account_address(&self, mint: &Pubkey, user: &Pubkey) -> Result<Pubkey, ExecutionError> {
+    pub fn get_user_token_account_address(&self, mint: &Pubkey, user: &Pubkey, token_program: &Pubkey) -> Result<Pubkey, ExecutionError> {
+        if *token_program == TOKEN_2022_PROGRAM {
+            return Ok(get_associated_token_address_with_program_id(user, mint, token_program));
+        }
         let seed = self.generate_token_account_seed(mint, user)?;
         self.derive_token_account_with_seed(user, &seed)
     }
@@ -651,6 +671,7 @@ impl TransactionBuilder {
 mod tests {
     use super::*;
     use solana_sdk::signature::Keypair;
+    use spl_associated_token_account::get_associated_token_address;
 
     #[test]
     fn test_pumpfun_account_count() {
@@ -665,7 +686,7 @@ mod tests {
             min_tokens_out: 1000,
         };
 
-        let accounts = builder.get_pumpfun_accounts(&trade, &user, Some(&creator));
+        let accounts = builder.get_pumpfun_accounts(&trade, &user, Some(&creator), &TOKEN_PROGRAM);
         
         match accounts {
             Ok(account_list) => {
@@ -694,6 +715,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_token_2022_accounts_use_mint_program() {
+        let builder = TransactionBuilder::new();
+        let mint = Keypair::new().pubkey();
+        let user = Keypair::new().pubkey();
+        let creator = Keypair::new().pubkey();
+        let (bonding_curve, _) = Pubkey::find_program_address(&[BONDING_CURVE_SEED, mint.as_ref()], &PUMPFUN);
+        let user_ata = get_associated_token_address_with_program_id(&user, &mint, &TOKEN_2022_PROGRAM);
+
+        let buy = builder.build_pumpfun_buy_with_creator(&mint, &user, 1_000_000, 1000, &creator, &TOKEN_2022_PROGRAM).unwrap();
+        assert_eq!(buy.accounts[8].pubkey, TOKEN_2022_PROGRAM);
+        assert_eq!(
+            buy.accounts[4].pubkey,
+            get_associated_token_address_with_program_id(&bonding_curve, &mint, &TOKEN_2022_PROGRAM)
+        );
+        assert_eq!(buy.accounts[5].pubkey, user_ata);
+
+        let sell = builder.build_pumpfun_sell_with_creator(&mint, &user, 1000, 0, &creator, &TOKEN_2022_PROGRAM).unwrap();
+        assert_eq!(sell.accounts[9].pubkey, TOKEN_2022_PROGRAM);
+        assert_eq!(sell.accounts[5].pubkey, user_ata);
+
+        // SPL Token 代币仍使用基于种子的账户
+        let seeded = builder.get_user_token_account_address(&mint, &user, &TOKEN_PROGRAM).unwrap();
+        assert_ne!(seeded, get_associated_token_address(&user, &mint));
+        let buy = builder.build_pumpfun_buy_with_creator(&mint, &user, 1_000_000, 1000, &creator, &TOKEN_PROGRAM).unwrap();
+        assert_eq!(buy.accounts[5].pubkey, seeded);
+    }
+
     #[test] 
     fn test_buy_instruction_data() {
         let trade = PumpFunTrade::Buy {
@@ -20,6 +20,7 @@ use crate::executor::{
     blockhash_cache::BlockhashCache,
     compute_budget::DynamicComputeBudgetManager,
 };
+use crate::constant::accounts::TOKEN_PROGRAM;
 
 /// ZeroSlot交易执行器
 pub struct ZeroShotExecutor {
@@ -171,6 +172,8 @@ impl ZeroShotExecutor {
             tip_lamports,
         );
 
+        let token_program = trade_params.token_program.unwrap_or(TOKEN_PROGRAM);
+
         // 使用transaction_builder的统一方法构建交易
         if trade_params.is_buy {
             if let Some(creator) = &trade_params.creator {
@@ -181,6 +184,7 @@ impl ZeroShotExecutor {
                     trade_params.sol_amount,
                     trade_params.min_tokens_out,
                     creator,
+                    &token_program,
                     tip_instruction,
                     recent_blockhash,
                 )
@@ -201,6 +205,7 @@ impl ZeroShotExecutor {
                             token_amount,
                             min_sol_out,
                             creator,
+                            &token_program,
                             tip_instruction,
                             recent_blockhash,
                         )
@@ -0,0 +1,416 @@
+use serde_json::{Map, Value};
+use solana_sdk::{hash::hashv, pubkey::Pubkey};
+use std::collections::HashMap;
+use thiserror::Error;
+
+use super::pumpfun_events::ANCHOR_EVENT_IX_TAG;
+use crate::processors::instruction_account_mapper::{Idl, IdlField, IdlInstruction, IdlType, IdlTypeDefTy};
+
+pub type Discriminator = [u8; 8];
+
+/// 按 IDL 解码失败的原因
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum IdlDecodeError {
+    #[error("Unknown {kind} discriminator {} for program {program}", hex::encode(.discriminator))]
+    UnknownDiscriminator {
+        program: String,
+        kind: &'static str,
+        discriminator: Discriminator,
+    },
+
+    #[error("Data too short while decoding {path}: needed {needed} bytes, {remaining} remaining")]
+    DataTooShort {
+        path: String,
+        needed: usize,
+        remaining: usize,
+    },
+
+    #[error("Type '{0}' is not defined in the IDL")]
+    UndefinedType(String),
+
+    #[error("Invalid {kind} tag {tag} at {path}")]
+    InvalidTag {
+        path: String,
+        kind: &'static str,
+        tag: u8,
+    },
+
+    #[error("Invalid UTF-8 string at {0}")]
+    InvalidUtf8(String),
+}
+
+/// 解码结果：名称 + 以 IDL 字段名为键的 JSON 对象
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedData {
+    pub name: String,
+    pub data: Value,
+}
+
+/// IDL 驱动的 Borsh 解码器
+///
+/// 判别器优先取 IDL 中声明的值，旧版 IDL 没有时按 Anchor 规则计算
+/// （`sha256("global:<name>")`、`sha256("account:<Name>")`、`sha256("event:<Name>")` 的前8字节）。
+/// 新增程序只需提供 IDL，无需手写判别器表和字节偏移。
+#[derive(Debug, Clone)]
+pub struct IdlDecoder {
+    idl: Idl,
+    program: String,
+    instructions: HashMap<Discriminator, usize>,
+    accounts: HashMap<Discriminator, String>,
+    events: HashMap<Discriminator, String>,
+    types: HashMap<String, IdlTypeDefTy>,
+}
+
+/// Anchor 判别器：`sha256("<namespace>:<name>")` 的前8字节
+pub fn anchor_discriminator(namespace: &str, name: &str) -> Discriminator {
+    let hash = hashv(&[namespace.as_bytes(), b":", name.as_bytes()]);
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash.to_bytes()[..8]);
+    discriminator
+}
+
+fn declared_or(declared: Option<&Vec<u8>>, namespace: &str, name: &str) -> Discriminator {
+    declared
+        .and_then(|bytes| Discriminator::try_from(bytes.as_slice()).ok())
+        .unwrap_or_else(|| anchor_discriminator(namespace, name))
+}
+
+impl IdlDecoder {
+    pub fn new(idl: Idl) -> Self {
+        let program = idl
+            .address
+            .clone()
+            .unwrap_or_else(|| idl.metadata.name.clone());
+
+        let instructions = idl
+            .instructions
+            .iter()
+            .enumerate()
+            .map(|(index, instruction)| {
+                (declared_or(instruction.discriminator.as_ref(), "global", &instruction.name), index)
+            })
+            .collect();
+
+        let accounts = idl
+            .accounts
+            .iter()
+            .flatten()
+            .map(|account| (declared_or(account.discriminator.as_ref(), "account", &account.name), account.name.clone()))
+            .collect();
+
+        let events = idl
+            .events
+            .iter()
+            .flatten()
+            .map(|event| (declared_or(event.discriminator.as_ref(), "event", &event.name), event.name.clone()))
+            .collect();
+
+        let mut types: HashMap<String, IdlTypeDefTy> = idl
+            .types
+            .iter()
+            .flatten()
+            .map(|definition| (definition.name.clone(), definition.type_.clone()))
+            .collect();
+
+        // 旧版 IDL 的事件字段直接写在 events 中，没有对应的类型定义
+        for event in idl.events.iter().flatten() {
+            if types.contains_key(&event.name) {
+                continue;
+            }
+            let fields = match (&event.fields, &event.r#type) {
+                (Some(fields), _) => fields.clone(),
+                (None, Some(event_type)) => event_type
+                    .fields
+                    .iter()
+                    .map(|field| IdlField { name: field.name.clone(), type_: field.type_.clone(), docs: None })
+                    .collect(),
+                (None, None) => continue,
+            };
+            types.insert(event.name.clone(), IdlTypeDefTy::Struct { fields });
+        }
+
+        Self {
+            idl,
+            program,
+            instructions,
+            accounts,
+            events,
+            types,
+        }
+    }
+
+    pub fn idl(&self) -> &Idl {
+        &self.idl
+    }
+
+    /// 按判别器查找指令定义
+    pub fn instruction(&self, data: &[u8]) -> Result<&IdlInstruction, IdlDecodeError> {
+        let discriminator = self.discriminator(data, "instruction")?;
+        self.instructions
+            .get(&discriminator)
+            .map(|&index| &self.idl.instructions[index])
+            .ok_or_else(|| self.unknown("instruction", discriminator))
+    }
+
+    /// 解码指令参数
+    ///
+    /// 程序可以在参数末尾追加新字段并兼容旧客户端（如 PumpFun buy 的 `track_volume`），
+    /// 因此数据恰好在某个参数边界结束时，其余参数记为 null 而不是报错。
+    pub fn decode_instruction(&self, data: &[u8]) -> Result<DecodedData, IdlDecodeError> {
+        let instruction = self.instruction(data)?;
+        let mut reader = &data[8..];
+        let mut args = Map::new();
+        for arg in &instruction.args {
+            let value = if reader.is_empty() {
+                Value::Null
+            } else {
+                self.decode_type(&arg.type_, &mut reader, &arg.name)?
+            };
+            args.insert(arg.name.clone(), value);
+        }
+
+        Ok(DecodedData {
+            name: instruction.name.clone(),
+            data: Value::Object(args),
+        })
+    }
+
+    /// 解码账户数据
+    pub fn decode_account(&self, data: &[u8]) -> Result<DecodedData, IdlDecodeError> {
+        let discriminator = self.discriminator(data, "account")?;
+        let name = self.accounts.get(&discriminator).ok_or_else(|| self.unknown("account", discriminator))?;
+        self.decode_named(name, &data[8..])
+    }
+
+    /// 解码事件；接受带 `emit_cpi!` 前缀的自调用指令数据或日志中的事件数据
+    pub fn decode_event(&self, data: &[u8]) -> Result<DecodedData, IdlDecodeError> {
+        let data = data.strip_prefix(&ANCHOR_EVENT_IX_TAG).unwrap_or(data);
+        let discriminator = self.discriminator(data, "event")?;
+        let name = self.events.get(&discriminator).ok_or_else(|| self.unknown("event", discriminator))?;
+        self.decode_named(name, &data[8..])
+    }
+
+    fn decode_named(&self, name: &str, mut reader: &[u8]) -> Result<DecodedData, IdlDecodeError> {
+        let data = self.decode_defined(name, &mut reader, name)?;
+        Ok(DecodedData { name: name.to_string(), data })
+    }
+
+    fn discriminator(&self, data: &[u8], kind: &'static str) -> Result<Discriminator, IdlDecodeError> {
+        data.first_chunk::<8>().copied().ok_or_else(|| IdlDecodeError::DataTooShort {
+            path: format!("{} discriminator", kind),
+            needed: 8,
+            remaining: data.len(),
+        })
+    }
+
+    fn unknown(&self, kind: &'static str, discriminator: Discriminator) -> IdlDecodeError {
+        IdlDecodeError::UnknownDiscriminator {
+            program: self.program.clone(),
+            kind,
+            discriminator,
+        }
+    }
+
+    /// 将任意 `IdlType` 解码为 JSON
+    ///
+    /// 超过 64 位的整数以十进制字符串表示（256 位为十六进制），bytes 为十六进制，pubkey 为 base58。
+    pub fn decode_type(&self, type_: &IdlType, reader: &mut &[u8], path: &str) -> Result<Value, IdlDecodeError> {
+        let value = match type_ {
+            IdlType::Bool => match take::<1>(reader, path)?[0] {
+                0 => Value::Bool(false),
+                1 => Value::Bool(true),
+                tag => return Err(IdlDecodeError::InvalidTag { path: path.to_string(), kind: "bool", tag }),
+            },
+            IdlType::U8 => Value::from(take::<1>(reader, path)?[0]),
+            IdlType::I8 => Value::from(i8::from_le_bytes(take(reader, path)?)),
+            IdlType::U16 => Value::from(u16::from_le_bytes(take(reader, path)?)),
+            IdlType::I16 => Value::from(i16::from_le_bytes(take(reader, path)?)),
+            IdlType::U32 => Value::from(u32::from_le_bytes(take(reader, path)?)),
+            IdlType::I32 => Value::from(i32::from_le_bytes(take(reader, path)?)),
+            IdlType::U64 => Value::from(u64::from_le_bytes(take(reader, path)?)),
+            IdlType::I64 => Value::from(i64::from_le_bytes(take(reader, path)?)),
+            IdlType::F32 => Value::from(f32::from_le_bytes(take(reader, path)?)),
+            IdlType::F64 => Value::from(f64::from_le_bytes(take(reader, path)?)),
+            IdlType::U128 => Value::String(u128::from_le_bytes(take(reader, path)?).to_string()),
+            IdlType::I128 => Value::String(i128::from_le_bytes(take(reader, path)?).to_string()),
+            IdlType::U256 | IdlType::I256 => {
+                let mut bytes = take::<32>(reader, path)?;
+                bytes.reverse();
+                Value::String(format!("0x{}", hex::encode(bytes)))
+            }
+            IdlType::Bytes => Value::String(hex::encode(take_len_prefixed(reader, path)?)),
+            IdlType::String => {
+                let bytes = take_len_prefixed(reader, path)?;
+                let string = std::str::from_utf8(bytes).map_err(|_| IdlDecodeError::InvalidUtf8(path.to_string()))?;
+                Value::String(string.to_string())
+            }
+            IdlType::PublicKey => Value::String(Pubkey::new_from_array(take(reader, path)?).to_string()),
+            IdlType::Option(inner) => match take::<1>(reader, path)?[0] {
+                0 => Value::Null,
+                1 => self.decode_type(inner, reader, path)?,
+                tag => return Err(IdlDecodeError::InvalidTag { path: path.to_string(), kind: "option", tag }),
+            },
+            IdlType::Vec(inner) => {
+                let len = u32::from_le_bytes(take(reader, path)?) as usize;
+                // 每个元素至少占1字节，先检查长度避免按伪造长度分配内存
+                if len > reader.len() {
+                    return Err(IdlDecodeError::DataTooShort { path: path.to_string(), needed: len, remaining: reader.len() });
+                }
+                self.decode_sequence(inner, len, reader, path)?
+            }
+            IdlType::Array(inner, len) => self.decode_sequence(inner, *len, reader, path)?,
+            IdlType::Defined { name } => self.decode_defined(name, reader, path)?,
+            // 新版 IDL 用 "pubkey"，解析时落入按名称引用的分支
+            IdlType::DefinedString(name) if name == "pubkey" => {
+                Value::String(Pubkey::new_from_array(take(reader, path)?).to_string())
+            }
+            IdlType::DefinedString(name) => self.decode_defined(name, reader, path)?,
+        };
+
+        Ok(value)
+    }
+
+    fn decode_sequence(&self, inner: &IdlType, len: usize, reader: &mut &[u8], path: &str) -> Result<Value, IdlDecodeError> {
+        (0..len)
+            .map(|index| self.decode_type(inner, reader, &format!("{}[{}]", path, index)))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array)
+    }
+
+    /// 结构体解码为对象；枚举的单元变体解码为变体名，带字段的变体解码为 `{变体名: {字段}}`
+    fn decode_defined(&self, name: &str, reader: &mut &[u8], path: &str) -> Result<Value, IdlDecodeError> {
+        let definition = self.types.get(name).ok_or_else(|| IdlDecodeError::UndefinedType(name.to_string()))?;
+
+        match definition {
+            IdlTypeDefTy::Struct { fields } => self.decode_fields(fields, reader, path),
+            IdlTypeDefTy::Enum { variants } => {
+                let tag = take::<1>(reader, path)?[0];
+                let variant = variants.get(tag as usize).ok_or_else(|| IdlDecodeError::InvalidTag {
+                    path: path.to_string(),
+                    kind: "enum variant",
+                    tag,
+                })?;
+                match &variant.fields {
+                    None => Ok(Value::String(variant.name.clone())),
+                    Some(fields) => {
+                        let value = self.decode_fields(fields, reader, &format!("{}.{}", path, variant.name))?;
+                        Ok(Value::Object(Map::from_iter([(variant.name.clone(), value)])))
+                    }
+                }
+            }
+        }
+    }
+
+    fn decode_fields(&self, fields: &[IdlField], reader: &mut &[u8], path: &str) -> Result<Value, IdlDecodeError> {
+        let mut object = Map::new();
+        for field in fields {
+            let value = self.decode_type(&field.type_, reader, &format!("{}.{}", path, field.name))?;
+            object.insert(field.name.clone(), value);
+        }
+        Ok(Value::Object(object))
+    }
+}
+
+fn take<const N: usize>(reader: &mut &[u8], path: &str) -> Result<[u8; N], IdlDecodeError> {
+    let (bytes, rest) = reader.split_first_chunk::<N>().ok_or_else(|| IdlDecodeError::DataTooShort {
+        path: path.to_string(),
+        needed: N,
+        remaining: reader.len(),
+    })?;
+    *reader = rest;
+    Ok(*bytes)
+}
+
+fn take_len_prefixed<'a>(reader: &mut &'a [u8], path: &str) -> Result<&'a [u8], IdlDecodeError> {
+    let len = u32::from_le_bytes(take(reader, path)?) as usize;
+    if len > reader.len() {
+        return Err(IdlDecodeError::DataTooShort { path: path.to_string(), needed: len, remaining: reader.len() });
+    }
+    let (bytes, rest) = reader.split_at(len);
+    *reader = rest;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pumpfun_decoder() -> IdlDecoder {
+        let idl: Idl = serde_json::from_str(include_str!("../../idls/pumpfun_0.1.0.json")).unwrap();
+        IdlDecoder::new(idl)
+    }
+
+    #[test]
+    fn test_computed_discriminators_match_idl() {
+        let decoder = pumpfun_decoder();
+        for instruction in &decoder.idl().instructions {
+            assert_eq!(
+                instruction.discriminator.as_deref(),
+                Some(anchor_discriminator("global", &instruction.name).as_slice()),
+                "{}",
+                instruction.name
+            );
+        }
+        assert_eq!(anchor_discriminator("event", "TradeEvent"), [189, 219, 127, 211, 78, 230, 97, 238]);
+    }
+
+    #[test]
+    fn test_decode_instruction_args() {
+        let decoder = pumpfun_decoder();
+        let creator = Pubkey::new_unique();
+
+        let mut create = anchor_discriminator("global", "create").to_vec();
+        create.extend(borsh::to_vec(&("Dog".to_string(), "DOG".to_string(), "https://d.og".to_string(), creator)).unwrap());
+        let decoded = decoder.decode_instruction(&create).unwrap();
+        assert_eq!(decoded.name, "create");
+        assert_eq!(decoded.data["symbol"], "DOG");
+        assert_eq!(decoded.data["creator"], creator.to_string());
+
+        // 旧客户端的 buy 不带 track_volume；新客户端带 OptionBool
+        let mut buy = anchor_discriminator("global", "buy").to_vec();
+        buy.extend(borsh::to_vec(&(1_000u64, 2_000u64)).unwrap());
+        let decoded = decoder.decode_instruction(&buy).unwrap();
+        assert_eq!(decoded.data["max_sol_cost"], 2_000);
+        assert_eq!(decoded.data["track_volume"], Value::Null);
+
+        buy.push(1);
+        let decoded = decoder.decode_instruction(&buy).unwrap();
+        assert_eq!(decoded.data["track_volume"]["field_0"], true);
+    }
+
+    #[test]
+    fn test_decode_event_and_account() {
+        let decoder = pumpfun_decoder();
+        let mint = Pubkey::new_unique();
+
+        let mut event = ANCHOR_EVENT_IX_TAG.to_vec();
+        event.extend(anchor_discriminator("event", "CompleteEvent"));
+        event.extend(borsh::to_vec(&(Pubkey::new_unique(), mint, Pubkey::new_unique(), 1_700_000_000i64)).unwrap());
+        let decoded = decoder.decode_event(&event).unwrap();
+        assert_eq!(decoded.name, "CompleteEvent");
+        assert_eq!(decoded.data["mint"], mint.to_string());
+        assert_eq!(decoded.data["timestamp"], 1_700_000_000i64);
+
+        let mut account = anchor_discriminator("account", "BondingCurve").to_vec();
+        account.extend(borsh::to_vec(&(1u64, 2u64, 3u64, 4u64, 5u64, true, Pubkey::new_unique())).unwrap());
+        let decoded = decoder.decode_account(&account).unwrap();
+        assert_eq!(decoded.name, "BondingCurve");
+        assert_eq!(decoded.data["complete"], true);
+    }
+
+    #[test]
+    fn test_typed_errors() {
+        let decoder = pumpfun_decoder();
+        assert!(matches!(
+            decoder.decode_instruction(&[0; 8]),
+            Err(IdlDecodeError::UnknownDiscriminator { kind: "instruction", .. })
+        ));
+
+        let mut create = anchor_discriminator("global", "create").to_vec();
+        create.extend_from_slice(&100u32.to_le_bytes());
+        assert!(matches!(
+            decoder.decode_instruction(&create),
+            Err(IdlDecodeError::DataTooShort { needed: 100, .. })
+        ));
+    }
+}
@@ -0,0 +1,159 @@
+use base64::Engine;
+use raydium_launchpad_interface::events::{
+    PoolCreateEvent, PoolCreateEventEvent, PoolStatus, TradeDirection as LaunchpadTradeDirection, TradeEvent,
+    TradeEventEvent, POOL_CREATE_EVENT_DISCM, TRADE_EVENT_DISCM,
+};
+use solana_sdk::pubkey::Pubkey;
+
+use super::pumpfun_events::ANCHOR_EVENT_IX_TAG;
+use crate::processors::{CurveReserves, DevBuy, LaunchpadFees, LaunchpadPoolStatus, TokenMetadata, TradeDirection};
+
+/// Raydium Launchpad 通过 `emit_cpi!` 或日志发出的事件
+#[derive(Debug, Clone, PartialEq)]
+pub enum LaunchpadCpiEvent {
+    PoolCreate(PoolCreateEvent),
+    Trade(TradeEvent),
+}
+
+impl LaunchpadCpiEvent {
+    /// 解码自调用指令数据（`标签 + 事件判别器 + Borsh 数据`）
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        Self::decode_payload(data.strip_prefix(&ANCHOR_EVENT_IX_TAG)?)
+    }
+
+    /// 解码 `Program data: <base64>` 日志行
+    pub fn decode_log(line: &str) -> Option<Self> {
+        let encoded = line.strip_prefix("Program data: ")?.trim();
+        let payload = base64::engine::general_purpose::STANDARD.decode(encoded).ok()?;
+        Self::decode_payload(&payload)
+    }
+
+    /// 解码 `事件判别器 + Borsh 数据`；末尾新增的字段会被忽略
+    fn decode_payload(mut payload: &[u8]) -> Option<Self> {
+        let (discriminator, _) = payload.split_first_chunk::<8>()?;
+        match *discriminator {
+            POOL_CREATE_EVENT_DISCM => PoolCreateEventEvent::deserialize(&mut payload).ok().map(|event| Self::PoolCreate(event.0)),
+            TRADE_EVENT_DISCM => TradeEventEvent::deserialize(&mut payload).ok().map(|event| Self::Trade(event.0)),
+            _ => None,
+        }
+    }
+
+    pub fn pool_state(&self) -> Pubkey {
+        match self {
+            Self::PoolCreate(event) => event.pool_state,
+            Self::Trade(event) => event.pool_state,
+        }
+    }
+}
+
+/// `PoolCreateEvent` 中的代币信息
+pub fn pool_create_metadata(event: &PoolCreateEvent) -> TokenMetadata {
+    TokenMetadata {
+        name: event.base_mint_param.name.clone(),
+        symbol: event.base_mint_param.symbol.clone(),
+        uri: event.base_mint_param.uri.clone(),
+    }
+}
+
+/// 对 `TradeEvent` 的统一视图（base 为代币，quote 为 SOL）
+pub trait LaunchpadTradeEventExt {
+    fn direction(&self) -> TradeDirection;
+    /// 实际成交金额
+    fn fill(&self) -> DevBuy;
+    /// 成交后的储备
+    fn reserves(&self) -> CurveReserves;
+    fn fees(&self) -> LaunchpadFees;
+    fn status(&self) -> LaunchpadPoolStatus;
+}
+
+impl LaunchpadTradeEventExt for TradeEvent {
+    fn direction(&self) -> TradeDirection {
+        match self.trade_direction {
+            LaunchpadTradeDirection::Buy => TradeDirection::Buy,
+            LaunchpadTradeDirection::Sell => TradeDirection::Sell,
+        }
+    }
+
+    fn fill(&self) -> DevBuy {
+        let (sol_amount, token_amount) = match self.trade_direction {
+            LaunchpadTradeDirection::Buy => (self.amount_in, self.amount_out),
+            LaunchpadTradeDirection::Sell => (self.amount_out, self.amount_in),
+        };
+        DevBuy { sol_amount, token_amount }
+    }
+
+    /// 事件中的 `virtual_*` 是池创建时的虚拟储备，这里换算为当前储备，
+    /// 使 `CurveReserves::price` 与 PumpFun 语义一致：
+    /// 虚拟 SOL = virtual_quote + real_quote，虚拟代币 = virtual_base - real_base，
+    /// 实际代币储备为剩余可售数量。
+    fn reserves(&self) -> CurveReserves {
+        CurveReserves {
+            virtual_sol_reserves: self.virtual_quote.saturating_add(self.real_quote_after),
+            virtual_token_reserves: self.virtual_base.saturating_sub(self.real_base_after),
+            real_sol_reserves: self.real_quote_after,
+            real_token_reserves: self.total_base_sell.saturating_sub(self.real_base_after),
+        }
+    }
+
+    fn fees(&self) -> LaunchpadFees {
+        LaunchpadFees {
+            protocol_fee: self.protocol_fee,
+            platform_fee: self.platform_fee,
+            share_fee: self.share_fee,
+        }
+    }
+
+    fn status(&self) -> LaunchpadPoolStatus {
+        match self.pool_status {
+            PoolStatus::Fund => LaunchpadPoolStatus::Fund,
+            PoolStatus::Migrate => LaunchpadPoolStatus::Migrate,
+            PoolStatus::Trade => LaunchpadPoolStatus::Trade,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 按链上布局编码的 TradeEvent（买入、募集中）
+    fn trade_event_payload(pool_state: Pubkey) -> Vec<u8> {
+        let mut payload = TRADE_EVENT_DISCM.to_vec();
+        payload.extend(borsh::to_vec(&(pool_state, 793_100_000_000_000u64, 1_073_025_605_596_382u64, 30_000_852_951u64)).unwrap());
+        payload.extend(borsh::to_vec(&(0u64, 0u64, 17_000_000_000_000u64, 480_000_000u64)).unwrap());
+        payload.extend(borsh::to_vec(&(490_000_000u64, 17_000_000_000_000u64, 1_200_000u64, 4_800_000u64, 0u64)).unwrap());
+        // trade_direction = Buy, pool_status = Fund
+        payload.extend_from_slice(&[0, 0]);
+        payload
+    }
+
+    #[test]
+    fn test_decode_trade_event_from_cpi_and_log() {
+        let pool_state = Pubkey::new_unique();
+        let mut payload = trade_event_payload(pool_state);
+        // 后续版本追加的字段
+        payload.extend_from_slice(&[1, 0, 0, 0, 0, 0, 0, 0, 0]);
+
+        let mut data = ANCHOR_EVENT_IX_TAG.to_vec();
+        data.extend_from_slice(&payload);
+        let Some(LaunchpadCpiEvent::Trade(trade)) = LaunchpadCpiEvent::decode(&data) else {
+            panic!("应解码出 TradeEvent");
+        };
+        assert_eq!(trade.pool_state, pool_state);
+        assert_eq!(trade.direction(), TradeDirection::Buy);
+        assert_eq!(trade.fill(), DevBuy { sol_amount: 490_000_000, token_amount: 17_000_000_000_000 });
+        assert_eq!(trade.fees().total(), 6_000_000);
+        assert_eq!(trade.status(), LaunchpadPoolStatus::Fund);
+
+        let reserves = trade.reserves();
+        assert_eq!(reserves.virtual_sol_reserves, 30_480_852_951);
+        assert_eq!(reserves.virtual_token_reserves, 1_056_025_605_596_382);
+        assert_eq!(reserves.real_token_reserves, 776_100_000_000_000);
+
+        let line = format!("Program data: {}", base64::engine::general_purpose::STANDARD.encode(&payload));
+        assert_eq!(LaunchpadCpiEvent::decode_log(&line), Some(LaunchpadCpiEvent::Trade(trade)));
+
+        // 没有事件标签的普通指令
+        assert_eq!(LaunchpadCpiEvent::decode(&payload), None);
+    }
+}
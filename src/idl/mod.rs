@@ -1,3 +1,13 @@
+pub mod decoder;
+#[cfg(feature = "letsbonk")]
+pub mod launchpad_events;
 pub mod processor;
+pub mod pumpfun_events;
+pub mod registry;
 
-pub use processor::*;
\ No newline at end of file
+pub use decoder::{anchor_discriminator, DecodedData, IdlDecodeError, IdlDecoder};
+#[cfg(feature = "letsbonk")]
+pub use launchpad_events::{LaunchpadCpiEvent, LaunchpadTradeEventExt};
+pub use processor::*;
+pub use pumpfun_events::{PumpFunCompleteEvent, PumpFunCpiEvent, PumpFunCreateEvent, PumpFunMigrationEvent, PumpFunTradeEvent};
+pub use registry::{IdlRegistry, IdlSource, RegisteredIdl};
@@ -1,9 +1,9 @@
 use anyhow::Result;
-use log::{debug, info, warn, error};
+use log::{debug, warn, error};
 use serde::Serialize;
 use solana_sdk::{
     instruction::{AccountMeta, CompiledInstruction, Instruction},
-    message::{v0::LoadedAddresses, VersionedMessage},
+    message::{v0::{LoadedAddresses, MessageAddressTableLookup}, VersionedMessage},
     pubkey::Pubkey,
     signature::Signature,
     transaction::VersionedTransaction,
@@ -12,16 +12,20 @@ use solana_transaction_status::{
     VersionedTransactionWithStatusMeta, InnerInstructions, InnerInstruction,
     TransactionStatusMeta as SolanaTransactionStatusMeta
 };
-use std::{fs, str::FromStr};
+use std::{collections::HashMap, sync::Arc};
 use yellowstone_grpc_proto::geyser::SubscribeUpdateTransactionInfo;
 use yellowstone_grpc_proto::prelude::TransactionStatusMeta;
 
-use crate::processors::{instruction_account_mapper::{AccountMetadata, Idl, InstructionAccountMapper}, TokenEvent, TransactionType};
+use super::decoder::IdlDecoder;
+use super::registry::IdlRegistry;
+use super::pumpfun_events::{PumpFunCpiEvent, PumpFunCreateEvent, PumpFunMigrationEvent, PumpFunTradeEvent};
+use crate::processors::{instruction_account_mapper::{AccountMetadata, Idl, InstructionAccountMapper}, CurveReserves, DevBuy, Protocol, ProtocolEvent, TokenEvent, TokenMetadata, TradeDirection};
 use crate::serialization::serialize_pubkey;
 
 // Program IDs
 const PUMPFUN_PROGRAM_ID: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
 const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
 
 #[derive(Debug, Serialize)]
 pub struct TransactionInstructionWithParent {
@@ -40,61 +44,82 @@ pub struct DecodedInstruction {
     pub parent_program_id: Option<Pubkey>,
 }
 
+/// PumpFun `create`/`create_v2` 指令参数，字段顺序与 `idls/pumpfun_0.1.0.json` 一致
+#[derive(Debug, Clone, PartialEq)]
+pub struct PumpFunCreateArgs {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub creator: Pubkey,
+}
+
+impl PumpFunCreateArgs {
+    /// 从 `decode_anchor_instruction` 输出的 JSON 中取回参数
+    fn from_decoded(data: &serde_json::Value) -> Option<Self> {
+        let field = |name: &str| data.get(name).and_then(|value| value.as_str());
+        Some(Self {
+            name: field("name")?.to_string(),
+            symbol: field("symbol")?.to_string(),
+            uri: field("uri")?.to_string(),
+            creator: field("creator")?.parse().ok()?,
+        })
+    }
+
+    fn metadata(&self) -> TokenMetadata {
+        TokenMetadata {
+            name: self.name.clone(),
+            symbol: self.symbol.clone(),
+            uri: self.uri.clone(),
+        }
+    }
+}
+
+/// 地址查找表解析器，用于在没有meta时解析v0交易的加载账户
+pub trait AddressLookupResolver: Send + Sync {
+    /// 返回该查找表中被引用的可写/只读地址，无法解析时返回None
+    fn resolve(&self, lookup: &MessageAddressTableLookup) -> Option<LoadedAddresses>;
+}
+
+/// 基于本地缓存的查找表内容（表地址 -> 地址列表）
+impl AddressLookupResolver for HashMap<Pubkey, Vec<Pubkey>> {
+    fn resolve(&self, lookup: &MessageAddressTableLookup) -> Option<LoadedAddresses> {
+        let addresses = self.get(&lookup.account_key)?;
+        let select = |indexes: &[u8]| -> Option<Vec<Pubkey>> {
+            indexes.iter().map(|&i| addresses.get(i as usize).copied()).collect()
+        };
+
+        Some(LoadedAddresses {
+            writable: select(&lookup.writable_indexes)?,
+            readonly: select(&lookup.readonly_indexes)?,
+        })
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct IdlTransactionProcessor {
     pub pumpfun_program_id: Pubkey,
     pub token_program_id: Pubkey,
-    pub pumpfun_idl: Option<Idl>,
-    pub token_idl: Option<Idl>,
+    pub token_2022_program_id: Pubkey,
+    registry: Arc<IdlRegistry>,
 }
 
 impl IdlTransactionProcessor {
+    /// 使用进程级 IDL 注册表（未配置时为内嵌默认 IDL）
     pub fn new() -> Result<Self> {
-        let mut processor = Self {
-            pumpfun_program_id: Pubkey::from_str(PUMPFUN_PROGRAM_ID)?,
-            token_program_id: Pubkey::from_str(TOKEN_PROGRAM_ID)?,
-            pumpfun_idl: None,
-            token_idl: None,
-        };
-
-        // 加载IDL文件
-        processor.load_idls()?;
-        
-        Ok(processor)
+        Ok(Self::with_registry(IdlRegistry::global()))
     }
 
-    fn load_idls(&mut self) -> Result<()> {
-        // 加载PumpFun IDL
-        if let Ok(idl_content) = fs::read_to_string("idls/pumpfun_0.1.0.json") {
-            match serde_json::from_str::<Idl>(&idl_content) {
-                Ok(idl) => {
-                    info!("✅ 成功加载PumpFun IDL");
-                    self.pumpfun_idl = Some(idl);
-                }
-                Err(e) => {
-                    error!("解析PumpFun IDL失败: {}", e);
-                }
-            }
-        } else {
-            info!("⚠️  未找到PumpFun IDL文件，将使用基础解析");
-        }
-
-        // 加载Token程序IDL
-        if let Ok(idl_content) = fs::read_to_string("idls/token_program_idl.json") {
-            match serde_json::from_str::<Idl>(&idl_content) {
-                Ok(idl) => {
-                    info!("✅ 成功加载Token程序IDL");
-                    self.token_idl = Some(idl);
-                }
-                Err(e) => {
-                    error!("解析Token程序IDL失败: {}", e);
-                }
-            }
-        } else {
-            info!("⚠️  未找到Token程序IDL文件，将使用基础解析");
+    pub fn with_registry(registry: Arc<IdlRegistry>) -> Self {
+        Self {
+            pumpfun_program_id: Pubkey::from_str_const(PUMPFUN_PROGRAM_ID),
+            token_program_id: Pubkey::from_str_const(TOKEN_PROGRAM_ID),
+            token_2022_program_id: Pubkey::from_str_const(TOKEN_2022_PROGRAM_ID),
+            registry,
         }
+    }
 
-        Ok(())
+    pub fn registry(&self) -> &IdlRegistry {
+        &self.registry
     }
 
     /// 使用IDL解析交易
@@ -113,6 +138,11 @@ impl IdlTransactionProcessor {
 
         // 构建完整的交易结构用于解析
         if let Some(parsed_transaction) = self.build_parsed_transaction(txn_info, slot).await {
+            // 检查是否为曲线迁移；迁移时 AMM 会初始化 LP mint，需先于创建检测
+            if let Some(token_event) = self.detect_migration(&parsed_transaction, &signature, slot) {
+                return Some(token_event);
+            }
+
             // 检查是否包含代币创建
             if let Some(token_event) = self.detect_token_creation(&parsed_transaction, &signature, slot) {
                 return Some(token_event);
@@ -127,6 +157,191 @@ impl IdlTransactionProcessor {
         None
     }
 
+    /// 无meta解码：直接解析尚未执行的 VersionedTransaction（来自 shreds 等执行前数据源）
+    ///
+    /// 只使用静态账户；v0 交易的地址查找表通过可选的 `resolver` 解析，
+    /// 无法解析的账户索引会被跳过。金额全部来自指令数据，事件标记为未确认。
+    pub fn process_unconfirmed_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+        slot: u64,
+        resolver: Option<&dyn AddressLookupResolver>,
+    ) -> Option<TokenEvent> {
+        let signature = transaction.signatures
+            .first()
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+
+        debug!("开始无meta解析交易: {}", signature);
+
+        let loaded_addresses = self.resolve_loaded_addresses(&transaction.message, resolver);
+        let parsed_accounts = self.parse_transaction_accounts(&transaction.message, loaded_addresses);
+
+        let decoded_instructions: Vec<DecodedInstruction> = transaction.message.instructions()
+            .iter()
+            .filter_map(|ci| {
+                self.decode_instruction(&TransactionInstructionWithParent {
+                    instruction: self.compiled_instruction_to_instruction(ci, &parsed_accounts),
+                    parent_program_id: None,
+                })
+            })
+            .collect();
+
+        let fee_payer = transaction.message.static_account_keys().first().copied();
+
+        self.detect_unconfirmed_event(&decoded_instructions, fee_payer, &signature, slot)
+    }
+
+    /// 通过解析器加载地址查找表中的账户
+    fn resolve_loaded_addresses(
+        &self,
+        message: &VersionedMessage,
+        resolver: Option<&dyn AddressLookupResolver>,
+    ) -> LoadedAddresses {
+        let mut loaded_addresses = LoadedAddresses::default();
+
+        let (Some(lookups), Some(resolver)) = (message.address_table_lookups(), resolver) else {
+            return loaded_addresses;
+        };
+
+        for lookup in lookups {
+            match resolver.resolve(lookup) {
+                Some(resolved) => {
+                    loaded_addresses.writable.extend(resolved.writable);
+                    loaded_addresses.readonly.extend(resolved.readonly);
+                }
+                None => {
+                    debug!("⚠️ 无法解析地址查找表: {}", lookup.account_key);
+                    // 后续表的可写账户及所有只读账户的位置都依赖该表长度，只保留之前的可写账户
+                    loaded_addresses.readonly.clear();
+                    break;
+                }
+            }
+        }
+
+        loaded_addresses
+    }
+
+    /// 从已解码的顶层指令构建未确认事件（create + 可选的开发者买入，或普通买卖）
+    fn detect_unconfirmed_event(
+        &self,
+        instructions: &[DecodedInstruction],
+        fee_payer: Option<Pubkey>,
+        signature: &str,
+        slot: u64,
+    ) -> Option<TokenEvent> {
+        let pumpfun_instructions = || instructions.iter().filter(|instr| instr.program_id == self.pumpfun_program_id);
+
+        let account_keys = self.extract_account_keys(instructions);
+        let timestamp = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs() as i64;
+
+        if let Some(create) = pumpfun_instructions().find(|instr| is_create_instruction(&instr.name)) {
+            let mint = account_by_name(create, "mint")?;
+            let args = PumpFunCreateArgs::from_decoded(&create.data);
+            let creator = args.as_ref().map(|args| args.creator).or(account_by_name(create, "user")).or(fee_payer)?;
+            let metadata = args.as_ref().map(PumpFunCreateArgs::metadata);
+            let dev_buy = self.extract_dev_buy(instructions, &mint);
+            let token_program = account_by_name(create, "token_program");
+
+            debug!("🚀 检测到未确认的PumpFun代币创建: {}", mint);
+
+            return Some(TokenEvent {
+                signature: signature.to_string(),
+                slot,
+                kind: ProtocolEvent::PumpFunCreate { mint, creator, metadata, dev_buy },
+                program_logs: vec![],
+                account_keys,
+                timestamp: Some(timestamp),
+                reserves: None,
+                raw_data: Some(serde_json::json!({
+                    "signature": signature,
+                    "slot": slot,
+                    "confirmed": false,
+                    "dev_buy": dev_buy,
+                })),
+                block_height: None,
+                unconfirmed: true,
+                token_program,
+            });
+        }
+
+        if let Some(migrate) = pumpfun_instructions().find(|instr| instr.name == "migrate") {
+            let mint = account_by_name(migrate, "mint")?;
+            let pool = account_by_name(migrate, "pool");
+            debug!("🎓 检测到未确认的PumpFun迁移: {} -> {:?}", mint, pool);
+
+            return Some(TokenEvent {
+                signature: signature.to_string(),
+                slot,
+                kind: ProtocolEvent::Migration { protocol: Protocol::PumpFun, mint, pool },
+                program_logs: vec![],
+                account_keys,
+                timestamp: Some(timestamp),
+                reserves: None,
+                raw_data: Some(serde_json::json!({
+                    "signature": signature,
+                    "slot": slot,
+                    "confirmed": false,
+                })),
+                block_height: None,
+                unconfirmed: true,
+                token_program: None,
+            });
+        }
+
+        for instruction in pumpfun_instructions() {
+            let direction = match instruction.name.as_str() {
+                "buy" => TradeDirection::Buy,
+                "sell" => TradeDirection::Sell,
+                _ => continue,
+            };
+            let (Some(sol_amount), Some(token_amount)) =
+                self.extract_buy_sell_amounts(&instruction.data, direction == TradeDirection::Buy)
+            else {
+                continue;
+            };
+            let (Some(mint), Some(trader)) = (
+                self.extract_mint_from_accounts(&instruction.accounts),
+                account_by_name(instruction, "user").or(fee_payer),
+            ) else {
+                continue;
+            };
+
+            return Some(TokenEvent {
+                signature: signature.to_string(),
+                slot,
+                kind: ProtocolEvent::PumpFunTrade { mint, trader, direction, sol_amount, token_amount },
+                program_logs: vec![],
+                account_keys,
+                timestamp: Some(timestamp),
+                reserves: None,
+                raw_data: Some(serde_json::json!({
+                    "signature": signature,
+                    "slot": slot,
+                    "confirmed": false,
+                    "instruction": instruction.data,
+                })),
+                block_height: None,
+                unconfirmed: true,
+                token_program: account_by_name(instruction, "token_program"),
+            });
+        }
+
+        None
+    }
+
+    /// 同一交易中针对该mint的买入即为开发者买入
+    fn extract_dev_buy(&self, instructions: &[DecodedInstruction], mint: &Pubkey) -> Option<DevBuy> {
+        instructions
+            .iter()
+            .filter(|instr| instr.program_id == self.pumpfun_program_id && instr.name == "buy")
+            .find(|instr| account_by_name(instr, "mint").as_ref() == Some(mint))
+            .and_then(|instr| match self.extract_buy_sell_amounts(&instr.data, true) {
+                (Some(sol_amount), Some(token_amount)) => Some(DevBuy { sol_amount, token_amount }),
+                _ => None,
+            })
+    }
+
     /// 构建解析后的交易结构
     async fn build_parsed_transaction(
         &self,
@@ -147,6 +362,7 @@ impl IdlTransactionProcessor {
         // 使用IDL解码指令
         let mut decoded_compiled_instructions = Vec::new();
         let mut decoded_inner_instructions = Vec::new();
+        let mut events = Vec::new();
 
         // 解码编译指令
         for instruction_with_parent in compiled_instructions {
@@ -155,9 +371,11 @@ impl IdlTransactionProcessor {
             }
         }
 
-        // 解码内部指令
+        // 解码内部指令；PumpFun 通过事件权限自调用发出的事件单独收集
         for instruction_with_parent in inner_instructions {
-            if let Some(decoded) = self.decode_instruction(&instruction_with_parent) {
+            if let Some(event) = PumpFunCpiEvent::from_instruction(&instruction_with_parent.instruction) {
+                events.push(event);
+            } else if let Some(decoded) = self.decode_instruction(&instruction_with_parent) {
                 decoded_inner_instructions.push(decoded);
             }
         }
@@ -167,6 +385,7 @@ impl IdlTransactionProcessor {
             signature: bs58::encode(&txn_info.signature).into_string(),
             compiled_instructions: decoded_compiled_instructions,
             inner_instructions: decoded_inner_instructions,
+            events,
             meta: meta.clone(),
         })
     }
@@ -285,157 +504,58 @@ impl IdlTransactionProcessor {
     fn decode_instruction(&self, instruction_with_parent: &TransactionInstructionWithParent) -> Option<DecodedInstruction> {
         let instruction = &instruction_with_parent.instruction;
 
-        // PumpFun程序指令解析
-        if instruction.program_id == self.pumpfun_program_id {
-            if let Some(ref idl) = self.pumpfun_idl {
-                return self.decode_pumpfun_instruction(instruction, instruction_with_parent.parent_program_id, idl);
-            }
-        }
+        let decoder = self.registry.get(&instruction.program_id)?;
 
-        // Token程序指令解析
-        if instruction.program_id == self.token_program_id {
-            if let Some(ref idl) = self.token_idl {
-                return self.decode_token_instruction(instruction, instruction_with_parent.parent_program_id, idl);
-            }
+        // Token程序不是 Anchor 程序，指令由 spl-token / spl-token-2022 解析，IDL 只用于账户命名
+        if self.is_token_program(&instruction.program_id) {
+            return self.decode_token_instruction(instruction, instruction_with_parent.parent_program_id, decoder.idl());
         }
 
-        None
+        // PumpFun 及其他注册的 Anchor 程序
+        self.decode_anchor_instruction(instruction, instruction_with_parent.parent_program_id, decoder)
     }
 
-    /// 解码PumpFun指令 - 改进版本
-    fn decode_pumpfun_instruction(
+    /// 按 IDL 解码 Anchor 程序指令
+    ///
+    /// 判别器和参数布局都来自 IDL；判别器已知但参数解码失败时保留原始数据。
+    fn decode_anchor_instruction(
         &self,
         instruction: &Instruction,
         parent_program_id: Option<Pubkey>,
-        idl: &Idl,
+        decoder: &IdlDecoder,
     ) -> Option<DecodedInstruction> {
-        // PumpFun指令判别器（8字节）
-        if instruction.data.len() < 8 {
-            warn!("PumpFun instruction data too short: {} bytes", instruction.data.len());
-            return None;
-        }
-
-        let discriminator = &instruction.data[0..8];
-        
-        // 使用IDL中的正确判别器进行匹配
-        let instruction_name = match discriminator {
-            // 从IDL文件中获取的完整判别器列表
-            [102, 6, 61, 18, 1, 218, 235, 234] => "buy",
-            [20, 22, 86, 123, 198, 28, 219, 132] => "collect_creator_fee",  
-            [24, 30, 200, 40, 5, 28, 7, 119] => "create",
-            [234, 102, 194, 203, 150, 72, 62, 229] => "extend_account",
-            [175, 175, 109, 31, 13, 152, 155, 237] => "initialize",
-            [155, 234, 231, 146, 236, 158, 162, 30] => "migrate",
-            [51, 230, 133, 164, 1, 127, 131, 173] => "sell",
-            [254, 148, 255, 112, 207, 142, 170, 165] => "set_creator",
-            [138, 96, 174, 217, 48, 85, 197, 246] => "set_metaplex_creator",
-            [27, 234, 178, 52, 147, 2, 187, 141] => "set_params",
-            [227, 181, 74, 196, 208, 21, 97, 213] => "update_global_authority",
-            _ => {
-                // 打印未知判别器的详细信息用于调试
-                debug!("Unknown PumpFun instruction discriminator: {:?} (hex: {})", 
-                     discriminator, 
-                     hex::encode(discriminator));
-                debug!("This might be a different program's instruction or a new PumpFun instruction");
-                debug!("Instruction data length: {} bytes", instruction.data.len());
-                debug!("Full instruction data (first 32 bytes): {}", 
-                     hex::encode(&instruction.data[..std::cmp::min(32, instruction.data.len())]));
-                return None; // 直接返回None而不是使用"unknown"
+        let instruction_name = match decoder.instruction(&instruction.data) {
+            Ok(idl_instruction) => idl_instruction.name.as_str(),
+            Err(err) => {
+                debug!("程序指令未识别: {}", err);
+                return None;
             }
         };
 
-        debug!("Decoded PumpFun instruction: {}", instruction_name);
-
-        // 使用IDL映射账户
-        match idl.map_accounts(&instruction.accounts, instruction_name) {
-            Ok(mapped_accounts) => {
-                // 解析指令数据
-                let instruction_data = match instruction_name {
-                    "buy" => {
-                        if instruction.data.len() >= 24 {
-                            // buy指令数据：8字节判别器 + 8字节amount + 8字节max_sol_cost
-                            let amount = u64::from_le_bytes(
-                                instruction.data[8..16].try_into().unwrap_or([0; 8])
-                            );
-                            let max_sol_cost = u64::from_le_bytes(
-                                instruction.data[16..24].try_into().unwrap_or([0; 8])
-                            );
-                            serde_json::json!({
-                                "instruction": instruction_name,
-                                "amount": amount,
-                                "max_sol_cost": max_sol_cost
-                            })
-                        } else {
-                            serde_json::json!({
-                                "instruction": instruction_name,
-                                "raw_data": hex::encode(&instruction.data)
-                            })
-                        }
-                    }
-                    "sell" => {
-                        if instruction.data.len() >= 24 {
-                            // sell指令数据：8字节判别器 + 8字节amount + 8字节min_sol_output
-                            let amount = u64::from_le_bytes(
-                                instruction.data[8..16].try_into().unwrap_or([0; 8])
-                            );
-                            let min_sol_output = u64::from_le_bytes(
-                                instruction.data[16..24].try_into().unwrap_or([0; 8])
-                            );
-                            serde_json::json!({
-                                "instruction": instruction_name,
-                                "amount": amount,
-                                "min_sol_output": min_sol_output
-                            })
-                        } else {
-                            serde_json::json!({
-                                "instruction": instruction_name,
-                                "raw_data": hex::encode(&instruction.data)
-                            })
-                        }
-                    }
-                    "create" => {
-                        // create指令包含代币名称、符号、URI等信息
-                        serde_json::json!({
-                            "instruction": instruction_name,
-                            "raw_data": hex::encode(&instruction.data),
-                            "description": "Token creation instruction"
-                        })
-                    }
-                    "collect_creator_fee" => {
-                        serde_json::json!({
-                            "instruction": instruction_name,
-                            "raw_data": hex::encode(&instruction.data),
-                            "description": "Collect creator fees"
-                        })
-                    }
-                    "migrate" => {
-                        serde_json::json!({
-                            "instruction": instruction_name,
-                            "raw_data": hex::encode(&instruction.data),
-                            "description": "Migrate to PumpAMM"
-                        })
-                    }
-                    _ => {
-                        // 其他指令的通用处理
-                        serde_json::json!({
-                            "instruction": instruction_name,
-                            "discriminator": hex::encode(discriminator),
-                            "raw_data": hex::encode(&instruction.data),
-                            "description": format!("PumpFun {} instruction", instruction_name)
-                        })
-                    }
-                };
+        debug!("Decoded {} instruction: {}", instruction.program_id, instruction_name);
 
-                Some(DecodedInstruction {
-                    name: instruction_name.to_string(),
-                    accounts: mapped_accounts,
-                    data: instruction_data,
-                    program_id: instruction.program_id,
-                    parent_program_id,
+        let instruction_data = match decoder.decode_instruction(&instruction.data) {
+            Ok(decoded) => decoded.data,
+            Err(err) => {
+                debug!("{} {} 参数解码失败: {}", instruction.program_id, instruction_name, err);
+                serde_json::json!({
+                    "raw_data": hex::encode(&instruction.data),
+                    "error": err.to_string()
                 })
             }
+        };
+
+        // 使用IDL映射账户
+        match decoder.idl().map_accounts(&instruction.accounts, instruction_name) {
+            Ok(mapped_accounts) => Some(DecodedInstruction {
+                name: instruction_name.to_string(),
+                accounts: mapped_accounts,
+                data: instruction_data,
+                program_id: instruction.program_id,
+                parent_program_id,
+            }),
             Err(err) => {
-                error!("Failed to map accounts for PumpFun instruction '{}': {:?}", instruction_name, err);
+                error!("Failed to map accounts for {} instruction '{}': {:?}", instruction.program_id, instruction_name, err);
                 error!("Instruction has {} accounts", instruction.accounts.len());
                 for (i, account) in instruction.accounts.iter().enumerate() {
                     debug!("  Account[{}]: {} (signer: {}, writable: {})", 
@@ -446,6 +566,11 @@ impl IdlTransactionProcessor {
         }
     }
 
+    /// SPL Token 或 Token-2022 程序
+    pub fn is_token_program(&self, program_id: &Pubkey) -> bool {
+        *program_id == self.token_program_id || *program_id == self.token_2022_program_id
+    }
+
     /// 解码Token程序指令
     fn decode_token_instruction(
         &self,
@@ -453,6 +578,10 @@ impl IdlTransactionProcessor {
         parent_program_id: Option<Pubkey>,
         idl: &Idl,
     ) -> Option<DecodedInstruction> {
+        if instruction.program_id == self.token_2022_program_id {
+            return self.decode_token_2022_instruction(instruction, parent_program_id, idl);
+        }
+
         // 使用spl-token库解析指令
         if let Ok(token_instruction) = spl_token::instruction::TokenInstruction::unpack(&instruction.data) {
             let instruction_name = self.get_token_instruction_name(&token_instruction);
@@ -474,6 +603,123 @@ impl IdlTransactionProcessor {
         None
     }
 
+    /// 解码Token-2022程序指令
+    ///
+    /// 基础指令与 SPL Token 同名同布局；扩展指令不在 IDL 中，账户按序号命名，
+    /// 第一个账户为目标 mint。
+    fn decode_token_2022_instruction(
+        &self,
+        instruction: &Instruction,
+        parent_program_id: Option<Pubkey>,
+        idl: &Idl,
+    ) -> Option<DecodedInstruction> {
+        use spl_token_2022::instruction::TokenInstruction;
+
+        let token_instruction = TokenInstruction::unpack(&instruction.data).ok()?;
+        let instruction_name = self.get_token_2022_instruction_name(&token_instruction);
+
+        let mut data = serde_json::json!({
+            "instruction_type": format!("{:?}", token_instruction),
+            "raw_data": bs58::encode(&instruction.data).into_string()
+        });
+        if let Some(extension) = self.decode_token_2022_extension(&token_instruction, &instruction.data) {
+            data["extension"] = extension;
+        }
+
+        let accounts = idl.map_accounts(&instruction.accounts, &instruction_name).unwrap_or_else(|_| {
+            instruction.accounts.iter().enumerate().map(|(index, account)| AccountMetadata {
+                name: format!("account_{}", index),
+                pubkey: account.pubkey,
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+                docs: None,
+            }).collect()
+        });
+
+        Some(DecodedInstruction {
+            name: instruction_name,
+            accounts,
+            data,
+            program_id: instruction.program_id,
+            parent_program_id,
+        })
+    }
+
+    /// 扩展指令的子指令内容，转账费率会影响实际到账数量，单独解出
+    fn decode_token_2022_extension(
+        &self,
+        instruction: &spl_token_2022::instruction::TokenInstruction,
+        data: &[u8],
+    ) -> Option<serde_json::Value> {
+        use spl_token_2022::extension::transfer_fee::instruction::TransferFeeInstruction;
+        use spl_token_2022::instruction::TokenInstruction;
+
+        let extension_data = data.get(1..)?;
+        match instruction {
+            TokenInstruction::TransferFeeExtension => match TransferFeeInstruction::unpack(extension_data).ok()? {
+                TransferFeeInstruction::InitializeTransferFeeConfig { transfer_fee_basis_points, maximum_fee, .. } => {
+                    Some(serde_json::json!({
+                        "name": "initializeTransferFeeConfig",
+                        "transfer_fee_basis_points": transfer_fee_basis_points,
+                        "maximum_fee": maximum_fee,
+                    }))
+                }
+                other => Some(serde_json::json!({ "name": format!("{:?}", other) })),
+            },
+            _ if instruction_is_extension(instruction) => {
+                Some(serde_json::json!({ "sub_instruction": extension_data.first() }))
+            }
+            _ => None,
+        }
+    }
+
+    /// 获取Token-2022指令名称，扩展指令以 `Extension` 结尾
+    #[allow(deprecated)]
+    fn get_token_2022_instruction_name(&self, instruction: &spl_token_2022::instruction::TokenInstruction) -> String {
+        use spl_token_2022::instruction::TokenInstruction;
+
+        match instruction {
+            TokenInstruction::InitializeMint { .. } => "initializeMint",
+            TokenInstruction::InitializeMint2 { .. } => "initializeMint2",
+            TokenInstruction::InitializeAccount => "initializeAccount",
+            TokenInstruction::InitializeAccount2 { .. } => "initializeAccount2",
+            TokenInstruction::InitializeAccount3 { .. } => "initializeAccount3",
+            TokenInstruction::Transfer { .. } => "transfer",
+            TokenInstruction::Approve { .. } => "approve",
+            TokenInstruction::Revoke => "revoke",
+            TokenInstruction::SetAuthority { .. } => "setAuthority",
+            TokenInstruction::MintTo { .. } => "mintTo",
+            TokenInstruction::Burn { .. } => "burn",
+            TokenInstruction::CloseAccount => "closeAccount",
+            TokenInstruction::FreezeAccount => "freezeAccount",
+            TokenInstruction::ThawAccount => "thawAccount",
+            TokenInstruction::TransferChecked { .. } => "transferChecked",
+            TokenInstruction::ApproveChecked { .. } => "approveChecked",
+            TokenInstruction::MintToChecked { .. } => "mintToChecked",
+            TokenInstruction::BurnChecked { .. } => "burnChecked",
+            TokenInstruction::SyncNative => "syncNative",
+            TokenInstruction::GetAccountDataSize { .. } => "getAccountDataSize",
+            TokenInstruction::InitializeImmutableOwner => "initializeImmutableOwner",
+            TokenInstruction::InitializeMintCloseAuthority { .. } => "initializeMintCloseAuthority",
+            TokenInstruction::TransferFeeExtension => "transferFeeExtension",
+            TokenInstruction::ConfidentialTransferExtension => "confidentialTransferExtension",
+            TokenInstruction::DefaultAccountStateExtension => "defaultAccountStateExtension",
+            TokenInstruction::MemoTransferExtension => "memoTransferExtension",
+            TokenInstruction::InitializeNonTransferableMint => "initializeNonTransferableMint",
+            TokenInstruction::InterestBearingMintExtension => "interestBearingMintExtension",
+            TokenInstruction::CpiGuardExtension => "cpiGuardExtension",
+            TokenInstruction::InitializePermanentDelegate { .. } => "initializePermanentDelegate",
+            TokenInstruction::TransferHookExtension => "transferHookExtension",
+            TokenInstruction::ConfidentialTransferFeeExtension => "confidentialTransferFeeExtension",
+            TokenInstruction::MetadataPointerExtension => "metadataPointerExtension",
+            TokenInstruction::GroupPointerExtension => "groupPointerExtension",
+            TokenInstruction::GroupMemberPointerExtension => "groupMemberPointerExtension",
+            TokenInstruction::ConfidentialMintBurnExtension => "confidentialMintBurnExtension",
+            _ => "unknown",
+        }
+        .to_string()
+    }
+
     /// 获取Token指令名称
     fn get_token_instruction_name(&self, instruction: &spl_token::instruction::TokenInstruction) -> String {
         match instruction {
@@ -505,7 +751,7 @@ impl IdlTransactionProcessor {
         // 查找initializeMint2指令 - 这是PumpFun代币创建的关键指令
         let has_mint_init = parsed_tx.compiled_instructions.iter()
             .chain(parsed_tx.inner_instructions.iter())
-            .any(|instr| instr.name == "initializeMint2");
+            .any(|instr| self.is_token_program(&instr.program_id) && instr.name == "initializeMint2");
 
         // 同时检查是否包含PumpFun程序的调用
         let has_pumpfun_call = parsed_tx.compiled_instructions.iter()
@@ -515,61 +761,57 @@ impl IdlTransactionProcessor {
             debug!("🚀 检测到PumpFun代币创建");
             
             // 提取mint地址和基本信息
-            let mint = self.extract_mint_from_instructions(&parsed_tx.compiled_instructions, &parsed_tx.inner_instructions);
-            let (sol_amount, token_amount) = self.extract_token_creation_amounts(parsed_tx);
-            
-            let creator_wallet = self.extract_pumpfun_creator(parsed_tx);
-            debug!("🔍 PumpFun创建交易 - 提取的创建者钱包: {:?}", creator_wallet);
-            debug!("🔍 PumpFun创建交易 - 提取的代币地址: {:?}", mint);
-            let timestamp = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs() as i64;
-            let raw_data = self.build_raw_event_data(parsed_tx, signature, slot);
+            let mint = self.extract_mint_from_instructions(&parsed_tx.compiled_instructions, &parsed_tx.inner_instructions)?;
+            let create_event = parsed_tx.create_event(&mint);
+            let args = parsed_tx.compiled_instructions.iter()
+                .find(|instr| instr.program_id == self.pumpfun_program_id && is_create_instruction(&instr.name))
+                .and_then(|create| PumpFunCreateArgs::from_decoded(&create.data));
+            // CreateEvent 是链上执行结果，优先于指令参数
+            let creator = match (create_event, &args) {
+                (Some(event), _) => event.creator,
+                (None, Some(args)) => args.creator,
+                (None, None) => self.extract_pumpfun_creator(parsed_tx)?,
+            };
+            let metadata = create_event
+                .map(|event| event.metadata())
+                .or_else(|| args.as_ref().map(PumpFunCreateArgs::metadata));
+            // 开发者买入取实际成交，缺少事件时退回指令上限
+            let dev_buy = parsed_tx
+                .trade_event(&mint, TradeDirection::Buy)
+                .map(|trade| trade.fill())
+                .or_else(|| self.extract_dev_buy(&parsed_tx.compiled_instructions, &mint));
+            debug!("🔍 PumpFun创建交易 - 提取的创建者钱包: {}", creator);
+            debug!("🔍 PumpFun创建交易 - 提取的代币地址: {}", mint);
+            let timestamp = create_event.map(|event| event.timestamp).unwrap_or_else(|| {
+                std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs() as i64
+            });
+            let mut raw_data = self.build_raw_event_data(parsed_tx, signature, slot);
+            raw_data["token_extensions"] = serde_json::json!(parsed_tx.mint_extensions(&mint));
             
             return Some(TokenEvent {
                 signature: signature.to_string(),
                 slot,
-                mint,
-                transaction_type: TransactionType::TokenCreation,
-                detection_method: "pumpfun_create".to_string(),
+                kind: ProtocolEvent::PumpFunCreate { mint, creator, metadata, dev_buy },
                 program_logs: parsed_tx.meta.log_messages.clone(),
                 account_keys: self.extract_account_keys(&parsed_tx.compiled_instructions),
-                sol_amount,
-                token_amount,
-                creator_wallet,
                 timestamp: Some(timestamp),
+                reserves: parsed_tx.latest_reserves(&mint),
                 raw_data: Some(raw_data),
                 block_height: Some(slot),
+                unconfirmed: false,
+                token_program: parsed_tx.mint_token_program(&mint),
             });
         }
 
         None
     }
 
-    /// 提取代币创建相关的金额信息
-    fn extract_token_creation_amounts(&self, parsed_tx: &ParsedConfirmedTransaction) -> (Option<u64>, Option<u64>) {
-        // 从交易的余额变化中推断SOL和代币数量
-        let pre_balances = &parsed_tx.meta.pre_balances;
-        let post_balances = &parsed_tx.meta.post_balances;
-        
-        if pre_balances.len() == post_balances.len() && pre_balances.len() > 0 {
-            // 计算第一个账户的余额变化（通常是发起者账户）
-            let balance_diff = if post_balances[0] < pre_balances[0] {
-                Some(pre_balances[0] - post_balances[0])
-            } else {
-                None
-            };
-            
-            return (balance_diff, Some(1000000000)); // PumpFun通常创建10亿代币
-        }
-        
-        (None, None)
-    }
-
     /// 提取账户密钥
-    fn extract_account_keys(&self, instructions: &[DecodedInstruction]) -> Vec<String> {
+    fn extract_account_keys(&self, instructions: &[DecodedInstruction]) -> Vec<Pubkey> {
         let mut account_keys = Vec::new();
         for instruction in instructions {
             for account in &instruction.accounts {
-                account_keys.push(account.pubkey.to_string());
+                account_keys.push(account.pubkey);
             }
         }
         account_keys.sort();
@@ -578,12 +820,12 @@ impl IdlTransactionProcessor {
     }
 
     /// 提取创建者/交易者钱包地址 (通用方法，保留作为后备)
-    fn extract_creator_wallet(&self, parsed_tx: &ParsedConfirmedTransaction) -> Option<String> {
+    fn extract_creator_wallet(&self, parsed_tx: &ParsedConfirmedTransaction) -> Option<Pubkey> {
         // 通用的创建者地址提取逻辑（后备方案）
         for instruction in &parsed_tx.compiled_instructions {
             for account in &instruction.accounts {
                 if account.is_signer {
-                    return Some(account.pubkey.to_string());
+                    return Some(account.pubkey);
                 }
             }
         }
@@ -591,20 +833,19 @@ impl IdlTransactionProcessor {
     }
 
     /// 提取PumpFun代币创建交易中的创建者钱包地址
-    fn extract_pumpfun_creator(&self, parsed_tx: &ParsedConfirmedTransaction) -> Option<String> {
+    fn extract_pumpfun_creator(&self, parsed_tx: &ParsedConfirmedTransaction) -> Option<Pubkey> {
         debug!("🔍 extract_pumpfun_creator: 开始提取创建者地址");
         
         // 从PumpFun create指令中提取创建者地址
         for instruction in &parsed_tx.compiled_instructions {
-            if instruction.program_id == self.pumpfun_program_id && instruction.name == "create" {
+            if instruction.program_id == self.pumpfun_program_id && is_create_instruction(&instruction.name) {
                 debug!("🔍 extract_pumpfun_creator: 找到create指令");
                 
                 // 根据IDL，PumpFun create指令中的创建者是"user"账户（是签名者）
                 for account in &instruction.accounts {
                     if account.name == "user" && account.is_signer {
-                        let creator_addr = account.pubkey.to_string();
-                        debug!("🔍 extract_pumpfun_creator: 从create指令的user账户找到创建者: {}", creator_addr);
-                        return Some(creator_addr);
+                        debug!("🔍 extract_pumpfun_creator: 从create指令的user账户找到创建者: {}", account.pubkey);
+                        return Some(account.pubkey);
                     }
                 }
                 
@@ -612,18 +853,14 @@ impl IdlTransactionProcessor {
                 let mint_address = self.extract_mint_from_instructions(&parsed_tx.compiled_instructions, &parsed_tx.inner_instructions);
                 for account in &instruction.accounts {
                     if account.is_signer {
-                        let account_addr = account.pubkey.to_string();
-                        
                         // 排除mint地址（mint在create指令中也是签名者）
-                        if let Some(ref mint_addr) = mint_address {
-                            if account_addr == *mint_addr {
-                                debug!("🔍 extract_pumpfun_creator: 跳过mint地址签名者: {}", account_addr);
-                                continue;
-                            }
+                        if mint_address == Some(account.pubkey) {
+                            debug!("🔍 extract_pumpfun_creator: 跳过mint地址签名者: {}", account.pubkey);
+                            continue;
                         }
                         
-                        debug!("🔍 extract_pumpfun_creator: 从create指令的签名者找到创建者: {}", account_addr);
-                        return Some(account_addr);
+                        debug!("🔍 extract_pumpfun_creator: 从create指令的签名者找到创建者: {}", account.pubkey);
+                        return Some(account.pubkey);
                     }
                 }
             }
@@ -635,7 +872,7 @@ impl IdlTransactionProcessor {
     }
 
     /// 提取PumpFun买卖交易中的交易者钱包地址  
-    fn extract_pumpfun_trader(&self, parsed_tx: &ParsedConfirmedTransaction) -> Option<String> {
+    fn extract_pumpfun_trader(&self, parsed_tx: &ParsedConfirmedTransaction) -> Option<Pubkey> {
         // 在PumpFun买卖交易中，交易者是buy/sell指令的签名者
         for instruction in &parsed_tx.compiled_instructions {
             if instruction.program_id == self.pumpfun_program_id && 
@@ -643,7 +880,7 @@ impl IdlTransactionProcessor {
                 // 在买卖指令中查找签名者（交易者）
                 for account in &instruction.accounts {
                     if account.is_signer {
-                        return Some(account.pubkey.to_string());
+                        return Some(account.pubkey);
                     }
                 }
             }
@@ -670,67 +907,107 @@ impl IdlTransactionProcessor {
         })
     }
 
+    /// 检测曲线迁移：`migrate` 可能由迁移程序通过 CPI 调用，同时查找内部指令
+    ///
+    /// 目标池优先取 `CompletePumpAmmMigrationEvent`，没有事件时取指令的 `pool` 账户。
+    fn detect_migration(&self, parsed_tx: &ParsedConfirmedTransaction, signature: &str, slot: u64) -> Option<TokenEvent> {
+        let migrate = parsed_tx.compiled_instructions.iter()
+            .chain(parsed_tx.inner_instructions.iter())
+            .find(|instr| instr.program_id == self.pumpfun_program_id && instr.name == "migrate");
+        let migration_event = parsed_tx.migration_event();
+        if migrate.is_none() && migration_event.is_none() {
+            return None;
+        }
+
+        let mint = migration_event.map(|event| event.mint).or_else(|| migrate.and_then(|instr| account_by_name(instr, "mint")))?;
+        let pool = migration_event.map(|event| event.pool).or_else(|| migrate.and_then(|instr| account_by_name(instr, "pool")));
+        let timestamp = migration_event.map(|event| event.timestamp).unwrap_or_else(|| {
+            std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs() as i64
+        });
+        debug!("🎓 检测到PumpFun迁移: {} -> {:?}", mint, pool);
+
+        let mut raw_data = self.build_raw_event_data(parsed_tx, signature, slot);
+        if let Some(event) = migration_event {
+            raw_data["migration"] = serde_json::json!({
+                "mint_amount": event.mint_amount,
+                "sol_amount": event.sol_amount,
+                "pool_migration_fee": event.pool_migration_fee,
+            });
+        }
+
+        Some(TokenEvent {
+            signature: signature.to_string(),
+            slot,
+            kind: ProtocolEvent::Migration { protocol: Protocol::PumpFun, mint, pool },
+            program_logs: parsed_tx.meta.log_messages.clone(),
+            account_keys: self.extract_account_keys(&parsed_tx.compiled_instructions),
+            timestamp: Some(timestamp),
+            reserves: None,
+            raw_data: Some(raw_data),
+            block_height: Some(slot),
+            unconfirmed: false,
+            token_program: None,
+        })
+    }
+
     /// 检测买卖交易 - 简化版本，只关注核心交易类型
     fn detect_buy_sell_transactions(&self, parsed_tx: &ParsedConfirmedTransaction, signature: &str, slot: u64) -> Option<TokenEvent> {
         // 只查找编译指令中的buy/sell，忽略其他指令类型
         for instruction in &parsed_tx.compiled_instructions {
-            if instruction.program_id == self.pumpfun_program_id {
-                match instruction.name.as_str() {
-                    "buy" => {
-                        debug!("💰 检测到BUY交易");
-                        let mint = self.extract_mint_from_accounts(&instruction.accounts);
-                        let (sol_amount, token_amount) = self.extract_buy_sell_amounts(&instruction.data, true);
-                        
-                        let creator_wallet = self.extract_pumpfun_trader(parsed_tx);
-                        let timestamp = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs() as i64;
-                        let raw_data = self.build_raw_event_data(parsed_tx, signature, slot);
-                        
-                        return Some(TokenEvent {
-                            signature: signature.to_string(),
-                            slot,
-                            mint,
-                            transaction_type: TransactionType::Buy,
-                            detection_method: "pumpfun_buy".to_string(),
-                            program_logs: parsed_tx.meta.log_messages.clone(),
-                            account_keys: self.extract_account_keys(&parsed_tx.compiled_instructions),
-                            sol_amount,
-                            token_amount,
-                            creator_wallet,
-                            timestamp: Some(timestamp),
-                            raw_data: Some(raw_data),
-                            block_height: Some(slot),
-                        });
-                    }
-                    "sell" => {
-                        debug!("💸 检测到SELL交易");
-                        let mint = self.extract_mint_from_accounts(&instruction.accounts);
-                        let (sol_amount, token_amount) = self.extract_buy_sell_amounts(&instruction.data, false);
-                        
-                        let creator_wallet = self.extract_pumpfun_trader(parsed_tx);
-                        let timestamp = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs() as i64;
-                        let raw_data = self.build_raw_event_data(parsed_tx, signature, slot);
-                        
-                        return Some(TokenEvent {
-                            signature: signature.to_string(),
-                            slot,
-                            mint,
-                            transaction_type: TransactionType::Sell,
-                            detection_method: "pumpfun_sell".to_string(),
-                            program_logs: parsed_tx.meta.log_messages.clone(),
-                            account_keys: self.extract_account_keys(&parsed_tx.compiled_instructions),
-                            sol_amount,
-                            token_amount,
-                            creator_wallet,
-                            timestamp: Some(timestamp),
-                            raw_data: Some(raw_data),
-                            block_height: Some(slot),
-                        });
-                    }
-                    _ => {
-                        // 静默忽略其他PumpFun指令类型
-                    }
-                }
+            if instruction.program_id != self.pumpfun_program_id {
+                continue;
             }
+            let direction = match instruction.name.as_str() {
+                "buy" => {
+                    debug!("💰 检测到BUY交易");
+                    TradeDirection::Buy
+                }
+                "sell" => {
+                    debug!("💸 检测到SELL交易");
+                    TradeDirection::Sell
+                }
+                // 静默忽略其他PumpFun指令类型
+                _ => continue,
+            };
+
+            let Some(mint) = self.extract_mint_from_accounts(&instruction.accounts) else {
+                continue;
+            };
+
+            // TradeEvent 给出实际成交和成交后储备；没有事件时退回指令中的上下限
+            let trade_event = parsed_tx.trade_event(&mint, direction);
+            let (trader, sol_amount, token_amount, timestamp) = match trade_event {
+                Some(trade) => (trade.user, trade.sol_amount, trade.token_amount, trade.timestamp),
+                None => {
+                    let (Some(sol_amount), Some(token_amount)) =
+                        self.extract_buy_sell_amounts(&instruction.data, direction == TradeDirection::Buy)
+                    else {
+                        continue;
+                    };
+                    let Some(trader) = self.extract_pumpfun_trader(parsed_tx) else {
+                        continue;
+                    };
+                    let timestamp = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs() as i64;
+                    (trader, sol_amount, token_amount, timestamp)
+                }
+            };
+            let mut raw_data = self.build_raw_event_data(parsed_tx, signature, slot);
+            // 这笔买入使曲线售罄，之后只能在 AMM 上交易
+            raw_data["curve_complete"] = serde_json::json!(parsed_tx.curve_completed(&mint));
+
+            return Some(TokenEvent {
+                signature: signature.to_string(),
+                slot,
+                kind: ProtocolEvent::PumpFunTrade { mint, trader, direction, sol_amount, token_amount },
+                program_logs: parsed_tx.meta.log_messages.clone(),
+                account_keys: self.extract_account_keys(&parsed_tx.compiled_instructions),
+                timestamp: Some(timestamp),
+                reserves: trade_event.map(|trade| trade.reserves()),
+                raw_data: Some(raw_data),
+                block_height: Some(slot),
+                unconfirmed: false,
+                token_program: account_by_name(instruction, "token_program"),
+            });
         }
 
         None
@@ -760,7 +1037,7 @@ impl IdlTransactionProcessor {
     }
 
     /// 从指令中提取mint地址
-    fn extract_mint_from_instructions(&self, compiled: &[DecodedInstruction], inner: &[DecodedInstruction]) -> Option<String> {
+    fn extract_mint_from_instructions(&self, compiled: &[DecodedInstruction], inner: &[DecodedInstruction]) -> Option<Pubkey> {
         debug!("🔍 extract_mint_from_instructions: 开始提取mint地址");
         
         for (idx, instruction) in compiled.iter().chain(inner.iter()).enumerate() {
@@ -776,9 +1053,8 @@ impl IdlTransactionProcessor {
                 
                 // mint通常是第一个账户
                 if let Some(account) = instruction.accounts.first() {
-                    let mint_addr = account.pubkey.to_string();
-                    debug!("🔍 extract_mint_from_instructions: 返回mint地址: {}", mint_addr);
-                    return Some(mint_addr);
+                    debug!("🔍 extract_mint_from_instructions: 返回mint地址: {}", account.pubkey);
+                    return Some(account.pubkey);
                 }
             }
         }
@@ -788,19 +1064,19 @@ impl IdlTransactionProcessor {
     }
 
     /// 从账户中提取mint地址
-    fn extract_mint_from_accounts(&self, accounts: &[AccountMetadata]) -> Option<String> {
+    fn extract_mint_from_accounts(&self, accounts: &[AccountMetadata]) -> Option<Pubkey> {
         // 查找名为"mint"的账户
         for account in accounts {
             if account.name.to_lowercase().contains("mint") {
-                return Some(account.pubkey.to_string());
+                return Some(account.pubkey);
             }
         }
         
         // 如果没找到，返回第一个非程序账户
         for account in accounts {
             let addr_str = account.pubkey.to_string();
-            if addr_str != PUMPFUN_PROGRAM_ID && addr_str != TOKEN_PROGRAM_ID {
-                return Some(addr_str);
+            if addr_str != PUMPFUN_PROGRAM_ID && addr_str != TOKEN_PROGRAM_ID && addr_str != TOKEN_2022_PROGRAM_ID {
+                return Some(account.pubkey);
             }
         }
         
@@ -961,11 +1237,228 @@ pub struct ParsedConfirmedTransaction {
     pub signature: String,
     pub compiled_instructions: Vec<DecodedInstruction>,
     pub inner_instructions: Vec<DecodedInstruction>,
+    /// 内部指令中解码出的 PumpFun 事件，按出现顺序
+    pub events: Vec<PumpFunCpiEvent>,
     pub meta: TransactionStatusMeta,
 }
 
+impl ParsedConfirmedTransaction {
+    fn create_event(&self, mint: &Pubkey) -> Option<&PumpFunCreateEvent> {
+        self.events.iter().find_map(|event| match event {
+            PumpFunCpiEvent::Create(create) if create.mint == *mint => Some(create),
+            _ => None,
+        })
+    }
+
+    /// 该mint在指定方向上的第一笔成交
+    fn trade_event(&self, mint: &Pubkey, direction: TradeDirection) -> Option<&PumpFunTradeEvent> {
+        self.events.iter().find_map(|event| match event {
+            PumpFunCpiEvent::Trade(trade) if trade.mint == *mint && trade.direction() == direction => Some(trade),
+            _ => None,
+        })
+    }
+
+    /// 该mint最后一笔成交后的储备，没有成交时取创建时的初始储备
+    fn latest_reserves(&self, mint: &Pubkey) -> Option<CurveReserves> {
+        self.events.iter().rev().find_map(|event| match event {
+            PumpFunCpiEvent::Trade(trade) if trade.mint == *mint => Some(trade.reserves()),
+            PumpFunCpiEvent::Create(create) if create.mint == *mint => Some(create.reserves()),
+            _ => None,
+        })
+    }
+
+    fn migration_event(&self) -> Option<&PumpFunMigrationEvent> {
+        self.events.iter().find_map(|event| match event {
+            PumpFunCpiEvent::Migration(migration) => Some(migration),
+            _ => None,
+        })
+    }
+
+    /// 交易中是否发出了该mint的 `CompleteEvent`
+    fn curve_completed(&self, mint: &Pubkey) -> bool {
+        self.events.iter().any(|event| matches!(event, PumpFunCpiEvent::Complete(complete) if complete.mint == *mint))
+    }
+
+    /// 该mint的Token程序指令（第一个账户为mint），按执行顺序
+    fn mint_instructions<'a>(&'a self, mint: &'a Pubkey) -> impl Iterator<Item = &'a DecodedInstruction> + 'a {
+        let token_program = Pubkey::from_str_const(TOKEN_PROGRAM_ID);
+        let token_2022_program = Pubkey::from_str_const(TOKEN_2022_PROGRAM_ID);
+        self.compiled_instructions
+            .iter()
+            .chain(self.inner_instructions.iter())
+            .filter(move |instr| instr.program_id == token_program || instr.program_id == token_2022_program)
+            .filter(move |instr| instr.accounts.first().map(|account| account.pubkey) == Some(*mint))
+    }
+
+    /// 初始化该mint的Token程序
+    fn mint_token_program(&self, mint: &Pubkey) -> Option<Pubkey> {
+        self.mint_instructions(mint)
+            .find(|instr| instr.name == "initializeMint2" || instr.name == "initializeMint")
+            .map(|instr| instr.program_id)
+    }
+
+    /// 创建时为该mint启用的Token-2022扩展
+    fn mint_extensions(&self, mint: &Pubkey) -> Vec<String> {
+        self.mint_instructions(mint)
+            .filter(|instr| {
+                instr.data.get("extension").is_some()
+                    || matches!(
+                        instr.name.as_str(),
+                        "initializeMintCloseAuthority" | "initializeNonTransferableMint" | "initializePermanentDelegate"
+                    )
+            })
+            .map(|instr| instr.name.clone())
+            .collect()
+    }
+}
+
+/// PumpFun 创建指令：`create`（SPL Token）或 `create_v2`（Token-2022）
+fn is_create_instruction(name: &str) -> bool {
+    matches!(name, "create" | "create_v2")
+}
+
+/// 按 IDL 账户名查找指令账户
+fn account_by_name(instruction: &DecodedInstruction, name: &str) -> Option<Pubkey> {
+    instruction.accounts.iter().find(|account| account.name == name).map(|account| account.pubkey)
+}
+
+/// Token-2022 扩展指令（对 mint 或代币账户启用扩展）
+fn instruction_is_extension(instruction: &spl_token_2022::instruction::TokenInstruction) -> bool {
+    use spl_token_2022::instruction::TokenInstruction;
+
+    matches!(
+        instruction,
+        TokenInstruction::TransferFeeExtension
+            | TokenInstruction::ConfidentialTransferExtension
+            | TokenInstruction::DefaultAccountStateExtension
+            | TokenInstruction::MemoTransferExtension
+            | TokenInstruction::InterestBearingMintExtension
+            | TokenInstruction::CpiGuardExtension
+            | TokenInstruction::TransferHookExtension
+            | TokenInstruction::ConfidentialTransferFeeExtension
+            | TokenInstruction::MetadataPointerExtension
+            | TokenInstruction::GroupPointerExtension
+            | TokenInstruction::GroupMemberPointerExtension
+            | TokenInstruction::ConfidentialMintBurnExtension
+    )
+}
+
 impl Default for IdlTransactionProcessor {
     fn default() -> Self {
         Self::new().expect("Failed to create IdlTransactionProcessor")
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::message::{Message, MessageHeader};
+    use std::str::FromStr;
+
+    #[test]
+    fn test_decode_token_2022_mint_with_extensions() {
+        let processor = IdlTransactionProcessor::new().unwrap();
+        let token_2022 = Pubkey::from_str(TOKEN_2022_PROGRAM_ID).unwrap();
+        let mint = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+
+        let transfer_fee = spl_token_2022::extension::transfer_fee::instruction::initialize_transfer_fee_config(
+            &token_2022, &mint, Some(&authority), Some(&authority), 250, 1_000_000,
+        )
+        .unwrap();
+        let initialize_mint =
+            spl_token_2022::instruction::initialize_mint2(&token_2022, &mint, &authority, None, 6).unwrap();
+
+        let decode = |instruction: Instruction| {
+            processor
+                .decode_instruction(&TransactionInstructionWithParent { instruction, parent_program_id: None })
+                .unwrap()
+        };
+        let transfer_fee = decode(transfer_fee);
+        assert_eq!(transfer_fee.name, "transferFeeExtension");
+        assert_eq!(transfer_fee.accounts[0].pubkey, mint);
+        assert_eq!(transfer_fee.data["extension"]["transfer_fee_basis_points"], 250);
+        assert_eq!(transfer_fee.data["extension"]["maximum_fee"], 1_000_000);
+
+        let initialize_mint = decode(initialize_mint);
+        assert_eq!(initialize_mint.name, "initializeMint2");
+        assert_eq!(initialize_mint.program_id, token_2022);
+
+        let parsed = ParsedConfirmedTransaction {
+            slot: 1,
+            signature: String::new(),
+            compiled_instructions: vec![],
+            inner_instructions: vec![transfer_fee, initialize_mint],
+            events: vec![],
+            meta: TransactionStatusMeta::default(),
+        };
+        assert_eq!(parsed.mint_token_program(&mint), Some(token_2022));
+        assert_eq!(parsed.mint_extensions(&mint), vec!["transferFeeExtension".to_string()]);
+        assert_eq!(parsed.mint_token_program(&Pubkey::new_unique()), None);
+    }
+
+    #[test]
+    fn test_unconfirmed_create_with_dev_buy() {
+        let processor = IdlTransactionProcessor::new().unwrap();
+        let pumpfun = Pubkey::from_str(PUMPFUN_PROGRAM_ID).unwrap();
+
+        let mut account_keys: Vec<Pubkey> = (0..14).map(|_| Pubkey::new_unique()).collect();
+        account_keys.push(pumpfun);
+        let mint = account_keys[1];
+
+        // create 参数中的创建者可以不是签名用户
+        let coin_creator = Pubkey::new_unique();
+        let mut create_data = vec![24, 30, 200, 40, 5, 28, 7, 119];
+        create_data.extend(
+            borsh::to_vec(&("Dog Coin".to_string(), "DOG".to_string(), "https://example.com/dog.json".to_string(), coin_creator))
+                .unwrap(),
+        );
+
+        let mut buy_data = vec![102, 6, 61, 18, 1, 218, 235, 234];
+        buy_data.extend_from_slice(&1_000_000u64.to_le_bytes());
+        buy_data.extend_from_slice(&500_000_000u64.to_le_bytes());
+
+        let message = Message {
+            header: MessageHeader {
+                num_required_signatures: 2,
+                num_readonly_signed_accounts: 0,
+                num_readonly_unsigned_accounts: 1,
+            },
+            account_keys,
+            recent_blockhash: Hash::default(),
+            instructions: vec![
+                CompiledInstruction {
+                    program_id_index: 14,
+                    accounts: vec![1, 2, 3, 4, 5, 6, 7, 0, 8, 9, 10, 11, 12, 13],
+                    data: create_data,
+                },
+                CompiledInstruction {
+                    program_id_index: 14,
+                    accounts: vec![2, 3, 1, 4, 5, 6, 0, 7, 8, 9, 10, 11],
+                    data: buy_data,
+                },
+            ],
+        };
+        let transaction = VersionedTransaction {
+            signatures: vec![Signature::default(); 2],
+            message: VersionedMessage::Legacy(message),
+        };
+
+        let event = processor.process_unconfirmed_transaction(&transaction, 7, None).unwrap();
+        assert!(event.unconfirmed);
+        assert_eq!(
+            event.kind,
+            ProtocolEvent::PumpFunCreate {
+                mint,
+                creator: coin_creator,
+                metadata: Some(TokenMetadata {
+                    name: "Dog Coin".to_string(),
+                    symbol: "DOG".to_string(),
+                    uri: "https://example.com/dog.json".to_string(),
+                }),
+                dev_buy: Some(DevBuy { sol_amount: 500_000_000, token_amount: 1_000_000 }),
+            }
+        );
+    }
+}
@@ -0,0 +1,232 @@
+use borsh::BorshDeserialize;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+
+use crate::constant::accounts::{EVENT_AUTHORITY, PUMPFUN};
+use crate::processors::{CurveReserves, DevBuy, TokenMetadata, TradeDirection};
+
+/// Anchor `emit_cpi!` 自调用指令的前缀（`sha256("anchor:event")[..8]`）
+pub const ANCHOR_EVENT_IX_TAG: [u8; 8] = [228, 69, 165, 46, 81, 203, 154, 29];
+
+/// 事件判别器，取自 `idls/pumpfun_0.1.0.json` 的 events
+const CREATE_EVENT_DISCRIMINATOR: [u8; 8] = [27, 114, 169, 77, 222, 235, 99, 118];
+const TRADE_EVENT_DISCRIMINATOR: [u8; 8] = [189, 219, 127, 211, 78, 230, 97, 238];
+const COMPLETE_EVENT_DISCRIMINATOR: [u8; 8] = [95, 114, 97, 156, 212, 46, 152, 8];
+const MIGRATION_EVENT_DISCRIMINATOR: [u8; 8] = [189, 233, 93, 185, 92, 148, 234, 148];
+
+/// PumpFun `CreateEvent`
+#[derive(Debug, Clone, PartialEq, BorshDeserialize)]
+pub struct PumpFunCreateEvent {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub user: Pubkey,
+    pub creator: Pubkey,
+    pub timestamp: i64,
+    pub virtual_token_reserves: u64,
+    pub virtual_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub token_total_supply: u64,
+}
+
+/// PumpFun `TradeEvent` 的前缀字段
+///
+/// 手续费和交易量统计字段是后续版本追加在末尾的，这里只解码各版本共有的部分。
+#[derive(Debug, Clone, PartialEq, BorshDeserialize)]
+pub struct PumpFunTradeEvent {
+    pub mint: Pubkey,
+    /// 实际成交的SOL（lamports）
+    pub sol_amount: u64,
+    /// 实际成交的代币数量
+    pub token_amount: u64,
+    pub is_buy: bool,
+    pub user: Pubkey,
+    pub timestamp: i64,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub real_token_reserves: u64,
+}
+
+/// PumpFun `CompleteEvent`：买入使曲线售罄，之后曲线不再接受买卖
+#[derive(Debug, Clone, PartialEq, BorshDeserialize)]
+pub struct PumpFunCompleteEvent {
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub timestamp: i64,
+}
+
+/// PumpFun `CompletePumpAmmMigrationEvent`：`migrate` 把流动性迁移到 PumpSwap 池
+#[derive(Debug, Clone, PartialEq, BorshDeserialize)]
+pub struct PumpFunMigrationEvent {
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub mint_amount: u64,
+    pub sol_amount: u64,
+    pub pool_migration_fee: u64,
+    pub bonding_curve: Pubkey,
+    pub timestamp: i64,
+    /// 目标 AMM 池
+    pub pool: Pubkey,
+}
+
+/// 通过事件权限账户自调用发出的 PumpFun 事件
+#[derive(Debug, Clone, PartialEq)]
+pub enum PumpFunCpiEvent {
+    Create(PumpFunCreateEvent),
+    Trade(PumpFunTradeEvent),
+    Complete(PumpFunCompleteEvent),
+    Migration(PumpFunMigrationEvent),
+}
+
+impl PumpFunCpiEvent {
+    /// 解码内部指令；不是 PumpFun 事件自调用或事件类型不关心时返回 None
+    pub fn from_instruction(instruction: &Instruction) -> Option<Self> {
+        if instruction.program_id != PUMPFUN {
+            return None;
+        }
+        // 只有程序自己能以事件权限 PDA 签名，借此排除伪造的事件数据
+        if instruction.accounts.first().map(|account| account.pubkey) != Some(EVENT_AUTHORITY) {
+            return None;
+        }
+        Self::decode(&instruction.data)
+    }
+
+    /// 解码 `标签 + 事件判别器 + Borsh 数据`
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        let payload = data.strip_prefix(&ANCHOR_EVENT_IX_TAG)?;
+        let (discriminator, mut body) = payload.split_first_chunk::<8>()?;
+        match *discriminator {
+            CREATE_EVENT_DISCRIMINATOR => PumpFunCreateEvent::deserialize(&mut body).ok().map(Self::Create),
+            TRADE_EVENT_DISCRIMINATOR => PumpFunTradeEvent::deserialize(&mut body).ok().map(Self::Trade),
+            COMPLETE_EVENT_DISCRIMINATOR => PumpFunCompleteEvent::deserialize(&mut body).ok().map(Self::Complete),
+            MIGRATION_EVENT_DISCRIMINATOR => PumpFunMigrationEvent::deserialize(&mut body).ok().map(Self::Migration),
+            _ => None,
+        }
+    }
+
+    pub fn mint(&self) -> Pubkey {
+        match self {
+            Self::Create(event) => event.mint,
+            Self::Trade(event) => event.mint,
+            Self::Complete(event) => event.mint,
+            Self::Migration(event) => event.mint,
+        }
+    }
+}
+
+impl PumpFunCreateEvent {
+    pub fn metadata(&self) -> TokenMetadata {
+        TokenMetadata {
+            name: self.name.clone(),
+            symbol: self.symbol.clone(),
+            uri: self.uri.clone(),
+        }
+    }
+
+    /// 创建后的初始储备
+    pub fn reserves(&self) -> CurveReserves {
+        CurveReserves {
+            virtual_sol_reserves: self.virtual_sol_reserves,
+            virtual_token_reserves: self.virtual_token_reserves,
+            real_sol_reserves: 0,
+            real_token_reserves: self.real_token_reserves,
+        }
+    }
+}
+
+impl PumpFunTradeEvent {
+    pub fn direction(&self) -> TradeDirection {
+        if self.is_buy {
+            TradeDirection::Buy
+        } else {
+            TradeDirection::Sell
+        }
+    }
+
+    pub fn fill(&self) -> DevBuy {
+        DevBuy {
+            sol_amount: self.sol_amount,
+            token_amount: self.token_amount,
+        }
+    }
+
+    /// 成交后的储备
+    pub fn reserves(&self) -> CurveReserves {
+        CurveReserves {
+            virtual_sol_reserves: self.virtual_sol_reserves,
+            virtual_token_reserves: self.virtual_token_reserves,
+            real_sol_reserves: self.real_sol_reserves,
+            real_token_reserves: self.real_token_reserves,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::instruction::AccountMeta;
+
+    /// 按链上版本编码的完整 TradeEvent，末尾带手续费等后续字段
+    fn trade_event_data(mint: Pubkey, user: Pubkey) -> Vec<u8> {
+        let mut data = ANCHOR_EVENT_IX_TAG.to_vec();
+        data.extend_from_slice(&TRADE_EVENT_DISCRIMINATOR);
+        data.extend(borsh::to_vec(&(mint, 990_000_000u64, 34_000_000_000_000u64, true, user, 1_700_000_000i64)).unwrap());
+        data.extend(borsh::to_vec(&(31_000_000_000u64, 1_039_000_000_000_000u64, 990_000_000u64, 759_000_000_000_000u64)).unwrap());
+        data.extend(borsh::to_vec(&(Pubkey::new_unique(), 95u64, 9_405_000u64)).unwrap());
+        data
+    }
+
+    #[test]
+    fn test_decode_trade_event_ignores_trailing_fields() {
+        let mint = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let instruction = Instruction {
+            program_id: PUMPFUN,
+            accounts: vec![AccountMeta::new_readonly(EVENT_AUTHORITY, true)],
+            data: trade_event_data(mint, user),
+        };
+
+        let Some(PumpFunCpiEvent::Trade(trade)) = PumpFunCpiEvent::from_instruction(&instruction) else {
+            panic!("应解码出 TradeEvent");
+        };
+        assert_eq!(trade.mint, mint);
+        assert_eq!(trade.user, user);
+        assert_eq!(trade.direction(), TradeDirection::Buy);
+        assert_eq!(trade.fill(), DevBuy { sol_amount: 990_000_000, token_amount: 34_000_000_000_000 });
+        assert_eq!(trade.reserves().virtual_sol_reserves, 31_000_000_000);
+        assert_eq!(trade.reserves().real_token_reserves, 759_000_000_000_000);
+    }
+
+    #[test]
+    fn test_rejects_event_without_event_authority() {
+        let instruction = Instruction {
+            program_id: PUMPFUN,
+            accounts: vec![AccountMeta::new_readonly(Pubkey::new_unique(), false)],
+            data: trade_event_data(Pubkey::new_unique(), Pubkey::new_unique()),
+        };
+        assert_eq!(PumpFunCpiEvent::from_instruction(&instruction), None);
+
+        // 普通指令判别器不是事件
+        assert_eq!(PumpFunCpiEvent::decode(&[102, 6, 61, 18, 1, 218, 235, 234, 0, 0]), None);
+    }
+
+    #[test]
+    fn test_decode_migration_event() {
+        let mint = Pubkey::new_unique();
+        let pool = Pubkey::new_unique();
+        let mut data = ANCHOR_EVENT_IX_TAG.to_vec();
+        data.extend_from_slice(&MIGRATION_EVENT_DISCRIMINATOR);
+        data.extend(borsh::to_vec(&(Pubkey::new_unique(), mint, 206_900_000_000_000u64, 84_990_359_054u64, 15_000_001u64)).unwrap());
+        data.extend(borsh::to_vec(&(Pubkey::new_unique(), 1_700_000_000i64, pool)).unwrap());
+
+        let Some(PumpFunCpiEvent::Migration(migration)) = PumpFunCpiEvent::decode(&data) else {
+            panic!("应解码出 CompletePumpAmmMigrationEvent");
+        };
+        assert_eq!(migration.mint, mint);
+        assert_eq!(migration.pool, pool);
+        assert_eq!(migration.sol_amount, 84_990_359_054);
+    }
+}
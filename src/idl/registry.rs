@@ -0,0 +1,267 @@
+use anyhow::{anyhow, Context, Result};
+use log::{info, warn};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+use super::decoder::IdlDecoder;
+use crate::config::{IdlConfig, IdlProgramConfig};
+use crate::constant::accounts::{PUMPFUN, TOKEN_2022_PROGRAM, TOKEN_PROGRAM};
+use crate::processors::instruction_account_mapper::Idl;
+
+/// 编译时内嵌的 IDL
+struct EmbeddedIdl {
+    program_id: Pubkey,
+    version: &'static str,
+    json: &'static str,
+}
+
+/// 同一程序可以内嵌多个版本，未指定版本时取列表中最后一个
+const EMBEDDED_IDLS: &[EmbeddedIdl] = &[
+    EmbeddedIdl {
+        program_id: PUMPFUN,
+        version: "0.1.0",
+        json: include_str!("../../idls/pumpfun_0.1.0.json"),
+    },
+    EmbeddedIdl {
+        program_id: TOKEN_PROGRAM,
+        version: "4.0.0",
+        json: include_str!("../../idls/token_program_idl.json"),
+    },
+    // Token-2022 的基础指令与 SPL Token 布局相同，扩展指令由处理器单独命名
+    EmbeddedIdl {
+        program_id: TOKEN_2022_PROGRAM,
+        version: "4.0.0",
+        json: include_str!("../../idls/token_program_idl.json"),
+    },
+];
+
+static GLOBAL_REGISTRY: OnceLock<Arc<IdlRegistry>> = OnceLock::new();
+
+/// IDL 来源
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdlSource {
+    Embedded,
+    File(PathBuf),
+    /// 运行时通过 `IdlRegistry::register` 注册
+    Registered,
+}
+
+impl fmt::Display for IdlSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Embedded => write!(f, "embedded"),
+            Self::File(path) => write!(f, "{}", path.display()),
+            Self::Registered => write!(f, "registered"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RegisteredIdl {
+    pub version: String,
+    pub source: IdlSource,
+    pub decoder: IdlDecoder,
+}
+
+/// 按程序 id 索引的 IDL 注册表
+///
+/// 默认内容来自编译时内嵌的 IDL，不依赖工作目录；`[idl]` 配置可以替换版本、
+/// 改用文件或注册新的程序。
+#[derive(Debug, Clone, Default)]
+pub struct IdlRegistry {
+    programs: HashMap<Pubkey, RegisteredIdl>,
+}
+
+impl IdlRegistry {
+    /// 只包含内嵌默认 IDL（每个程序取最新版本）
+    pub fn embedded() -> Self {
+        let mut registry = Self::default();
+        for embedded in EMBEDDED_IDLS {
+            let idl = serde_json::from_str(embedded.json).expect("内嵌IDL应当有效");
+            registry.insert(embedded.program_id, idl, embedded.version.to_string(), IdlSource::Embedded);
+        }
+        registry
+    }
+
+    /// 在内嵌默认 IDL 的基础上应用配置
+    ///
+    /// `base_dir` 用于解析相对路径（通常为配置文件所在目录）。必需的条目加载失败时返回错误，
+    /// 其余条目告警后保留内嵌版本。
+    pub fn from_config(config: &IdlConfig, base_dir: &Path) -> Result<Self> {
+        let mut registry = Self::embedded();
+
+        for program in &config.programs {
+            let required = config.require_all || program.required;
+            if let Err(e) = registry.apply(program, base_dir) {
+                if required {
+                    return Err(e.context(format!("必需的IDL加载失败: {}", program.program_id)));
+                }
+                warn!("⚠️ IDL加载失败，继续使用内嵌版本: {:#}", e);
+            }
+        }
+
+        for (program_id, registered) in &registry.programs {
+            info!("📘 IDL {} v{} ({})", program_id, registered.version, registered.source);
+        }
+        Ok(registry)
+    }
+
+    fn apply(&mut self, program: &IdlProgramConfig, base_dir: &Path) -> Result<()> {
+        let program_id: Pubkey = program
+            .program_id
+            .parse()
+            .with_context(|| format!("无效的程序id: {}", program.program_id))?;
+
+        match &program.path {
+            Some(path) => {
+                let path = base_dir.join(path);
+                self.register_file(program_id, &path, program.version.as_deref())
+            }
+            None => self.select_embedded(program_id, program.version.as_deref()),
+        }
+    }
+
+    /// 从文件注册 IDL；指定版本时校验 `metadata.version`
+    pub fn register_file(&mut self, program_id: Pubkey, path: &Path, version: Option<&str>) -> Result<()> {
+        let content = std::fs::read_to_string(path).with_context(|| format!("读取IDL文件失败: {}", path.display()))?;
+        let idl: Idl = serde_json::from_str(&content).with_context(|| format!("解析IDL文件失败: {}", path.display()))?;
+
+        if let Some(version) = version {
+            if idl.metadata.version != version {
+                return Err(anyhow!(
+                    "IDL版本不匹配: {} 为 {}，期望 {}",
+                    path.display(),
+                    idl.metadata.version,
+                    version
+                ));
+            }
+        }
+
+        let version = idl.metadata.version.clone();
+        self.insert(program_id, idl, version, IdlSource::File(path.to_path_buf()));
+        Ok(())
+    }
+
+    /// 选择内嵌的指定版本
+    pub fn select_embedded(&mut self, program_id: Pubkey, version: Option<&str>) -> Result<()> {
+        let embedded = EMBEDDED_IDLS
+            .iter()
+            .filter(|embedded| embedded.program_id == program_id)
+            .rfind(|embedded| version.is_none_or(|version| embedded.version == version))
+            .ok_or_else(|| anyhow!("没有内嵌的IDL: {} (版本 {})", program_id, version.unwrap_or("任意")))?;
+
+        let idl = serde_json::from_str(embedded.json).expect("内嵌IDL应当有效");
+        self.insert(program_id, idl, embedded.version.to_string(), IdlSource::Embedded);
+        Ok(())
+    }
+
+    /// 注册（或替换）程序的 IDL
+    pub fn register(&mut self, program_id: Pubkey, idl: Idl) {
+        let version = idl.metadata.version.clone();
+        self.insert(program_id, idl, version, IdlSource::Registered);
+    }
+
+    fn insert(&mut self, program_id: Pubkey, idl: Idl, version: String, source: IdlSource) {
+        self.programs.insert(
+            program_id,
+            RegisteredIdl {
+                version,
+                source,
+                decoder: IdlDecoder::new(idl),
+            },
+        );
+    }
+
+    pub fn get(&self, program_id: &Pubkey) -> Option<&IdlDecoder> {
+        self.programs.get(program_id).map(|registered| &registered.decoder)
+    }
+
+    pub fn entry(&self, program_id: &Pubkey) -> Option<&RegisteredIdl> {
+        self.programs.get(program_id)
+    }
+
+    pub fn program_ids(&self) -> impl Iterator<Item = &Pubkey> {
+        self.programs.keys()
+    }
+
+    /// 设置进程级注册表，供各事件流内部创建的处理器使用；只能设置一次
+    pub fn install(self) -> Result<()> {
+        GLOBAL_REGISTRY
+            .set(Arc::new(self))
+            .map_err(|_| anyhow!("IDL注册表已经设置"))
+    }
+
+    /// 进程级注册表，未设置时为内嵌默认 IDL
+    pub fn global() -> Arc<IdlRegistry> {
+        GLOBAL_REGISTRY.get_or_init(|| Arc::new(Self::embedded())).clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn program(path: Option<&str>, version: Option<&str>, required: bool) -> IdlProgramConfig {
+        IdlProgramConfig {
+            program_id: PUMPFUN.to_string(),
+            path: path.map(str::to_string),
+            version: version.map(str::to_string),
+            required,
+        }
+    }
+
+    #[test]
+    fn test_embedded_defaults() {
+        let registry = IdlRegistry::embedded();
+        assert!(registry.get(&PUMPFUN).is_some());
+        assert!(registry.get(&TOKEN_PROGRAM).is_some());
+        assert!(registry.get(&TOKEN_2022_PROGRAM).is_some());
+        assert_eq!(registry.entry(&PUMPFUN).unwrap().version, "0.1.0");
+    }
+
+    #[test]
+    fn test_runtime_registration_source() {
+        let mut registry = IdlRegistry::embedded();
+        let idl = registry.get(&PUMPFUN).unwrap().idl().clone();
+        let program_id = Pubkey::new_unique();
+        registry.register(program_id, idl);
+        assert_eq!(registry.entry(&program_id).unwrap().source, IdlSource::Registered);
+        assert_eq!(registry.entry(&PUMPFUN).unwrap().source, IdlSource::Embedded);
+    }
+
+    #[test]
+    fn test_relative_path_and_version_check() {
+        let base_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let config = IdlConfig {
+            require_all: false,
+            programs: vec![program(Some("idls/pumpfun_0.1.0.json"), Some("0.1.0"), true)],
+        };
+        let registry = IdlRegistry::from_config(&config, base_dir).unwrap();
+        assert_eq!(
+            registry.entry(&PUMPFUN).unwrap().source,
+            IdlSource::File(base_dir.join("idls/pumpfun_0.1.0.json"))
+        );
+
+        let config = IdlConfig {
+            require_all: false,
+            programs: vec![program(Some("idls/pumpfun_0.1.0.json"), Some("9.9.9"), true)],
+        };
+        assert!(IdlRegistry::from_config(&config, base_dir).is_err());
+    }
+
+    #[test]
+    fn test_missing_optional_idl_keeps_embedded() {
+        let config = IdlConfig {
+            require_all: false,
+            programs: vec![program(Some("missing.json"), None, false)],
+        };
+        let registry = IdlRegistry::from_config(&config, Path::new("/nonexistent")).unwrap();
+        assert_eq!(registry.entry(&PUMPFUN).unwrap().source, IdlSource::Embedded);
+
+        let config = IdlConfig { require_all: true, ..config };
+        assert!(IdlRegistry::from_config(&config, Path::new("/nonexistent")).is_err());
+    }
+}
@@ -9,16 +9,18 @@ pub mod utils;
 pub mod constant;
 
 // Re-export commonly used types
-pub use config::StreamShyftConfig;
-pub use processors::{TokenEvent, TransactionType, TokenDetector, process_transaction_for_tokens};
-pub use streams::{ShyftStream, LetsbonkStream};
+pub use config::{StreamShyftConfig, StreamShredConfig, StreamPubsubConfig};
+pub use processors::{TokenEvent, TransactionType, ProtocolEvent, Protocol, TradeDirection, DevBuy, CurveReserves, TokenDetector, process_transaction_for_tokens};
+pub use streams::{ShyftStream, LetsbonkStream, ShredStream, MultiProtocolStream, RacingTokenStream, ReplayStream, RpcPubsubStream, AccountPriceStream, TokenEventSource, StreamMetrics};
 pub use strategy::{
     // TokenFilter, FilterCriteria, FilterResult, TokenSniper,
     TradeSignal, TradeSignalType, SignalPriority,
     Position, PositionStatus, TradeRecord,
     StrategyConfig,
     // 新增：优化后的组件
-    OptimizedStrategyManager, OptimizedTokenFilter, SimpleFilterResult
+    OptimizedStrategyManager, OptimizedTokenFilter, SimpleFilterResult,
+    // 事件流到策略的有界管道
+    EventPipeline, PushOutcome,
 };
 pub use executor::{
     ExecutorConfig, ExecutionStrategy, ExecutionResult, TradeParams,
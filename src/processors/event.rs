@@ -0,0 +1,346 @@
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::serialization::{deserialize_option_pubkey, deserialize_pubkey, serialize_option_pubkey, serialize_pubkey};
+
+/// 事件来源协议
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Protocol {
+    PumpFun,
+    RaydiumLaunchpad,
+}
+
+/// 交易方向
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum TradeDirection {
+    Buy,
+    Sell,
+}
+
+/// 创建交易中针对同一 mint 的开发者买入
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DevBuy {
+    /// 买入花费的SOL（lamports）
+    pub sol_amount: u64,
+    /// 买入获得的代币数量
+    pub token_amount: u64,
+}
+
+/// 成交后的曲线/池储备，来自链上事件
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CurveReserves {
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub real_token_reserves: u64,
+}
+
+impl CurveReserves {
+    /// 按虚拟储备计算的价格（lamports / 代币最小单位）
+    pub fn price(&self) -> Option<f64> {
+        if self.virtual_token_reserves == 0 {
+            return None;
+        }
+        Some(self.virtual_sol_reserves as f64 / self.virtual_token_reserves as f64)
+    }
+}
+
+/// Raydium Launchpad 池账户，取自 Initialize 指令的命名账户
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LaunchpadPool {
+    #[serde(serialize_with = "serialize_pubkey", deserialize_with = "deserialize_pubkey")]
+    pub pool_state: Pubkey,
+    #[serde(serialize_with = "serialize_pubkey", deserialize_with = "deserialize_pubkey")]
+    pub base_vault: Pubkey,
+    #[serde(serialize_with = "serialize_pubkey", deserialize_with = "deserialize_pubkey")]
+    pub quote_vault: Pubkey,
+    #[serde(serialize_with = "serialize_pubkey", deserialize_with = "deserialize_pubkey")]
+    pub platform_config: Pubkey,
+}
+
+/// Raydium Launchpad 池状态
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum LaunchpadPoolStatus {
+    /// 募集中，可在曲线上买卖
+    Fund,
+    /// 募集完成，等待迁移
+    Migrate,
+    /// 已迁移到 AMM
+    Trade,
+}
+
+/// Raydium Launchpad 单笔成交的手续费（lamports）
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LaunchpadFees {
+    pub protocol_fee: u64,
+    pub platform_fee: u64,
+    pub share_fee: u64,
+}
+
+impl LaunchpadFees {
+    pub fn total(&self) -> u64 {
+        self.protocol_fee + self.platform_fee + self.share_fee
+    }
+}
+
+/// 创建指令参数中的代币信息
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TokenMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
+/// 按协议区分的代币事件
+///
+/// 取代按 `detection_method` 子串分支：协议、方向、开发者买入都是显式字段。
+/// 已确认交易的金额来自链上事件（实际成交）；未确认交易只能取指令参数
+/// （买入为 SOL 上限，卖出为 SOL 下限）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ProtocolEvent {
+    /// PumpFun create（可能附带开发者买入）
+    #[serde(rename = "pumpfun_create")]
+    PumpFunCreate {
+        #[serde(serialize_with = "serialize_pubkey", deserialize_with = "deserialize_pubkey")]
+        mint: Pubkey,
+        /// create 参数中的创建者（决定 creator_vault），参数无法解码时为签名用户
+        #[serde(serialize_with = "serialize_pubkey", deserialize_with = "deserialize_pubkey")]
+        creator: Pubkey,
+        metadata: Option<TokenMetadata>,
+        dev_buy: Option<DevBuy>,
+    },
+    /// PumpFun 曲线上的买卖
+    #[serde(rename = "pumpfun_trade")]
+    PumpFunTrade {
+        #[serde(serialize_with = "serialize_pubkey", deserialize_with = "deserialize_pubkey")]
+        mint: Pubkey,
+        #[serde(serialize_with = "serialize_pubkey", deserialize_with = "deserialize_pubkey")]
+        trader: Pubkey,
+        direction: TradeDirection,
+        sol_amount: u64,
+        token_amount: u64,
+    },
+    /// Raydium Launchpad initialize（可能附带开发者买入）
+    LaunchpadInitialize {
+        #[serde(serialize_with = "serialize_pubkey", deserialize_with = "deserialize_pubkey")]
+        mint: Pubkey,
+        #[serde(serialize_with = "serialize_pubkey", deserialize_with = "deserialize_pubkey")]
+        creator: Pubkey,
+        metadata: Option<TokenMetadata>,
+        dev_buy: Option<DevBuy>,
+        /// 池和金库地址；无法解析全部账户时为空
+        #[serde(default)]
+        pool: Option<LaunchpadPool>,
+        /// 按 platform_config 识别出的平台名（如 "LetsBonk"）
+        #[serde(default)]
+        platform: Option<String>,
+    },
+    /// Raydium Launchpad 池上的买卖
+    LaunchpadTrade {
+        #[serde(serialize_with = "serialize_pubkey", deserialize_with = "deserialize_pubkey")]
+        mint: Pubkey,
+        #[serde(serialize_with = "serialize_pubkey", deserialize_with = "deserialize_pubkey")]
+        trader: Pubkey,
+        direction: TradeDirection,
+        sol_amount: u64,
+        token_amount: u64,
+        #[serde(default)]
+        platform: Option<String>,
+        /// 成交后的池状态，来自 TradeEvent
+        #[serde(default)]
+        pool_status: Option<LaunchpadPoolStatus>,
+        /// 来自 TradeEvent；只有指令参数时为空
+        #[serde(default)]
+        fees: Option<LaunchpadFees>,
+    },
+    /// 曲线完成后迁移到 AMM
+    Migration {
+        protocol: Protocol,
+        #[serde(serialize_with = "serialize_pubkey", deserialize_with = "deserialize_pubkey")]
+        mint: Pubkey,
+        /// 目标池地址
+        #[serde(serialize_with = "serialize_option_pubkey", deserialize_with = "deserialize_option_pubkey")]
+        pool: Option<Pubkey>,
+    },
+}
+
+impl ProtocolEvent {
+    pub fn protocol(&self) -> Protocol {
+        match self {
+            Self::PumpFunCreate { .. } | Self::PumpFunTrade { .. } => Protocol::PumpFun,
+            Self::LaunchpadInitialize { .. } | Self::LaunchpadTrade { .. } => Protocol::RaydiumLaunchpad,
+            Self::Migration { protocol, .. } => *protocol,
+        }
+    }
+
+    pub fn mint(&self) -> Pubkey {
+        match self {
+            Self::PumpFunCreate { mint, .. }
+            | Self::PumpFunTrade { mint, .. }
+            | Self::LaunchpadInitialize { mint, .. }
+            | Self::LaunchpadTrade { mint, .. }
+            | Self::Migration { mint, .. } => *mint,
+        }
+    }
+
+    pub fn is_creation(&self) -> bool {
+        matches!(self, Self::PumpFunCreate { .. } | Self::LaunchpadInitialize { .. })
+    }
+
+    /// 曲线迁移事件：代币已毕业，之后只能在 AMM 上交易
+    pub fn is_migration(&self) -> bool {
+        matches!(self, Self::Migration { .. })
+    }
+
+    /// 迁移的目标池，非迁移事件或未知时为 None
+    pub fn migration_pool(&self) -> Option<Pubkey> {
+        match self {
+            Self::Migration { pool, .. } => *pool,
+            _ => None,
+        }
+    }
+
+    /// 买卖方向，非交易事件为 None
+    pub fn direction(&self) -> Option<TradeDirection> {
+        match self {
+            Self::PumpFunTrade { direction, .. } | Self::LaunchpadTrade { direction, .. } => Some(*direction),
+            _ => None,
+        }
+    }
+
+    /// 创建者或交易者钱包
+    pub fn wallet(&self) -> Option<Pubkey> {
+        match self {
+            Self::PumpFunCreate { creator, .. } | Self::LaunchpadInitialize { creator, .. } => Some(*creator),
+            Self::PumpFunTrade { trader, .. } | Self::LaunchpadTrade { trader, .. } => Some(*trader),
+            Self::Migration { .. } => None,
+        }
+    }
+
+    pub fn dev_buy(&self) -> Option<DevBuy> {
+        match self {
+            Self::PumpFunCreate { dev_buy, .. } | Self::LaunchpadInitialize { dev_buy, .. } => *dev_buy,
+            _ => None,
+        }
+    }
+
+    pub fn metadata(&self) -> Option<&TokenMetadata> {
+        match self {
+            Self::PumpFunCreate { metadata, .. } | Self::LaunchpadInitialize { metadata, .. } => metadata.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Launchpad 平台名，其他协议为 None
+    pub fn platform(&self) -> Option<&str> {
+        match self {
+            Self::LaunchpadInitialize { platform, .. } | Self::LaunchpadTrade { platform, .. } => platform.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// 交易金额，创建事件取开发者买入
+    pub fn sol_amount(&self) -> Option<u64> {
+        match self {
+            Self::PumpFunTrade { sol_amount, .. } | Self::LaunchpadTrade { sol_amount, .. } => Some(*sol_amount),
+            _ => self.dev_buy().map(|buy| buy.sol_amount),
+        }
+    }
+
+    /// 代币数量，创建事件取开发者买入
+    pub fn token_amount(&self) -> Option<u64> {
+        match self {
+            Self::PumpFunTrade { token_amount, .. } | Self::LaunchpadTrade { token_amount, .. } => Some(*token_amount),
+            _ => self.dev_buy().map(|buy| buy.token_amount),
+        }
+    }
+
+    /// 事件名称，用于日志；与 JSON 的 `event` 标签一致，买卖再细分方向
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::PumpFunCreate { .. } => "pumpfun_create",
+            Self::PumpFunTrade { direction: TradeDirection::Buy, .. } => "pumpfun_buy",
+            Self::PumpFunTrade { direction: TradeDirection::Sell, .. } => "pumpfun_sell",
+            Self::LaunchpadInitialize { .. } => "launchpad_initialize",
+            Self::LaunchpadTrade { direction: TradeDirection::Buy, .. } => "launchpad_buy",
+            Self::LaunchpadTrade { direction: TradeDirection::Sell, .. } => "launchpad_sell",
+            Self::Migration { .. } => "migration",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_form_uses_base58_and_tag() {
+        let mint = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+        let event = ProtocolEvent::PumpFunCreate {
+            mint,
+            creator,
+            metadata: None,
+            dev_buy: Some(DevBuy { sol_amount: 1_000_000_000, token_amount: 35_000_000 }),
+        };
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["event"], "pumpfun_create");
+        assert_eq!(json["event"], event.name());
+        assert_eq!(json["mint"], mint.to_string());
+        assert_eq!(json["creator"], creator.to_string());
+        assert_eq!(json["dev_buy"]["sol_amount"], 1_000_000_000u64);
+
+        let decoded: ProtocolEvent = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded, event);
+    }
+
+    #[test]
+    fn test_accessors() {
+        let mint = Pubkey::new_unique();
+        let trader = Pubkey::new_unique();
+        let sell = ProtocolEvent::LaunchpadTrade {
+            mint,
+            trader,
+            direction: TradeDirection::Sell,
+            sol_amount: 5,
+            token_amount: 100,
+            platform: Some("LetsBonk".to_string()),
+            pool_status: None,
+            fees: None,
+        };
+        assert_eq!(sell.protocol(), Protocol::RaydiumLaunchpad);
+        assert_eq!(sell.direction(), Some(TradeDirection::Sell));
+        assert_eq!(sell.wallet(), Some(trader));
+        assert_eq!(sell.sol_amount(), Some(5));
+        assert!(!sell.is_creation());
+        assert_eq!(sell.name(), "launchpad_sell");
+        assert_eq!(sell.platform(), Some("LetsBonk"));
+
+        let create = ProtocolEvent::LaunchpadInitialize { mint, creator: trader, metadata: None, dev_buy: None, pool: None, platform: None };
+        assert!(create.is_creation());
+        assert!(!create.is_migration());
+        assert_eq!(create.sol_amount(), None);
+        assert_eq!(create.mint(), mint);
+    }
+
+    #[test]
+    fn test_name_matches_json_tag() {
+        let mint = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let buy = ProtocolEvent::PumpFunTrade { mint, trader: user, direction: TradeDirection::Buy, sol_amount: 1, token_amount: 1 };
+        let create = ProtocolEvent::PumpFunCreate { mint, creator: user, metadata: None, dev_buy: None };
+        let initialize = ProtocolEvent::LaunchpadInitialize { mint, creator: user, metadata: None, dev_buy: None, pool: None, platform: None };
+
+        let tag = |event: &ProtocolEvent| serde_json::to_value(event).unwrap()["event"].as_str().unwrap().to_string();
+        assert_eq!(tag(&create), create.name());
+        assert_eq!(tag(&initialize), initialize.name());
+        // 买卖的名称把标签中的 trade 换成方向
+        assert_eq!(tag(&buy), "pumpfun_trade");
+        assert_eq!(buy.name(), "pumpfun_buy");
+    }
+}
@@ -29,6 +29,9 @@ pub struct IdlAccount {
     pub relations: Option<Vec<String>>,
     #[serde(default)]
     pub address: Option<String>,
+    /// 顶层 accounts 中的账户数据判别器
+    #[serde(default)]
+    pub discriminator: Option<Vec<u8>>,
     // Legacy fields for backwards compatibility
     #[serde(alias = "isMut", default)]
     pub is_mut: Option<bool>,
@@ -39,6 +42,9 @@ pub struct IdlAccount {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IdlInstruction {
     pub name: String,
+    /// 新版 IDL 声明的判别器，旧版需按 `sha256("global:<name>")` 计算
+    #[serde(default)]
+    pub discriminator: Option<Vec<u8>>,
     pub accounts: Vec<IdlAccount>,
     pub args: Vec<IdlField>,
     pub docs: Option<Vec<String>>,
@@ -144,53 +150,38 @@ where
     #[serde(untagged)]
     enum FieldFormat {
         Full(Vec<IdlField>),
-        Simple(Vec<String>),
+        Simple(Vec<IdlType>),
     }
     
     match FieldFormat::deserialize(deserializer)? {
         FieldFormat::Full(fields) => Ok(fields),
-        FieldFormat::Simple(field_names) => {
-            Ok(field_names.into_iter().enumerate().map(|(i, name)| {
-                if name == "bool" {
-                    IdlField {
-                        name: format!("field_{}", i),
-                        type_: IdlType::Bool,
-                        docs: None,
-                    }
-                } else {
-                    // 处理其他基础类型
-                    let type_ = match name.as_str() {
-                        "u8" => IdlType::U8,
-                        "i8" => IdlType::I8,
-                        "u16" => IdlType::U16,
-                        "i16" => IdlType::I16,
-                        "u32" => IdlType::U32,
-                        "i32" => IdlType::I32,
-                        "u64" => IdlType::U64,
-                        "i64" => IdlType::I64,
-                        "f32" => IdlType::F32,
-                        "f64" => IdlType::F64,
-                        "u128" => IdlType::U128,
-                        "i128" => IdlType::I128,
-                        "string" => IdlType::String,
-                        "publicKey" => IdlType::PublicKey,
-                        "bytes" => IdlType::Bytes,
-                        _ => IdlType::DefinedString(name.clone()),
-                    };
-                    IdlField {
-                        name: format!("field_{}", i),
-                        type_,
-                        docs: None,
-                    }
-                }
-            }).collect())
-        }
+        // 元组结构只列出类型，按位置命名为 field_<i>
+        FieldFormat::Simple(types) => Ok(types
+            .into_iter()
+            .enumerate()
+            .map(|(i, type_)| IdlField {
+                name: format!("field_{}", i),
+                type_,
+                docs: None,
+            })
+            .collect()),
     }
 }
 
+fn deserialize_variant_fields<'de, D>(deserializer: D) -> Result<Option<Vec<IdlField>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Fields(#[serde(deserialize_with = "deserialize_struct_fields")] Vec<IdlField>);
+
+    Ok(Option::<Fields>::deserialize(deserializer)?.map(|fields| fields.0))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IdlEnumVariant {
     pub name: String,
+    #[serde(default, deserialize_with = "deserialize_variant_fields")]
     pub fields: Option<Vec<IdlField>>,
 }
 
@@ -0,0 +1,106 @@
+use anyhow::{anyhow, Context, Result};
+use log::info;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+use crate::config::LaunchpadConfig;
+
+static GLOBAL_PLATFORMS: OnceLock<Arc<LaunchpadPlatforms>> = OnceLock::new();
+
+/// 按 `platform_config` 账户识别 Raydium Launchpad 平台
+///
+/// 平台配置账户只能由 Launchpad 程序管理员创建，不能像 mint 地址后缀那样被随意伪造。
+#[derive(Debug, Clone)]
+pub struct LaunchpadPlatforms {
+    platforms: HashMap<Pubkey, String>,
+    allow_unknown: bool,
+}
+
+/// 平台识别结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlatformMatch<'a> {
+    /// 白名单中的平台
+    Known(&'a str),
+    /// 不在白名单中，但配置允许输出
+    Unknown,
+}
+
+impl PlatformMatch<'_> {
+    pub fn name(&self) -> Option<String> {
+        match self {
+            Self::Known(name) => Some(name.to_string()),
+            Self::Unknown => None,
+        }
+    }
+}
+
+impl LaunchpadPlatforms {
+    pub fn from_config(config: &LaunchpadConfig) -> Result<Self> {
+        let mut platforms = HashMap::new();
+        for platform in &config.platforms {
+            let platform_config: Pubkey = platform
+                .platform_config
+                .parse()
+                .with_context(|| format!("无效的平台配置账户 {}: {}", platform.name, platform.platform_config))?;
+            platforms.insert(platform_config, platform.name.clone());
+        }
+
+        for (platform_config, name) in &platforms {
+            info!("🏷️ Launchpad平台 {} ({})", name, platform_config);
+        }
+        Ok(Self {
+            platforms,
+            allow_unknown: config.allow_unknown,
+        })
+    }
+
+    /// 识别平台；不在白名单且不允许未知平台时返回 None，调用方应丢弃该事件
+    pub fn classify(&self, platform_config: &Pubkey) -> Option<PlatformMatch<'_>> {
+        match self.platforms.get(platform_config) {
+            Some(name) => Some(PlatformMatch::Known(name)),
+            None if self.allow_unknown => Some(PlatformMatch::Unknown),
+            None => None,
+        }
+    }
+
+    /// 设置进程级平台白名单，供各事件流内部创建的检测器使用；只能设置一次
+    pub fn install(self) -> Result<()> {
+        GLOBAL_PLATFORMS
+            .set(Arc::new(self))
+            .map_err(|_| anyhow!("Launchpad平台白名单已经设置"))
+    }
+
+    /// 进程级平台白名单，未设置时为默认配置（仅 LetsBonk）
+    pub fn global() -> Arc<LaunchpadPlatforms> {
+        GLOBAL_PLATFORMS
+            .get_or_init(|| Arc::new(Self::from_config(&LaunchpadConfig::default()).expect("默认平台配置应当有效")))
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::LaunchpadPlatformConfig;
+
+    #[test]
+    fn test_classify_by_platform_config() {
+        let other = Pubkey::new_unique();
+        let mut config = LaunchpadConfig::default();
+        config.platforms.push(LaunchpadPlatformConfig {
+            name: "Other".to_string(),
+            platform_config: other.to_string(),
+        });
+
+        let platforms = LaunchpadPlatforms::from_config(&config).unwrap();
+        let letsbonk: Pubkey = "FfYek5vEz23cMkWsdJwG2oa6EphsvXSHrGpdALN4g6W1".parse().unwrap();
+        assert_eq!(platforms.classify(&letsbonk), Some(PlatformMatch::Known("LetsBonk")));
+        assert_eq!(platforms.classify(&other), Some(PlatformMatch::Known("Other")));
+        assert_eq!(platforms.classify(&Pubkey::new_unique()), None);
+
+        config.allow_unknown = true;
+        let platforms = LaunchpadPlatforms::from_config(&config).unwrap();
+        assert_eq!(platforms.classify(&Pubkey::new_unique()), Some(PlatformMatch::Unknown));
+    }
+}
@@ -1,11 +1,19 @@
 use anyhow::Result;
+use async_trait::async_trait;
 use log::debug;
 use solana_sdk::pubkey::Pubkey;
 use std::str::FromStr;
+use std::sync::Arc;
 use yellowstone_grpc_proto::geyser::SubscribeUpdateTransactionInfo;
 
-use crate::processors::{TokenEvent, TransactionType};
+use crate::constant::seeds::EVENT_AUTHORITY_SEED;
+use crate::processors::{
+    CurveReserves, DevBuy, LaunchpadFees, LaunchpadPlatforms, LaunchpadPool, LaunchpadPoolStatus, ProtocolEvent,
+    TokenEvent, TokenMetadata, TradeDirection, TransactionEventDetector,
+};
 
+#[cfg(feature = "letsbonk")]
+use crate::idl::launchpad_events::{pool_create_metadata, LaunchpadCpiEvent, LaunchpadTradeEventExt};
 #[cfg(feature = "letsbonk")]
 use raydium_launchpad_interface::RaydiumLaunchpadProgramIx;
 
@@ -24,9 +32,10 @@ pub struct LetsbonkTokenCreationEvent {
 
 #[derive(Debug, Clone)]
 pub struct LetsbonkCreationWithBuyInfo {
-    pub mint: String,
     pub slot: u64,
     pub signature: String,
+    /// Initialize 指令的命名账户
+    pub accounts: LaunchpadInitializeAccounts,
     pub token_name: Option<String>,
     pub token_symbol: Option<String>,
     pub token_uri: Option<String>,
@@ -35,19 +44,179 @@ pub struct LetsbonkCreationWithBuyInfo {
     pub minimum_token_out: Option<u64>,    // 最小代币输出
 }
 
+impl LetsbonkCreationWithBuyInfo {
+    /// `base_mint_param` 中的代币信息
+    pub fn metadata(&self) -> Option<TokenMetadata> {
+        Some(TokenMetadata {
+            name: self.token_name.clone()?,
+            symbol: self.token_symbol.clone()?,
+            uri: self.token_uri.clone()?,
+        })
+    }
+}
+
+/// Initialize/InitializeV2/InitializeWithToken2022 指令的账户，顺序与 Raydium Launchpad IDL 一致
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LaunchpadInitializeAccounts {
+    pub payer: Pubkey,
+    pub creator: Pubkey,
+    pub global_config: Pubkey,
+    pub platform_config: Pubkey,
+    pub pool_state: Pubkey,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    /// base mint 所属的代币程序（SPL Token 或 Token-2022）
+    pub base_token_program: Option<Pubkey>,
+}
+
+impl LaunchpadInitializeAccounts {
+    /// 按指令账户位置取出命名账户，账户不足时返回 None
+    pub fn from_accounts(accounts: &[Pubkey]) -> Option<Self> {
+        Some(Self {
+            payer: *accounts.first()?,
+            creator: *accounts.get(1)?,
+            global_config: *accounts.get(2)?,
+            platform_config: *accounts.get(3)?,
+            // [4] 为金库权限 PDA
+            pool_state: *accounts.get(5)?,
+            base_mint: *accounts.get(6)?,
+            quote_mint: *accounts.get(7)?,
+            base_vault: *accounts.get(8)?,
+            quote_vault: *accounts.get(9)?,
+            // [10] 为元数据账户
+            base_token_program: accounts.get(11).copied(),
+        })
+    }
+
+    /// InitializeWithToken2022 的账户：没有元数据账户，[10] 即为 base 代币程序
+    pub fn from_token_2022_accounts(accounts: &[Pubkey]) -> Option<Self> {
+        let base_token_program = *accounts.get(10)?;
+        Self::from_accounts(accounts).map(|accounts| Self { base_token_program: Some(base_token_program), ..accounts })
+    }
+
+    /// 按创建指令的版本取出命名账户
+    #[cfg(feature = "letsbonk")]
+    pub fn for_instruction(instruction: &RaydiumLaunchpadProgramIx, accounts: &[Pubkey]) -> Option<Self> {
+        match instruction {
+            RaydiumLaunchpadProgramIx::InitializeWithToken2022(_) => Self::from_token_2022_accounts(accounts),
+            _ => Self::from_accounts(accounts),
+        }
+    }
+
+    pub fn pool(&self) -> LaunchpadPool {
+        LaunchpadPool {
+            pool_state: self.pool_state,
+            base_vault: self.base_vault,
+            quote_vault: self.quote_vault,
+            platform_config: self.platform_config,
+        }
+    }
+}
+
+/// 买卖指令（BuyExactIn/BuyExactOut/SellExactIn/SellExactOut）共用的账户布局
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LaunchpadTradeAccounts {
+    pub payer: Pubkey,
+    pub global_config: Pubkey,
+    pub platform_config: Pubkey,
+    pub pool_state: Pubkey,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    /// base mint 所属的代币程序（SPL Token 或 Token-2022）
+    pub base_token_program: Option<Pubkey>,
+}
+
+impl LaunchpadTradeAccounts {
+    pub fn from_accounts(accounts: &[Pubkey]) -> Option<Self> {
+        Some(Self {
+            payer: *accounts.first()?,
+            // [1] 为金库权限 PDA
+            global_config: *accounts.get(2)?,
+            platform_config: *accounts.get(3)?,
+            pool_state: *accounts.get(4)?,
+            // [5]/[6] 为用户的 base/quote 代币账户
+            base_vault: *accounts.get(7)?,
+            quote_vault: *accounts.get(8)?,
+            base_mint: *accounts.get(9)?,
+            quote_mint: *accounts.get(10)?,
+            base_token_program: accounts.get(11).copied(),
+        })
+    }
+}
+
+/// 账户索引已解析为地址的 Launchpad 指令
+#[derive(Debug)]
+struct LaunchpadInstruction<'a> {
+    accounts: Vec<Pubkey>,
+    data: &'a [u8],
+}
+
+/// TradeEvent 中的实际成交
+#[derive(Debug, Clone, Copy)]
+struct TradeFill {
+    pool_state: Pubkey,
+    direction: TradeDirection,
+    fill: DevBuy,
+    reserves: CurveReserves,
+    status: LaunchpadPoolStatus,
+    fees: LaunchpadFees,
+}
+
+/// PoolCreateEvent 中的池信息
+#[derive(Debug, Clone)]
+struct PoolCreated {
+    pool_state: Pubkey,
+    creator: Pubkey,
+    metadata: TokenMetadata,
+}
+
+/// 交易中解码出的 Launchpad 事件，按执行顺序排列
+#[derive(Debug, Default)]
+struct LaunchpadEvents {
+    trades: Vec<TradeFill>,
+    pools: Vec<PoolCreated>,
+}
+
+impl LaunchpadEvents {
+    /// 指定池上第一笔同方向的成交
+    fn trade(&self, pool_state: &Pubkey, direction: TradeDirection) -> Option<&TradeFill> {
+        self.trades.iter().find(|trade| trade.pool_state == *pool_state && trade.direction == direction)
+    }
+
+    fn pool(&self, pool_state: &Pubkey) -> Option<&PoolCreated> {
+        self.pools.iter().find(|pool| pool.pool_state == *pool_state)
+    }
+}
+
 pub struct LetsbonkDetector {
     raydium_launchpad_program_id: Pubkey,
+    event_authority: Pubkey,
+    platforms: Arc<LaunchpadPlatforms>,
 }
 
 impl LetsbonkDetector {
     pub fn new() -> Result<Self> {
         let raydium_launchpad_program_id = Pubkey::from_str(RAYDIUM_LAUNCHPAD_PROGRAM_ID)?;
-        
+
+        let (event_authority, _) = Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], &raydium_launchpad_program_id);
+
         Ok(Self {
             raydium_launchpad_program_id,
+            event_authority,
+            platforms: LaunchpadPlatforms::global(),
         })
     }
 
+    /// 使用指定的平台白名单（默认为进程级白名单）
+    pub fn with_platforms(mut self, platforms: Arc<LaunchpadPlatforms>) -> Self {
+        self.platforms = platforms;
+        self
+    }
+
     /// 检测Raydium Launchpad代币创建事件
     pub async fn detect_bonk_token_creation(
         &self,
@@ -62,285 +231,256 @@ impl LetsbonkDetector {
 
         debug!("🔍 检查Raydium Launchpad交易: {}", signature);
 
-        // 检查交易是否包含Raydium Launchpad程序
-        let transaction = txn_info.transaction.as_ref()?;
-        let message = transaction.message.as_ref()?;
-        
-        let account_keys: Vec<String> = message.account_keys.iter()
-            .map(|key| bs58::encode(key).into_string())
-            .collect();
-        
-        let has_raydium_launchpad = account_keys.iter().any(|key| key == RAYDIUM_LAUNCHPAD_PROGRAM_ID);
-        
-        if !has_raydium_launchpad {
+        // 完整账户列表：静态账户 + 地址查找表加载的可写/只读账户
+        let account_keys = resolve_account_keys(txn_info)?;
+        let instructions = self.launchpad_instructions(txn_info, &account_keys);
+        if instructions.is_empty() {
             return None;
         }
-
-        // 分析整个交易的所有指令
-        if let Some(creation_info) = self.analyze_transaction_instructions(txn_info, slot).await {
-            // 检查mint地址是否以"bonk"结尾（letsbonk池的特征）
-            if creation_info.mint.to_lowercase().ends_with("bonk") {                
-                // 构建包含创建和可能的买入信息的事件
-                return Some(TokenEvent {
-                    signature: creation_info.signature,
-                    slot: creation_info.slot,
-                    mint: Some(creation_info.mint),
-                    transaction_type: TransactionType::TokenCreation,
-                    detection_method: if creation_info.buy_amount.is_some() { 
-                        "Raydium Launchpad letsbonk Filter (含买入)"
-                    } else {
-                        "Raydium Launchpad letsbonk Filter"
-                    }.to_string(),
-                    program_logs: self.extract_program_logs(txn_info),
-                    account_keys: account_keys.clone(),
-                    sol_amount: creation_info.buy_amount, // 如果有买入，记录买入金额
-                    token_amount: creation_info.minimum_token_out, // 如果有买入，记录最小代币输出
-                    creator_wallet: Some(account_keys.get(0).cloned().unwrap_or("unknown".to_string())), // 第一个账户通常是创建者
-                    timestamp: Some(std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs() as i64),
-                    raw_data: None,
-                    block_height: Some(slot),
-                });
+        // 已确认交易的实际成交、储备和手续费来自链上事件，指令参数只是限额
+        let events = self.decode_events(txn_info, &instructions);
+
+        // 分析整个交易的所有指令（含内部指令）
+        if let Some(creation_info) = self.analyze_transaction_instructions(&instructions, signature.clone(), slot) {
+            let accounts = creation_info.accounts;
+            // 按池的平台配置账户识别平台（LetsBonk 等）
+            match self.platforms.classify(&accounts.platform_config) {
+                Some(platform) => {
+                    let dev_fill = events.trade(&accounts.pool_state, TradeDirection::Buy);
+                    let dev_buy = dev_fill.map(|trade| trade.fill).or_else(|| {
+                        creation_info.buy_amount
+                            .zip(creation_info.minimum_token_out)
+                            .map(|(sol_amount, token_amount)| DevBuy { sol_amount, token_amount })
+                    });
+                    let pool_created = events.pool(&accounts.pool_state);
+                    let metadata = pool_created.map(|pool| pool.metadata.clone()).or_else(|| creation_info.metadata());
+                    let creator = pool_created.map_or(accounts.creator, |pool| pool.creator);
+
+                    // 构建包含创建和可能的买入信息的事件
+                    return Some(TokenEvent {
+                        signature: creation_info.signature,
+                        slot: creation_info.slot,
+                        kind: ProtocolEvent::LaunchpadInitialize {
+                            mint: accounts.base_mint,
+                            creator,
+                            metadata,
+                            dev_buy,
+                            pool: Some(accounts.pool()),
+                            platform: platform.name(),
+                        },
+                        program_logs: self.extract_program_logs(txn_info),
+                        account_keys,
+                        timestamp: Some(std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs() as i64),
+                        reserves: dev_fill.map(|trade| trade.reserves),
+                        raw_data: None,
+                        block_height: Some(slot),
+                        unconfirmed: false,
+                        token_program: accounts.base_token_program,
+                    });
+                }
+                None => {
+                    debug!("⏭️ 非白名单平台的Launchpad池: {} (平台配置 {})", accounts.base_mint, accounts.platform_config);
+                    return None;
+                }
             }
         }
 
         // 检查买卖交易（对已存在的letsbonk池代币）
-        if let Some(trade_event) = self.parse_trade_instructions(txn_info, slot).await {
-            if let Some(ref mint) = trade_event.mint {
-                if mint.to_lowercase().ends_with("bonk") {
-                    return Some(trade_event);
-                }
-            }
+        if let Some(trade_event) = self.parse_trade_instructions(txn_info, &instructions, &events, &account_keys, signature, slot) {
+            return Some(trade_event);
         }
 
         None
     }
 
-
-    /// 解析交易指令（买/卖）
-    async fn parse_trade_instructions(
+    /// 按执行顺序收集 Launchpad 指令：每条顶层指令之后紧跟它触发的内部指令
+    fn launchpad_instructions<'a>(
         &self,
-        txn_info: &SubscribeUpdateTransactionInfo,
-        slot: u64,
-    ) -> Option<TokenEvent> {
-        let signature = if !txn_info.signature.is_empty() {
-            bs58::encode(&txn_info.signature).into_string()
-        } else {
-            "unknown".to_string()
+        txn_info: &'a SubscribeUpdateTransactionInfo,
+        account_keys: &[Pubkey],
+    ) -> Vec<LaunchpadInstruction<'a>> {
+        let Some(message) = txn_info.transaction.as_ref().and_then(|transaction| transaction.message.as_ref()) else {
+            return vec![];
         };
+        let inner_instructions = txn_info.meta.as_ref().map(|meta| meta.inner_instructions.as_slice()).unwrap_or_default();
+
+        let mut result = Vec::new();
+        for (index, instruction) in message.instructions.iter().enumerate() {
+            let inner = inner_instructions
+                .iter()
+                .filter(|inner| inner.index as usize == index)
+                .flat_map(|inner| inner.instructions.iter())
+                .map(|inner| (inner.program_id_index, inner.accounts.as_slice(), inner.data.as_slice()));
+
+            let compiled = std::iter::once((instruction.program_id_index, instruction.accounts.as_slice(), instruction.data.as_slice()));
+            for (program_id_index, accounts, data) in compiled.chain(inner) {
+                if account_keys.get(program_id_index as usize) != Some(&self.raydium_launchpad_program_id) {
+                    continue;
+                }
+                let Some(accounts) = accounts.iter().map(|&i| account_keys.get(i as usize).copied()).collect() else {
+                    debug!("⚠️ Launchpad指令的账户索引越界，跳过");
+                    continue;
+                };
+                result.push(LaunchpadInstruction { accounts, data });
+            }
+        }
 
-        let transaction = txn_info.transaction.as_ref()?;
-        let message = transaction.message.as_ref()?;
-        
-        let account_keys: Vec<String> = message.account_keys.iter()
-            .map(|key| bs58::encode(key).into_string())
-            .collect();
+        result
+    }
 
-        let account_pubkeys: Vec<Pubkey> = message.account_keys.iter()
-            .filter_map(|key| {
-                if key.len() == 32 {
-                    Some(Pubkey::try_from(key.as_slice()).ok()?)
-                } else {
-                    None
-                }
-            })
+    /// 解码 Launchpad 事件
+    ///
+    /// 优先使用以事件权限账户发起的自调用指令；没有时回退到 Launchpad 程序自身输出的
+    /// `Program data:` 日志（其他程序的同名事件判别器相同，必须按调用栈归属）。
+    #[cfg(feature = "letsbonk")]
+    fn decode_events(&self, txn_info: &SubscribeUpdateTransactionInfo, instructions: &[LaunchpadInstruction]) -> LaunchpadEvents {
+        let mut decoded: Vec<LaunchpadCpiEvent> = instructions
+            .iter()
+            .filter(|instruction| instruction.accounts.first() == Some(&self.event_authority))
+            .filter_map(|instruction| LaunchpadCpiEvent::decode(instruction.data))
             .collect();
 
-        // 查找买卖交易指令
-        for instruction in &message.instructions {
-            if let Some(program_key) = account_pubkeys.get(instruction.program_id_index as usize) {
-                if *program_key == self.raydium_launchpad_program_id {
-                    if instruction.data.len() >= 8 {
-                        let discriminator = &instruction.data[..8];
-                        
-                        #[cfg(feature = "letsbonk")]
-                        {
-                            debug!("尝试解析买卖指令，数据长度: {}, 判别器: {:?}", 
-                                instruction.data.len(), discriminator);
-                                
-                            match RaydiumLaunchpadProgramIx::deserialize(&instruction.data) {
-                                Ok(RaydiumLaunchpadProgramIx::BuyExactIn(args)) => {
-                                    // 提取mint地址并检查是否符合bonk条件
-                                    if let Some(mint) = self.extract_mint_from_accounts(&instruction.accounts, &account_keys) {
-                                        if mint.to_lowercase().ends_with("bonk") {
-                                            return Some(TokenEvent {
-                                                signature: signature.clone(),
-                                                slot,
-                                                mint: Some(mint),
-                                                transaction_type: TransactionType::Buy,
-                                                detection_method: "Raydium Launchpad Buy".to_string(),
-                                                program_logs: self.extract_program_logs(txn_info),
-                                                account_keys: account_keys.clone(),
-                                                sol_amount: Some(args.amount_in), // SOL输入金额
-                                                token_amount: Some(args.minimum_amount_out), // 最小代币输出
-                                                creator_wallet: None,
-                                                timestamp: Some(std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs() as i64),
-                                                raw_data: None,
-                                                block_height: Some(slot),
-                                            });
-                                        } else {
-                                            debug!("❌ mint地址不是bonk类型: {}", mint);
-                                        }
-                                    }
-                                }
-                                Ok(RaydiumLaunchpadProgramIx::BuyExactOut(args)) => {
-                                    debug!("✅ 发现BuyExactOut指令: amount_out={}, maximum_amount_in={}", 
-                                        args.amount_out, args.maximum_amount_in);
-                                    
-                                    // 提取mint地址并检查是否符合bonk条件
-                                    if let Some(mint) = self.extract_mint_from_accounts(&instruction.accounts, &account_keys) {
-                                        debug!("检查mint地址是否为bonk类型: {}", mint);
-                                        if mint.to_lowercase().ends_with("bonk") {
-                                            return Some(TokenEvent {
-                                                signature: signature.clone(),
-                                                slot,
-                                                mint: Some(mint),
-                                                transaction_type: TransactionType::Buy,
-                                                detection_method: "Raydium Launchpad Buy Exact Out".to_string(),
-                                                program_logs: self.extract_program_logs(txn_info),
-                                                account_keys: account_keys.clone(),
-                                                sol_amount: Some(args.maximum_amount_in), // 最大SOL输入
-                                                token_amount: Some(args.amount_out), // 确切代币输出
-                                                creator_wallet: None,
-                                                timestamp: Some(std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs() as i64),
-                                                raw_data: None,
-                                                block_height: Some(slot),
-                                            });
-                                        } else {
-                                            debug!("❌ mint地址不是bonk类型: {}", mint);
-                                        }
-                                    }
-                                }
-                                Ok(RaydiumLaunchpadProgramIx::SellExactIn(args)) => {
-                                    debug!("✅ 发现SellExactIn指令: amount_in={}, minimum_amount_out={}", 
-                                        args.amount_in, args.minimum_amount_out);
-                                    
-                                    // 提取mint地址并检查是否符合bonk条件
-                                    if let Some(mint) = self.extract_mint_from_accounts(&instruction.accounts, &account_keys) {
-                                        debug!("检查mint地址是否为bonk类型: {}", mint);
-                                        if mint.to_lowercase().ends_with("bonk") {
-                                            return Some(TokenEvent {
-                                                signature: signature.clone(),
-                                                slot,
-                                                mint: Some(mint),
-                                                transaction_type: TransactionType::Sell,
-                                                detection_method: "Raydium Launchpad Sell".to_string(),
-                                                program_logs: self.extract_program_logs(txn_info),
-                                                account_keys: account_keys.clone(),
-                                                sol_amount: Some(args.minimum_amount_out), // 最小SOL输出
-                                                token_amount: Some(args.amount_in), // 代币输入金额
-                                                creator_wallet: None,
-                                                timestamp: Some(std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs() as i64),
-                                                raw_data: None,
-                                                block_height: Some(slot),
-                                            });
-                                        } else {
-                                            debug!("❌ mint地址不是bonk类型: {}", mint);
-                                        }
-                                    }
-                                }
-                                Ok(RaydiumLaunchpadProgramIx::SellExactOut(args)) => {
-                                    debug!("✅ 发现SellExactOut指令: amount_out={}, maximum_amount_in={}", 
-                                        args.amount_out, args.maximum_amount_in);
-                                    
-                                    // 提取mint地址并检查是否符合bonk条件
-                                    if let Some(mint) = self.extract_mint_from_accounts(&instruction.accounts, &account_keys) {
-                                        debug!("检查mint地址是否为bonk类型: {}", mint);
-                                        if mint.to_lowercase().ends_with("bonk") {
-                                            return Some(TokenEvent {
-                                                signature: signature.clone(),
-                                                slot,
-                                                mint: Some(mint),
-                                                transaction_type: TransactionType::Sell,
-                                                detection_method: "Raydium Launchpad Sell Exact Out".to_string(),
-                                                program_logs: self.extract_program_logs(txn_info),
-                                                account_keys: account_keys.clone(),
-                                                sol_amount: Some(args.amount_out), // 确切SOL输出
-                                                token_amount: Some(args.maximum_amount_in), // 最大代币输入
-                                                creator_wallet: None,
-                                                timestamp: Some(std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs() as i64),
-                                                raw_data: None,
-                                                block_height: Some(slot),
-                                            });
-                                        } else {
-                                            debug!("❌ mint地址不是bonk类型: {}", mint);
-                                        }
-                                    }
-                                }
-                                Err(e) => {
-                                    debug!("❌ 买卖指令解析失败: {:?}", e);
-                                    debug!("指令数据 (前32字节): {}", hex::encode(&instruction.data[..std::cmp::min(32, instruction.data.len())]));
-                                }
-                                Ok(other) => {
-                                    debug!("🔍 其他买卖指令类型: {:?}", other);
-                                }
-                            }
+        if decoded.is_empty() {
+            let program_id = self.raydium_launchpad_program_id.to_string();
+            // 调用栈中每一层是否为 Launchpad 程序
+            let mut invoke_stack: Vec<bool> = Vec::new();
+            for line in self.extract_program_logs(txn_info).iter() {
+                if let Some(rest) = line.strip_prefix("Program ") {
+                    let mut parts = rest.split_whitespace();
+                    match (parts.next(), parts.next()) {
+                        (Some(id), Some("invoke")) => invoke_stack.push(id == program_id),
+                        (Some(_), Some("success" | "failed:")) => {
+                            invoke_stack.pop();
                         }
+                        _ => {}
                     }
                 }
+                if invoke_stack.last() == Some(&true) {
+                    decoded.extend(LaunchpadCpiEvent::decode_log(line));
+                }
             }
         }
 
-        None
-    }
-
-    /// 从账户列表中提取mint地址
-    fn extract_mint_from_accounts(&self, account_indices: &[u8], account_keys: &[String]) -> Option<String> {
-        debug!("🔍 账户索引: {:?}", account_indices);
-        debug!("🔍 可用账户 ({} 个):", account_keys.len());
-        for (i, key) in account_keys.iter().enumerate() {
-            debug!("   [{}]: {}", i, key);
-        }
-        
-        // 对于 Raydium Launchpad，我们需要找到实际的 mint 地址
-        // 通常交易的各个账户的含义：
-        // [0]: 用户/交易发起者
-        // [1]: 用户的相关账户 (ATA等)
-        // [2]: 可能是mint地址，也可能是其他账户
-        // 我们需要尝试多个位置找到正确的mint地址
-        
-        // 方法1: 查找以 mint 结尾的账户 (BONK 特征)
-        for &index in account_indices.iter() {
-            if let Some(account) = account_keys.get(index as usize) {
-                debug!("检查账户索引 {}: {}", index, account);
-                if account.to_lowercase().ends_with("bonk") {
-                    debug!("✅ 找到 BONK mint 地址: {}", account);
-                    return Some(account.clone());
-                }
+        let mut events = LaunchpadEvents::default();
+        for event in decoded {
+            match event {
+                LaunchpadCpiEvent::Trade(trade) => events.trades.push(TradeFill {
+                    pool_state: trade.pool_state,
+                    direction: trade.direction(),
+                    fill: trade.fill(),
+                    reserves: trade.reserves(),
+                    status: trade.status(),
+                    fees: trade.fees(),
+                }),
+                LaunchpadCpiEvent::PoolCreate(pool) => events.pools.push(PoolCreated {
+                    pool_state: pool.pool_state,
+                    creator: pool.creator,
+                    metadata: pool_create_metadata(&pool),
+                }),
             }
         }
-        
-        // 方法2: 如果没找到明确的BONK地址，尝试找到看起来像mint的地址
-        // Mint地址通常不是已知的程序地址
-        let known_programs = [
-            "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA", // Token Program
-            "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL", // Associated Token Program  
-            "LanMV9sAd7wArD4vJFi2qDdfnVhFxYSUg6eADduJ3uj", // Raydium Launchpad Program
-            "ComputeBudget111111111111111111111111111111", // Compute Budget Program
-            "11111111111111111111111111111111", // System Program
-            "So11111111111111111111111111111111111111112", // WSOL
-            "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s", // Metaplex Program
-            "SysvarRent111111111111111111111111111111111", // Sysvar Rent
-        ];
-        
-        for &index in account_indices.iter() {
-            if let Some(account) = account_keys.get(index as usize) {
-                if !known_programs.contains(&account.as_str()) {
-                    debug!("💡 可能的mint地址候选: {}", account);
-                    // 返回第一个不是已知程序的账户
-                    return Some(account.clone());
-                }
+        debug!("📣 Launchpad事件: {} 笔成交, {} 个新池", events.trades.len(), events.pools.len());
+        events
+    }
+
+    #[cfg(not(feature = "letsbonk"))]
+    fn decode_events(&self, _txn_info: &SubscribeUpdateTransactionInfo, _instructions: &[LaunchpadInstruction]) -> LaunchpadEvents {
+        LaunchpadEvents::default()
+    }
+
+    /// 解析交易指令（买/卖）
+    fn parse_trade_instructions(
+        &self,
+        txn_info: &SubscribeUpdateTransactionInfo,
+        instructions: &[LaunchpadInstruction],
+        events: &LaunchpadEvents,
+        account_keys: &[Pubkey],
+        signature: String,
+        slot: u64,
+    ) -> Option<TokenEvent> {
+        // 查找买卖交易指令
+        for instruction in instructions {
+            if instruction.data.len() < 8 {
+                continue;
             }
-        }
-        
-        // 方法3: 退化到使用第二个账户（原来的逻辑）
-        if account_indices.len() > 1 {
-            if let Some(mint_key) = account_keys.get(account_indices[1] as usize) {
-                debug!("⚠️ 使用退化逻辑，返回第二个账户: {}", mint_key);
-                return Some(mint_key.clone());
+
+            #[cfg(feature = "letsbonk")]
+            {
+                debug!("尝试解析买卖指令，数据长度: {}, 判别器: {:?}",
+                    instruction.data.len(), &instruction.data[..8]);
+
+                // (方向, SOL金额, 代币金额)，金额为指令参数中的限额
+                let trade = match RaydiumLaunchpadProgramIx::deserialize(instruction.data) {
+                    Ok(RaydiumLaunchpadProgramIx::BuyExactIn(args)) => {
+                        Some((TradeDirection::Buy, args.amount_in, args.minimum_amount_out))
+                    }
+                    Ok(RaydiumLaunchpadProgramIx::BuyExactOut(args)) => {
+                        debug!("✅ 发现BuyExactOut指令: amount_out={}, maximum_amount_in={}",
+                            args.amount_out, args.maximum_amount_in);
+                        Some((TradeDirection::Buy, args.maximum_amount_in, args.amount_out))
+                    }
+                    Ok(RaydiumLaunchpadProgramIx::SellExactIn(args)) => {
+                        debug!("✅ 发现SellExactIn指令: amount_in={}, minimum_amount_out={}",
+                            args.amount_in, args.minimum_amount_out);
+                        Some((TradeDirection::Sell, args.minimum_amount_out, args.amount_in))
+                    }
+                    Ok(RaydiumLaunchpadProgramIx::SellExactOut(args)) => {
+                        debug!("✅ 发现SellExactOut指令: amount_out={}, maximum_amount_in={}",
+                            args.amount_out, args.maximum_amount_in);
+                        Some((TradeDirection::Sell, args.amount_out, args.maximum_amount_in))
+                    }
+                    Err(e) => {
+                        debug!("❌ 买卖指令解析失败: {:?}", e);
+                        debug!("指令数据 (前32字节): {}", hex::encode(&instruction.data[..std::cmp::min(32, instruction.data.len())]));
+                        None
+                    }
+                    Ok(other) => {
+                        debug!("🔍 其他买卖指令类型: {:?}", other);
+                        None
+                    }
+                };
+
+                let Some((direction, sol_amount, token_amount)) = trade else {
+                    continue;
+                };
+                let Some(accounts) = LaunchpadTradeAccounts::from_accounts(&instruction.accounts) else {
+                    debug!("❌ 买卖指令账户不足: {}", instruction.accounts.len());
+                    continue;
+                };
+                let Some(platform) = self.platforms.classify(&accounts.platform_config) else {
+                    debug!("❌ 非白名单平台: {} (平台配置 {})", accounts.base_mint, accounts.platform_config);
+                    continue;
+                };
+
+                let filled = events.trade(&accounts.pool_state, direction);
+                let (sol_amount, token_amount) = filled
+                    .map_or((sol_amount, token_amount), |trade| (trade.fill.sol_amount, trade.fill.token_amount));
+
+                return Some(TokenEvent {
+                    signature,
+                    slot,
+                    kind: ProtocolEvent::LaunchpadTrade {
+                        mint: accounts.base_mint,
+                        trader: accounts.payer,
+                        direction,
+                        sol_amount,
+                        token_amount,
+                        platform: platform.name(),
+                        pool_status: filled.map(|trade| trade.status),
+                        fees: filled.map(|trade| trade.fees),
+                    },
+                    program_logs: self.extract_program_logs(txn_info),
+                    account_keys: account_keys.to_vec(),
+                    timestamp: Some(std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs() as i64),
+                    reserves: filled.map(|trade| trade.reserves),
+                    raw_data: None,
+                    block_height: Some(slot),
+                    unconfirmed: false,
+                    token_program: accounts.base_token_program,
+                });
             }
         }
-        
-        debug!("❌ 无法提取mint地址");
+
         None
     }
 
@@ -353,103 +493,75 @@ impl LetsbonkDetector {
         }
     }
 
-    /// 分析整个交易的所有指令，提取创建和买入信息
-    async fn analyze_transaction_instructions(
+    /// 分析整个交易的所有 Launchpad 指令，提取创建和买入信息
+    fn analyze_transaction_instructions(
         &self,
-        txn_info: &SubscribeUpdateTransactionInfo,
+        instructions: &[LaunchpadInstruction],
+        signature: String,
         slot: u64,
     ) -> Option<LetsbonkCreationWithBuyInfo> {
-        let signature = if !txn_info.signature.is_empty() {
-            bs58::encode(&txn_info.signature).into_string()
-        } else {
-            "unknown".to_string()
-        };
-
-        let transaction = txn_info.transaction.as_ref()?;
-        let message = transaction.message.as_ref()?;
-        
-        let account_keys: Vec<Pubkey> = message.account_keys.iter()
-            .filter_map(|key| {
-                if key.len() == 32 {
-                    Some(Pubkey::try_from(key.as_slice()).ok()?)
-                } else {
-                    None
-                }
-            })
-            .collect();
-
-        let _account_keys_str: Vec<String> = message.account_keys.iter()
-            .map(|key| bs58::encode(key).into_string())
-            .collect();
-
         // 存储找到的创建和买入信息
-        let mut creation_info: Option<(String, String, String, String)> = None; // (mint, name, symbol, uri)
-        let mut buy_info: Option<(u64, u64)> = None; // (amount_in, minimum_amount_out)
+        let mut creation_info: Option<(LaunchpadInitializeAccounts, String, String, String)> = None; // (accounts, name, symbol, uri)
+        let mut buy_info: Option<(Pubkey, u64, u64)> = None; // (mint, amount_in, minimum_amount_out)
 
-        debug!("🔍 分析交易中的 {} 条指令", message.instructions.len());
+        debug!("🔍 分析交易中的 {} 条Launchpad指令", instructions.len());
 
         // 分析所有指令
-        for (i, instruction) in message.instructions.iter().enumerate() {
-            if let Some(program_key) = account_keys.get(instruction.program_id_index as usize) {
-                if *program_key == self.raydium_launchpad_program_id {
-                    debug!("📝 指令 {}: Raydium Launchpad 指令，数据长度: {}", i, instruction.data.len());
-                    
-                    if instruction.data.len() >= 8 {
-                        let _discriminator = &instruction.data[..8];
-                        
-                        #[cfg(feature = "letsbonk")]
-                        {
-                            match RaydiumLaunchpadProgramIx::deserialize(&instruction.data) {
-                                Ok(RaydiumLaunchpadProgramIx::Initialize(args)) => {
-                                    debug!("✅ 找到Initialize指令");
-                                    // 提取mint地址（根据Raydium Launchpad IDL，mint地址在账户索引6）
-                                    if let Some(&mint_index) = instruction.accounts.get(6) {
-                                        if let Some(mint_pubkey) = account_keys.get(mint_index as usize) {
-                                            debug!("🪙 mint地址: {}", mint_pubkey);
-                                            creation_info = Some((
-                                                mint_pubkey.to_string(),
-                                                args.base_mint_param.name.clone(),
-                                                args.base_mint_param.symbol.clone(),
-                                                args.base_mint_param.uri.clone(),
-                                            ));
-                                        }
-                                    }
-                                }
-                                Ok(RaydiumLaunchpadProgramIx::BuyExactIn(args)) => {
-                                    debug!("💰 找到BuyExactIn指令: amount_in={}, minimum_amount_out={}", 
-                                        args.amount_in, args.minimum_amount_out);
-                                    buy_info = Some((args.amount_in, args.minimum_amount_out));
-                                }
-                                Ok(RaydiumLaunchpadProgramIx::BuyExactOut(args)) => {
-                                    debug!("💰 找到BuyExactOut指令: amount_out={}, maximum_amount_in={}", 
-                                        args.amount_out, args.maximum_amount_in);
-                                    buy_info = Some((args.maximum_amount_in, args.amount_out));
-                                }
-                                Ok(other) => {
-                                    debug!("🔍 其他指令类型: {:?}", other);
-                                }
-                                Err(e) => {
-                                    debug!("❌ 指令解析失败: {:?}", e);
-                                }
+        for (i, instruction) in instructions.iter().enumerate() {
+            debug!("📝 指令 {}: Raydium Launchpad 指令，数据长度: {}", i, instruction.data.len());
+
+            #[cfg(feature = "letsbonk")]
+            {
+                let trade_mint = || LaunchpadTradeAccounts::from_accounts(&instruction.accounts).map(|accounts| accounts.base_mint);
+                match RaydiumLaunchpadProgramIx::deserialize(instruction.data) {
+                    Ok(ix) if ix.is_pool_creation() => {
+                        debug!("✅ 找到{}指令", ix.name());
+                        match (LaunchpadInitializeAccounts::for_instruction(&ix, &instruction.accounts), ix.get_token_info()) {
+                            (Some(accounts), Some(mint_param)) => {
+                                debug!("🪙 mint地址: {}, 创建者: {}, 池: {}", accounts.base_mint, accounts.creator, accounts.pool_state);
+                                creation_info = Some((
+                                    accounts,
+                                    mint_param.name.clone(),
+                                    mint_param.symbol.clone(),
+                                    mint_param.uri.clone(),
+                                ));
                             }
+                            _ => debug!("❌ {}指令账户不足: {}", ix.name(), instruction.accounts.len()),
                         }
                     }
+                    Ok(RaydiumLaunchpadProgramIx::BuyExactIn(args)) => {
+                        debug!("💰 找到BuyExactIn指令: amount_in={}, minimum_amount_out={}",
+                            args.amount_in, args.minimum_amount_out);
+                        buy_info = trade_mint().map(|mint| (mint, args.amount_in, args.minimum_amount_out));
+                    }
+                    Ok(RaydiumLaunchpadProgramIx::BuyExactOut(args)) => {
+                        debug!("💰 找到BuyExactOut指令: amount_out={}, maximum_amount_in={}",
+                            args.amount_out, args.maximum_amount_in);
+                        buy_info = trade_mint().map(|mint| (mint, args.maximum_amount_in, args.amount_out));
+                    }
+                    Ok(other) => {
+                        debug!("🔍 其他指令类型: {:?}", other);
+                    }
+                    Err(e) => {
+                        debug!("❌ 指令解析失败: {:?}", e);
+                    }
                 }
             }
         }
 
-        // 如果找到了创建信息，构建完整的创建事件
-        if let Some((mint, name, symbol, uri)) = creation_info {
+        // 如果找到了创建信息，构建完整的创建事件；只有针对同一 mint 的买入才算开发者买入
+        if let Some((accounts, name, symbol, uri)) = creation_info {
+            let buy_info = buy_info.filter(|(mint, _, _)| *mint == accounts.base_mint);
             debug!("✅ 构建创建事件信息，包含买入信息: {:?}", buy_info.is_some());
             Some(LetsbonkCreationWithBuyInfo {
-                mint,
                 slot,
                 signature,
+                accounts,
                 token_name: Some(name),
                 token_symbol: Some(symbol),
                 token_uri: Some(uri),
-                buy_amount: buy_info.map(|(amount_in, _)| amount_in),
-                minimum_token_out: buy_info.map(|(_, min_out)| min_out),
+                buy_amount: buy_info.map(|(_, amount_in, _)| amount_in),
+                minimum_token_out: buy_info.map(|(_, _, min_out)| min_out),
             })
         } else {
             debug!("❌ 未找到Initialize指令");
@@ -458,12 +570,36 @@ impl LetsbonkDetector {
     }
 }
 
+#[async_trait]
+impl TransactionEventDetector for LetsbonkDetector {
+    async fn detect(&self, txn_info: &SubscribeUpdateTransactionInfo, slot: u64) -> Option<TokenEvent> {
+        self.detect_bonk_token_creation(txn_info, slot).await
+    }
+}
+
 impl Default for LetsbonkDetector {
     fn default() -> Self {
         Self::new().expect("Failed to create LetsbonkDetector")
     }
 }
 
+/// 按 v0 交易的账户顺序拼接完整账户列表：静态账户、查找表可写账户、查找表只读账户
+///
+/// 任一地址长度不合法时返回 None，避免索引错位。
+fn resolve_account_keys(txn_info: &SubscribeUpdateTransactionInfo) -> Option<Vec<Pubkey>> {
+    let message = txn_info.transaction.as_ref()?.message.as_ref()?;
+    let loaded = txn_info.meta.iter().flat_map(|meta| {
+        meta.loaded_writable_addresses.iter().chain(meta.loaded_readonly_addresses.iter())
+    });
+
+    message
+        .account_keys
+        .iter()
+        .chain(loaded)
+        .map(|key| Pubkey::try_from(key.as_slice()).ok())
+        .collect()
+}
+
 /// 处理letsbonk交易的便利函数
 pub async fn process_letsbonk_transaction(
     txn_info: &SubscribeUpdateTransactionInfo,
@@ -476,10 +612,161 @@ pub async fn process_letsbonk_transaction(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::{LaunchpadConfig, LaunchpadPlatformConfig};
+    use yellowstone_grpc_proto::prelude::{
+        CompiledInstruction, InnerInstruction, InnerInstructions, Message, Transaction, TransactionStatusMeta,
+    };
 
     #[test]
     fn test_detector_creation() {
         let detector = LetsbonkDetector::new();
         assert!(detector.is_ok());
     }
-}
\ No newline at end of file
+
+    fn platforms(platform_config: Pubkey) -> Arc<LaunchpadPlatforms> {
+        let config = LaunchpadConfig {
+            allow_unknown: false,
+            platforms: vec![LaunchpadPlatformConfig { name: "LetsBonk".to_string(), platform_config: platform_config.to_string() }],
+        };
+        Arc::new(LaunchpadPlatforms::from_config(&config).unwrap())
+    }
+
+    #[cfg(feature = "letsbonk")]
+    #[tokio::test]
+    async fn test_v0_initialize_via_cpi_with_lookup_table() {
+        use raydium_launchpad_interface::{typedefs::MintParams, BuyExactInIxArgs, InitializeIxArgs};
+
+        // mint 不需要任何地址特征，平台由 platform_config（named[3]）决定
+        let mint = Pubkey::new_unique();
+        let named: Vec<Pubkey> = (0..10).map(|_| Pubkey::new_unique()).collect();
+        let detector = LetsbonkDetector::new().unwrap().with_platforms(platforms(named[3]));
+
+        // 静态账户：[0] 付款人 [1] 聚合器程序 [2] Launchpad 程序 [3..] 其余
+        // 查找表加载的 mint 位于所有静态账户之后
+        let mut static_keys = vec![named[0], Pubkey::new_unique(), detector.raydium_launchpad_program_id];
+        static_keys.extend_from_slice(&named[1..]);
+        let mint_index = static_keys.len() as u8;
+
+        let initialize_accounts: Vec<u8> = vec![0, 3, 4, 5, 6, 7, mint_index, 9, 10, 11];
+        let initialize = RaydiumLaunchpadProgramIx::Initialize(InitializeIxArgs {
+            base_mint_param: MintParams { decimals: 6, name: "Bonk Dog".to_string(), symbol: "BDOG".to_string(), uri: "https://b.dog".to_string() },
+            ..Default::default()
+        });
+        let buy_accounts: Vec<u8> = vec![0, 6, 4, 5, 7, 3, 3, 10, 11, mint_index, 9];
+        let buy = RaydiumLaunchpadProgramIx::BuyExactIn(BuyExactInIxArgs { amount_in: 500_000_000, minimum_amount_out: 1_000, share_fee_rate: 0 });
+
+        let inner = |accounts: Vec<u8>, ix: &RaydiumLaunchpadProgramIx| InnerInstruction {
+            program_id_index: 2,
+            accounts,
+            data: ix.try_to_vec().unwrap(),
+            stack_height: Some(2),
+        };
+        let txn_info = SubscribeUpdateTransactionInfo {
+            signature: vec![1; 64],
+            transaction: Some(Transaction {
+                signatures: vec![vec![1; 64]],
+                message: Some(Message {
+                    account_keys: static_keys.iter().map(|key| key.to_bytes().to_vec()).collect(),
+                    instructions: vec![CompiledInstruction { program_id_index: 1, accounts: vec![0], data: vec![9; 8] }],
+                    versioned: true,
+                    ..Default::default()
+                }),
+            }),
+            meta: Some(TransactionStatusMeta {
+                loaded_writable_addresses: vec![mint.to_bytes().to_vec()],
+                inner_instructions: vec![InnerInstructions {
+                    index: 0,
+                    instructions: vec![inner(initialize_accounts, &initialize), inner(buy_accounts, &buy)],
+                }],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let event = detector.detect(&txn_info, 42).await.unwrap();
+        assert_eq!(
+            event.kind,
+            ProtocolEvent::LaunchpadInitialize {
+                mint,
+                creator: named[1],
+                metadata: Some(TokenMetadata { name: "Bonk Dog".to_string(), symbol: "BDOG".to_string(), uri: "https://b.dog".to_string() }),
+                dev_buy: Some(DevBuy { sol_amount: 500_000_000, token_amount: 1_000 }),
+                pool: Some(LaunchpadPool {
+                    pool_state: named[5],
+                    base_vault: named[8],
+                    quote_vault: named[9],
+                    platform_config: named[3],
+                }),
+                platform: Some("LetsBonk".to_string()),
+            }
+        );
+        assert_eq!(event.account_keys.last(), Some(&mint));
+
+        // 白名单之外的平台不输出事件
+        let detector = detector.with_platforms(platforms(Pubkey::new_unique()));
+        assert!(detector.detect(&txn_info, 42).await.is_none());
+    }
+
+    #[cfg(feature = "letsbonk")]
+    #[tokio::test]
+    async fn test_trade_uses_event_fill_over_instruction_limits() {
+        use crate::idl::pumpfun_events::ANCHOR_EVENT_IX_TAG;
+        use raydium_launchpad_interface::{events::TRADE_EVENT_DISCM, SellExactInIxArgs};
+
+        let named: Vec<Pubkey> = (0..11).map(|_| Pubkey::new_unique()).collect();
+        let detector = LetsbonkDetector::new().unwrap().with_platforms(platforms(named[3]));
+
+        // [0..11] 买卖指令账户，[11] Launchpad 程序，[12] 事件权限
+        let mut static_keys = named.clone();
+        static_keys.extend([detector.raydium_launchpad_program_id, detector.event_authority]);
+
+        let sell = RaydiumLaunchpadProgramIx::SellExactIn(SellExactInIxArgs { amount_in: 2_000_000, minimum_amount_out: 1, share_fee_rate: 0 });
+        let mut event_data = ANCHOR_EVENT_IX_TAG.to_vec();
+        event_data.extend_from_slice(&TRADE_EVENT_DISCM);
+        event_data.extend(borsh::to_vec(&(named[4], 793_100_000_000_000u64, 1_073_025_605_596_382u64, 30_000_852_951u64)).unwrap());
+        event_data.extend(borsh::to_vec(&(19_000_000u64, 600_000u64, 17_000_000u64, 540_000u64)).unwrap());
+        event_data.extend(borsh::to_vec(&(2_000_000u64, 59_000u64, 150u64, 600u64, 0u64)).unwrap());
+        // trade_direction = Sell, pool_status = Migrate
+        event_data.extend_from_slice(&[1, 1]);
+
+        let txn_info = SubscribeUpdateTransactionInfo {
+            signature: vec![2; 64],
+            transaction: Some(Transaction {
+                signatures: vec![vec![2; 64]],
+                message: Some(Message {
+                    account_keys: static_keys.iter().map(|key| key.to_bytes().to_vec()).collect(),
+                    instructions: vec![CompiledInstruction {
+                        program_id_index: 11,
+                        accounts: (0..11).collect(),
+                        data: sell.try_to_vec().unwrap(),
+                    }],
+                    ..Default::default()
+                }),
+            }),
+            meta: Some(TransactionStatusMeta {
+                inner_instructions: vec![InnerInstructions {
+                    index: 0,
+                    instructions: vec![InnerInstruction { program_id_index: 11, accounts: vec![12], data: event_data, stack_height: Some(2) }],
+                }],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let event = detector.detect(&txn_info, 7).await.unwrap();
+        assert_eq!(
+            event.kind,
+            ProtocolEvent::LaunchpadTrade {
+                mint: named[9],
+                trader: named[0],
+                direction: TradeDirection::Sell,
+                sol_amount: 59_000,
+                token_amount: 2_000_000,
+                platform: Some("LetsBonk".to_string()),
+                pool_status: Some(LaunchpadPoolStatus::Migrate),
+                fees: Some(LaunchpadFees { protocol_fee: 150, platform_fee: 600, share_fee: 0 }),
+            }
+        );
+        assert_eq!(event.reserves.unwrap().real_sol_reserves, 540_000);
+    }
+}
@@ -1,9 +1,18 @@
+pub mod event;
 pub mod processor;
 pub mod token_detector;
 pub mod instruction_account_mapper;
 pub mod letsbonk_detector;
+pub mod launchpad_platforms;
+pub mod prefilter;
 
-pub use processor::{TokenEvent, TransactionType, TransactionProcessor};
+pub use event::{CurveReserves, DevBuy, LaunchpadFees, LaunchpadPool, LaunchpadPoolStatus, Protocol, ProtocolEvent, TokenMetadata, TradeDirection};
+pub use processor::{TokenEvent, TransactionType, TransactionProcessor, TransactionEventDetector};
 pub use token_detector::{TokenDetector, process_transaction_for_tokens, TransactionData, is_program_transaction};
-pub use letsbonk_detector::{LetsbonkDetector, LetsbonkTokenCreationEvent, process_letsbonk_transaction};
+pub use letsbonk_detector::{
+    LaunchpadInitializeAccounts, LaunchpadTradeAccounts, LetsbonkDetector, LetsbonkTokenCreationEvent,
+    process_letsbonk_transaction,
+};
+pub use launchpad_platforms::{LaunchpadPlatforms, PlatformMatch};
+pub use prefilter::InstructionPrefilter;
 pub use instruction_account_mapper::*;
\ No newline at end of file
@@ -0,0 +1,184 @@
+use solana_sdk::pubkey::Pubkey;
+use yellowstone_grpc_proto::geyser::SubscribeUpdateTransactionInfo;
+
+use crate::constant::accounts::PUMPFUN;
+
+/// Raydium Launchpad (LetsBonk) 程序
+const RAYDIUM_LAUNCHPAD: Pubkey = Pubkey::from_str_const("LanMV9sAd7wArD4vJFi2qDdfnVhFxYSUg6eADduJ3uj");
+
+/// PumpFun create / create_v2 / buy / sell / migrate 指令判别器
+const PUMPFUN_DISCRIMINATORS: &[[u8; 8]] = &[
+    [24, 30, 200, 40, 5, 28, 7, 119],
+    [214, 144, 76, 236, 95, 139, 49, 180],
+    [102, 6, 61, 18, 1, 218, 235, 234],
+    [51, 230, 133, 164, 1, 127, 131, 173],
+    [155, 234, 231, 146, 236, 158, 162, 30],
+];
+
+/// Raydium Launchpad initialize / initialize_v2 / initialize_with_token_2022 /
+/// buy_exact_in / buy_exact_out / sell_exact_in / sell_exact_out 指令判别器
+const LAUNCHPAD_DISCRIMINATORS: &[[u8; 8]] = &[
+    [175, 175, 109, 31, 13, 152, 155, 237],
+    [67, 153, 175, 39, 218, 16, 38, 32],
+    [37, 190, 126, 222, 44, 154, 171, 17],
+    [250, 234, 13, 123, 213, 156, 19, 236],
+    [24, 211, 116, 40, 105, 3, 153, 56],
+    [149, 39, 222, 155, 211, 124, 152, 26],
+    [95, 200, 71, 34, 8, 9, 11, 166],
+];
+
+/// 原始字节预过滤器
+///
+/// 直接在 protobuf 解出的字节切片上比较程序ID与指令判别器，不做 base58 编码、
+/// 不构建 `VersionedTransaction`，也不分配内存。只有包含候选指令（顶层或 CPI 内部指令）
+/// 的交易才交给检测器完整解码。
+#[derive(Debug, Clone, Copy)]
+pub struct InstructionPrefilter {
+    program_id: Pubkey,
+    discriminators: &'static [[u8; 8]],
+}
+
+impl InstructionPrefilter {
+    pub const fn new(program_id: Pubkey, discriminators: &'static [[u8; 8]]) -> Self {
+        Self { program_id, discriminators }
+    }
+
+    pub const fn pumpfun() -> Self {
+        Self::new(PUMPFUN, PUMPFUN_DISCRIMINATORS)
+    }
+
+    pub const fn letsbonk() -> Self {
+        Self::new(RAYDIUM_LAUNCHPAD, LAUNCHPAD_DISCRIMINATORS)
+    }
+
+    /// 交易是否包含目标程序的候选指令
+    pub fn matches(&self, txn_info: &SubscribeUpdateTransactionInfo) -> bool {
+        let Some(message) = txn_info.transaction.as_ref().and_then(|transaction| transaction.message.as_ref()) else {
+            return false;
+        };
+        let meta = txn_info.meta.as_ref();
+
+        // 程序ID可能来自静态账户，也可能来自地址查找表加载的账户（仅 CPI）
+        let loaded = meta.into_iter().flat_map(|meta| {
+            meta.loaded_writable_addresses.iter().chain(meta.loaded_readonly_addresses.iter())
+        });
+        let Some(program_index) = message
+            .account_keys
+            .iter()
+            .chain(loaded)
+            .position(|key| key.as_slice() == self.program_id.as_ref())
+        else {
+            return false;
+        };
+        let program_index = program_index as u32;
+
+        let top_level = message
+            .instructions
+            .iter()
+            .map(|instruction| (instruction.program_id_index, instruction.data.as_slice()));
+        let inner = meta.into_iter().flat_map(|meta| {
+            meta.inner_instructions.iter().flat_map(|inner| {
+                inner.instructions.iter().map(|instruction| (instruction.program_id_index, instruction.data.as_slice()))
+            })
+        });
+
+        top_level
+            .chain(inner)
+            .any(|(index, data)| index == program_index && self.is_candidate(data))
+    }
+
+    fn is_candidate(&self, data: &[u8]) -> bool {
+        data.get(..8)
+            .is_some_and(|discriminator| self.discriminators.iter().any(|candidate| candidate == discriminator))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yellowstone_grpc_proto::prelude::{
+        CompiledInstruction, InnerInstruction, InnerInstructions, Message, Transaction, TransactionStatusMeta,
+    };
+
+    fn transaction(account_keys: Vec<Vec<u8>>, instructions: Vec<CompiledInstruction>, meta: TransactionStatusMeta) -> SubscribeUpdateTransactionInfo {
+        SubscribeUpdateTransactionInfo {
+            signature: vec![1; 64],
+            transaction: Some(Transaction {
+                signatures: vec![vec![1; 64]],
+                message: Some(Message { account_keys, instructions, ..Default::default() }),
+            }),
+            meta: Some(meta),
+            ..Default::default()
+        }
+    }
+
+    fn instruction(program_id_index: u32, discriminator: [u8; 8]) -> CompiledInstruction {
+        CompiledInstruction {
+            program_id_index,
+            accounts: vec![0],
+            data: [discriminator.as_slice(), &[0; 16]].concat(),
+        }
+    }
+
+    #[test]
+    fn test_top_level_candidate_instruction() {
+        let keys = vec![vec![7; 32], PUMPFUN.to_bytes().to_vec()];
+        let buy = transaction(keys.clone(), vec![instruction(1, PUMPFUN_DISCRIMINATORS[2])], TransactionStatusMeta::default());
+        assert!(InstructionPrefilter::pumpfun().matches(&buy));
+        assert!(!InstructionPrefilter::letsbonk().matches(&buy));
+
+        // 程序存在但不是 create/buy/sell（例如 set_params）
+        let admin = transaction(keys, vec![instruction(1, [27, 234, 178, 52, 147, 2, 187, 141])], TransactionStatusMeta::default());
+        assert!(!InstructionPrefilter::pumpfun().matches(&admin));
+    }
+
+    #[test]
+    fn test_cpi_candidate_via_lookup_table_program() {
+        // 聚合器在顶层，Launchpad 程序来自查找表，只出现在内部指令中
+        let keys = vec![vec![7; 32], vec![8; 32]];
+        let meta = TransactionStatusMeta {
+            loaded_readonly_addresses: vec![vec![9; 32], RAYDIUM_LAUNCHPAD.to_bytes().to_vec()],
+            inner_instructions: vec![InnerInstructions {
+                index: 0,
+                instructions: vec![InnerInstruction {
+                    program_id_index: 3,
+                    accounts: vec![],
+                    data: LAUNCHPAD_DISCRIMINATORS[5].to_vec(),
+                    stack_height: Some(2),
+                }],
+            }],
+            ..Default::default()
+        };
+        let swap = transaction(keys, vec![instruction(1, [0; 8])], meta);
+        assert!(InstructionPrefilter::letsbonk().matches(&swap));
+        assert!(!InstructionPrefilter::pumpfun().matches(&swap));
+    }
+
+    #[test]
+    fn test_migrate_via_cpi() {
+        // 迁移程序在顶层，PumpFun migrate 只出现在内部指令中
+        let keys = vec![vec![7; 32], vec![8; 32], PUMPFUN.to_bytes().to_vec()];
+        let meta = TransactionStatusMeta {
+            inner_instructions: vec![InnerInstructions {
+                index: 0,
+                instructions: vec![InnerInstruction {
+                    program_id_index: 2,
+                    accounts: vec![],
+                    data: [155, 234, 231, 146, 236, 158, 162, 30].to_vec(),
+                    stack_height: Some(2),
+                }],
+            }],
+            ..Default::default()
+        };
+        let migrate = transaction(keys, vec![instruction(1, [0; 8])], meta);
+        assert!(InstructionPrefilter::pumpfun().matches(&migrate));
+    }
+
+    #[test]
+    fn test_short_data_and_missing_message() {
+        let keys = vec![PUMPFUN.to_bytes().to_vec()];
+        let short = CompiledInstruction { program_id_index: 0, accounts: vec![], data: vec![24, 30, 200] };
+        assert!(!InstructionPrefilter::pumpfun().matches(&transaction(keys, vec![short], TransactionStatusMeta::default())));
+        assert!(!InstructionPrefilter::pumpfun().matches(&SubscribeUpdateTransactionInfo::default()));
+    }
+}
@@ -1,9 +1,14 @@
 use anyhow::Result;
+use async_trait::async_trait;
 use log::info;
 use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
 use yellowstone_grpc_proto::geyser::SubscribeUpdateTransactionInfo;
 
-use crate::idl::IdlTransactionProcessor;
+use crate::idl::{AddressLookupResolver, IdlTransactionProcessor};
+use crate::processors::event::{CurveReserves, DevBuy, Protocol, ProtocolEvent, TradeDirection};
+use crate::serialization::{deserialize_option_pubkey, deserialize_pubkey_vec, serialize_option_pubkey, serialize_pubkey_vec};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum TransactionType {
@@ -13,25 +18,109 @@ pub enum TransactionType {
     Unknown,
 }
 
+/// 代币事件：协议相关的 `kind` 加上交易元信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenEvent {
     pub signature: String,
     pub slot: u64,
-    pub mint: Option<String>,
-    pub transaction_type: TransactionType,
-    pub detection_method: String,
+    /// 协议相关的事件内容，JSON 中展开为同级字段
+    #[serde(flatten)]
+    pub kind: ProtocolEvent,
     pub program_logs: Vec<String>,
-    pub account_keys: Vec<String>,
-    pub sol_amount: Option<u64>,
-    pub token_amount: Option<u64>,
-    /// 创建者/交易者钱包地址
-    pub creator_wallet: Option<String>,
+    #[serde(serialize_with = "serialize_pubkey_vec", deserialize_with = "deserialize_pubkey_vec")]
+    pub account_keys: Vec<Pubkey>,
     /// 交易时间戳
     pub timestamp: Option<i64>,
+    /// 成交后的储备，仅在解码到链上事件时存在
+    #[serde(default)]
+    pub reserves: Option<CurveReserves>,
     /// 完整的原始事件数据
     pub raw_data: Option<serde_json::Value>,
     /// 区块高度，用于区块对齐检查
     pub block_height: Option<u64>,
+    /// 未确认事件：仅由指令数据解码（交易尚未执行，无meta）
+    #[serde(default)]
+    pub unconfirmed: bool,
+    /// 代币 mint 所属的代币程序（SPL Token 或 Token-2022），未知时为 None
+    #[serde(default, serialize_with = "serialize_option_pubkey", deserialize_with = "deserialize_option_pubkey")]
+    pub token_program: Option<Pubkey>,
+}
+
+impl TokenEvent {
+    /// 仅含必要字段的事件，其余字段为空
+    pub fn new(signature: impl Into<String>, slot: u64, kind: ProtocolEvent) -> Self {
+        Self {
+            signature: signature.into(),
+            slot,
+            kind,
+            program_logs: vec![],
+            account_keys: vec![],
+            timestamp: None,
+            reserves: None,
+            raw_data: None,
+            block_height: None,
+            unconfirmed: false,
+            token_program: None,
+        }
+    }
+
+    pub fn mint(&self) -> Pubkey {
+        self.kind.mint()
+    }
+
+    pub fn protocol(&self) -> Protocol {
+        self.kind.protocol()
+    }
+
+    pub fn is_creation(&self) -> bool {
+        self.kind.is_creation()
+    }
+
+    /// 粗粒度的交易类型，供过滤条件使用
+    pub fn transaction_type(&self) -> TransactionType {
+        match self.kind.direction() {
+            Some(TradeDirection::Buy) => TransactionType::Buy,
+            Some(TradeDirection::Sell) => TransactionType::Sell,
+            None if self.kind.is_creation() => TransactionType::TokenCreation,
+            None => TransactionType::Unknown,
+        }
+    }
+
+    /// 创建者或交易者钱包
+    pub fn wallet(&self) -> Option<Pubkey> {
+        self.kind.wallet()
+    }
+
+    pub fn dev_buy(&self) -> Option<DevBuy> {
+        self.kind.dev_buy()
+    }
+
+    pub fn sol_amount(&self) -> Option<u64> {
+        self.kind.sol_amount()
+    }
+
+    pub fn token_amount(&self) -> Option<u64> {
+        self.kind.token_amount()
+    }
+
+    /// 成交后的价格：优先按储备计算，否则按事件金额估算
+    pub fn price(&self) -> Option<f64> {
+        if let Some(price) = self.reserves.as_ref().and_then(CurveReserves::price) {
+            return Some(price);
+        }
+        match (self.sol_amount(), self.token_amount()) {
+            (Some(sol_amount), Some(token_amount)) if token_amount > 0 => {
+                Some(sol_amount as f64 / token_amount as f64)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// 交易事件检测器：从 gRPC 交易更新中检测代币事件
+#[async_trait]
+pub trait TransactionEventDetector: Send + Sync {
+    async fn detect(&self, txn_info: &SubscribeUpdateTransactionInfo, slot: u64) -> Option<TokenEvent>;
 }
 
 #[derive(Debug, Clone)]
@@ -71,6 +160,25 @@ impl TransactionProcessor {
 
         None
     }
+
+    /// 解析尚未执行的交易（无meta），产生未确认事件
+    pub fn process_unconfirmed_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+        slot: u64,
+        resolver: Option<&dyn AddressLookupResolver>,
+    ) -> Option<TokenEvent> {
+        self.idl_processor
+            .as_ref()?
+            .process_unconfirmed_transaction(transaction, slot, resolver)
+    }
+}
+
+#[async_trait]
+impl TransactionEventDetector for TransactionProcessor {
+    async fn detect(&self, txn_info: &SubscribeUpdateTransactionInfo, slot: u64) -> Option<TokenEvent> {
+        self.process_transaction(txn_info, slot).await
+    }
 }
 
 impl Default for TransactionProcessor {
@@ -6,6 +6,12 @@ use std::str::FromStr;
 
 pub const PUMP_PROGRAM_PUBKEY: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
 
+/// PumpFun create（SPL Token）与 create_v2（Token-2022）指令判别器，两者的 [0] 账户都是 mint
+const CREATE_DISCRIMINATORS: [[u8; 8]; 2] = [
+    [24, 30, 200, 40, 5, 28, 7, 119],
+    [214, 144, 76, 236, 95, 139, 49, 180],
+];
+
 #[derive(Debug, Clone)]
 pub struct TransactionData {
     pub transaction: VersionedTransaction,
@@ -74,7 +80,7 @@ impl TokenDetector {
             if program_key == self.pump_program {
                 if instr.data.len() >= 8 {
                     let discriminator = &instr.data[..8];
-                    if discriminator == [24, 30, 200, 40, 5, 28, 7, 119] {
+                    if CREATE_DISCRIMINATORS.iter().any(|candidate| candidate == discriminator) {
                         if let Some(mint_key) = keys.get(instr.accounts[0] as usize) {
                             return Some(*mint_key);
                         }
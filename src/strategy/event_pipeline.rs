@@ -0,0 +1,512 @@
+use log::{debug, error, info};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+
+use crate::config::PipelineConfig;
+use crate::processors::TokenEvent;
+use super::optimized_strategy_manager::OptimizedStrategyManager;
+
+/// 队列满时对事件的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// 可被更新的事件挤掉（买卖事件）
+    DropOldest,
+    /// 从不丢弃，入队方等待空位（代币创建和迁移事件，每个 mint 只出现一次）
+    Block,
+}
+
+impl OverflowPolicy {
+    pub fn for_event(event: &TokenEvent) -> Self {
+        if event.is_creation() || event.kind.is_migration() {
+            OverflowPolicy::Block
+        } else {
+            OverflowPolicy::DropOldest
+        }
+    }
+}
+
+/// 入队结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PushOutcome {
+    Queued,
+    /// 入队成功，但挤掉了队列中最旧的一个买卖事件
+    QueuedDroppedOldest,
+    /// 队列已满且全是创建/迁移事件，新来的买卖事件被丢弃
+    Dropped,
+    /// 队列已关闭
+    Closed,
+}
+
+/// 单个管道阶段的计数器
+#[derive(Debug, Default)]
+pub struct StageStats {
+    processed: AtomicU64,
+    dropped: AtomicU64,
+    max_depth: AtomicUsize,
+}
+
+impl StageStats {
+    pub fn record_processed(&self) {
+        self.processed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_dropped(&self) {
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_depth(&self, depth: usize) {
+        self.max_depth.fetch_max(depth, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self, stage: &'static str, capacity: usize, depth: usize) -> StageSnapshot {
+        StageSnapshot {
+            stage,
+            capacity,
+            depth,
+            max_depth: self.max_depth.load(Ordering::Relaxed),
+            processed: self.processed.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// 管道阶段的统计快照
+#[derive(Debug, Clone)]
+pub struct StageSnapshot {
+    pub stage: &'static str,
+    pub capacity: usize,
+    /// 当前排队数
+    pub depth: usize,
+    /// 运行以来的最大排队数
+    pub max_depth: usize,
+    pub processed: u64,
+    pub dropped: u64,
+}
+
+impl StageSnapshot {
+    pub fn print(&self) {
+        info!("   📥 {}: 排队 {}/{} (峰值 {}) | 已处理 {} | 丢弃 {}",
+              self.stage, self.depth, self.capacity, self.max_depth, self.processed, self.dropped);
+    }
+}
+
+/// 有界事件队列，按 slot 顺序出队
+///
+/// 满时先挤掉最旧的买卖事件；队列里只剩创建/迁移事件时，买卖事件直接丢弃，
+/// 创建和迁移事件则等待消费者腾出空位，把背压传回事件流。
+pub struct EventQueue {
+    events: Mutex<VecDeque<TokenEvent>>,
+    capacity: usize,
+    closed: AtomicBool,
+    not_empty: Notify,
+    not_full: Notify,
+    stats: StageStats,
+}
+
+impl EventQueue {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            events: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+            closed: AtomicBool::new(false),
+            not_empty: Notify::new(),
+            not_full: Notify::new(),
+            stats: StageStats::default(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 按溢出策略入队
+    pub async fn push(&self, event: TokenEvent) -> PushOutcome {
+        let mut pending = Some(event);
+        loop {
+            let not_full = self.not_full.notified();
+            tokio::pin!(not_full);
+            not_full.as_mut().enable();
+
+            if self.closed.load(Ordering::Acquire) {
+                return PushOutcome::Closed;
+            }
+            if let Some(outcome) = self.try_push(&mut pending) {
+                return outcome;
+            }
+            // 队列里全是创建/迁移事件，等待消费者腾出空位
+            not_full.await;
+        }
+    }
+
+    /// 不等待地入队；需要等待空位时返回 None，事件留在 `pending` 中
+    fn try_push(&self, pending: &mut Option<TokenEvent>) -> Option<PushOutcome> {
+        let event = pending.take()?;
+        let mut events = self.lock();
+        let outcome = if events.len() < self.capacity {
+            PushOutcome::Queued
+        } else if let Some(oldest) = events.iter().position(|queued| OverflowPolicy::for_event(queued) == OverflowPolicy::DropOldest) {
+            let dropped = events.remove(oldest);
+            debug!("事件队列已满，丢弃最旧的买卖事件: {:?}", dropped.map(|event| event.signature));
+            self.stats.record_dropped();
+            PushOutcome::QueuedDroppedOldest
+        } else if OverflowPolicy::for_event(&event) == OverflowPolicy::DropOldest {
+            debug!("事件队列已满且全部为创建/迁移事件，丢弃买卖事件: {}", event.signature);
+            self.stats.record_dropped();
+            return Some(PushOutcome::Dropped);
+        } else {
+            *pending = Some(event);
+            return None;
+        };
+
+        // 按 slot 插入，同 slot 保持到达顺序；绝大多数情况下就是追加到队尾
+        let position = events.iter().rposition(|queued| queued.slot <= event.slot).map_or(0, |index| index + 1);
+        events.insert(position, event);
+        self.stats.record_depth(events.len());
+        drop(events);
+        self.not_empty.notify_one();
+        Some(outcome)
+    }
+
+    /// 取出最早的事件；队列关闭且排空后返回 None
+    pub async fn recv(&self) -> Option<TokenEvent> {
+        loop {
+            let not_empty = self.not_empty.notified();
+            tokio::pin!(not_empty);
+            not_empty.as_mut().enable();
+
+            if let Some(event) = self.lock().pop_front() {
+                self.stats.record_processed();
+                self.not_full.notify_one();
+                return Some(event);
+            }
+            if self.closed.load(Ordering::Acquire) {
+                return None;
+            }
+            not_empty.await;
+        }
+    }
+
+    /// 关闭队列：不再接受新事件，消费者排空剩余事件后退出
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        self.not_empty.notify_waiters();
+        self.not_full.notify_waiters();
+    }
+
+    pub fn stats(&self) -> StageSnapshot {
+        self.stats.snapshot("事件队列", self.capacity, self.len())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<TokenEvent>> {
+        self.events.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// 按 mint 分片的事件车道
+///
+/// 同一 mint 的事件总是进入同一条车道，由该车道唯一的工作任务顺序处理；
+/// 不同 mint 分布在各车道上并行处理。
+#[derive(Clone)]
+pub struct EventLanes {
+    lanes: Arc<Vec<EventQueue>>,
+}
+
+impl EventLanes {
+    fn new(lane_count: usize, total_capacity: usize) -> Self {
+        let lane_count = lane_count.max(1);
+        let lane_capacity = total_capacity.div_ceil(lane_count);
+        Self {
+            lanes: Arc::new((0..lane_count).map(|_| EventQueue::new(lane_capacity)).collect()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.lanes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lanes.is_empty()
+    }
+
+    /// 事件所属车道：按 mint 分片
+    pub fn lane_index(&self, event: &TokenEvent) -> usize {
+        let mut hasher = DefaultHasher::new();
+        event.mint().hash(&mut hasher);
+        (hasher.finish() % self.lanes.len() as u64) as usize
+    }
+
+    fn lane(&self, index: usize) -> &EventQueue {
+        &self.lanes[index]
+    }
+
+    async fn push(&self, event: TokenEvent) -> PushOutcome {
+        self.lane(self.lane_index(&event)).push(event).await
+    }
+
+    fn close(&self) {
+        for lane in self.lanes.iter() {
+            lane.close();
+        }
+    }
+
+    /// 所有车道合计的统计；峰值取单条车道的最大值
+    pub fn stats(&self) -> StageSnapshot {
+        self.lanes.iter().map(EventQueue::stats).fold(
+            StageSnapshot { stage: "事件队列", capacity: 0, depth: 0, max_depth: 0, processed: 0, dropped: 0 },
+            |total, lane| StageSnapshot {
+                stage: total.stage,
+                capacity: total.capacity + lane.capacity,
+                depth: total.depth + lane.depth,
+                max_depth: total.max_depth.max(lane.max_depth),
+                processed: total.processed + lane.processed,
+                dropped: total.dropped + lane.dropped,
+            },
+        )
+    }
+
+    /// 每条车道各自的统计，用于发现热点 mint 造成的车道倾斜
+    pub fn lane_stats(&self) -> Vec<StageSnapshot> {
+        self.lanes.iter().map(EventQueue::stats).collect()
+    }
+}
+
+/// 事件流与策略管理器之间的有界管道：每条车道一个工作任务
+pub struct EventPipeline {
+    lanes: EventLanes,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl EventPipeline {
+    pub fn start(config: &PipelineConfig, strategy_manager: Arc<OptimizedStrategyManager>) -> Self {
+        Self::with_handler(config, move |event| {
+            let strategy_manager = Arc::clone(&strategy_manager);
+            async move {
+                if let Err(e) = strategy_manager.handle_token_event(&event).await {
+                    error!("策略管理器处理事件失败: {}", e);
+                }
+            }
+        })
+    }
+
+    fn with_handler<F, Fut>(config: &PipelineConfig, handler: F) -> Self
+    where
+        F: Fn(TokenEvent) -> Fut + Clone + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let lanes = EventLanes::new(config.workers, config.event_queue_capacity);
+        let workers = (0..lanes.len())
+            .map(|index| {
+                let lanes = lanes.clone();
+                let handler = handler.clone();
+                tokio::spawn(async move {
+                    while let Some(event) = lanes.lane(index).recv().await {
+                        handler(event).await;
+                    }
+                })
+            })
+            .collect();
+
+        info!("🚰 事件管道已启动: 队列容量 {}, 车道 {}", config.event_queue_capacity, lanes.len());
+        Self { lanes, workers }
+    }
+
+    /// 提交事件到其 mint 所在车道；车道满时按溢出策略处理，创建事件会等待空位
+    pub async fn submit(&self, event: TokenEvent) -> PushOutcome {
+        self.lanes.push(event).await
+    }
+
+    pub fn stats(&self) -> StageSnapshot {
+        self.lanes.stats()
+    }
+
+    /// 共享的车道句柄，用于在其他任务中查询统计
+    pub fn lanes(&self) -> EventLanes {
+        self.lanes.clone()
+    }
+
+    /// 关闭所有车道并等待工作任务处理完剩余事件
+    pub async fn shutdown(self) {
+        self.lanes.close();
+        for worker in self.workers {
+            if let Err(e) = worker.await {
+                error!("事件管道工作任务异常退出: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processors::{Protocol, ProtocolEvent, TradeDirection, TransactionType};
+    use solana_sdk::pubkey::Pubkey;
+    use std::time::Duration;
+
+    fn event(signature: &str, transaction_type: TransactionType) -> TokenEvent {
+        event_for_mint(Pubkey::new_unique(), signature, transaction_type)
+    }
+
+    fn event_for_mint(mint: Pubkey, signature: &str, transaction_type: TransactionType) -> TokenEvent {
+        let wallet = Pubkey::default();
+        let trade = |direction| ProtocolEvent::PumpFunTrade { mint, trader: wallet, direction, sol_amount: 1, token_amount: 1 };
+        let kind = match transaction_type {
+            TransactionType::TokenCreation => ProtocolEvent::PumpFunCreate { mint, creator: wallet, metadata: None, dev_buy: None },
+            TransactionType::Buy => trade(TradeDirection::Buy),
+            TransactionType::Sell => trade(TradeDirection::Sell),
+            TransactionType::Unknown => ProtocolEvent::Migration { protocol: Protocol::PumpFun, mint, pool: None },
+        };
+        TokenEvent::new(signature, 1, kind)
+    }
+
+    #[tokio::test]
+    async fn test_full_queue_drops_oldest_trade_but_keeps_creations() {
+        let queue = EventQueue::new(3);
+        assert_eq!(queue.push(event("buy1", TransactionType::Buy)).await, PushOutcome::Queued);
+        assert_eq!(queue.push(event("create1", TransactionType::TokenCreation)).await, PushOutcome::Queued);
+        assert_eq!(queue.push(event("sell1", TransactionType::Sell)).await, PushOutcome::Queued);
+
+        assert_eq!(queue.push(event("create2", TransactionType::TokenCreation)).await, PushOutcome::QueuedDroppedOldest);
+        assert_eq!(queue.push(event("buy2", TransactionType::Buy)).await, PushOutcome::QueuedDroppedOldest);
+
+        let mut order = Vec::new();
+        while let Some(event) = queue.lock().pop_front() {
+            order.push(event.signature);
+        }
+        assert_eq!(order, vec!["create1", "create2", "buy2"]);
+
+        let stats = queue.stats();
+        assert_eq!(stats.dropped, 2);
+        assert_eq!(stats.max_depth, 3);
+    }
+
+    #[tokio::test]
+    async fn test_full_queue_keeps_migration() {
+        let queue = EventQueue::new(2);
+        queue.push(event("migrate", TransactionType::Unknown)).await;
+        queue.push(event("buy1", TransactionType::Buy)).await;
+
+        assert_eq!(queue.push(event("buy2", TransactionType::Buy)).await, PushOutcome::QueuedDroppedOldest);
+        assert_eq!(queue.push(event("sell", TransactionType::Sell)).await, PushOutcome::QueuedDroppedOldest);
+
+        let mut order = Vec::new();
+        while let Some(event) = queue.lock().pop_front() {
+            order.push(event.signature);
+        }
+        assert_eq!(order, vec!["migrate", "sell"]);
+        assert_eq!(queue.stats().dropped, 2);
+    }
+
+    #[tokio::test]
+    async fn test_creation_waits_for_space_when_queue_holds_only_creations() {
+        let queue = Arc::new(EventQueue::new(1));
+        queue.push(event("create1", TransactionType::TokenCreation)).await;
+        assert_eq!(queue.push(event("buy", TransactionType::Buy)).await, PushOutcome::Dropped);
+
+        let producer = {
+            let queue = Arc::clone(&queue);
+            tokio::spawn(async move { queue.push(event("create2", TransactionType::TokenCreation)).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!producer.is_finished());
+
+        assert_eq!(queue.recv().await.unwrap().signature, "create1");
+        assert_eq!(producer.await.unwrap(), PushOutcome::Queued);
+        assert_eq!(queue.recv().await.unwrap().signature, "create2");
+        assert_eq!(queue.stats().dropped, 1);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_drains_queue_with_all_workers() {
+        let handled = Arc::new(AtomicUsize::new(0));
+        let config = PipelineConfig { event_queue_capacity: 16, signal_queue_capacity: 1, workers: 3 };
+        let pipeline = EventPipeline::with_handler(&config, {
+            let handled = Arc::clone(&handled);
+            move |_event| {
+                let handled = Arc::clone(&handled);
+                async move {
+                    handled.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        });
+
+        for i in 0..10 {
+            pipeline.submit(event(&format!("sig{}", i), TransactionType::TokenCreation)).await;
+        }
+        let lanes = pipeline.lanes();
+        pipeline.shutdown().await;
+
+        assert_eq!(handled.load(Ordering::Relaxed), 10);
+        assert_eq!(lanes.stats().processed, 10);
+        assert_eq!(lanes.push(event("late", TransactionType::Buy)).await, PushOutcome::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_queue_orders_by_slot() {
+        let queue = EventQueue::new(8);
+        for (signature, slot) in [("a", 10), ("b", 12), ("c", 11), ("d", 12), ("e", 9)] {
+            let mut event = event(signature, TransactionType::Buy);
+            event.slot = slot;
+            queue.push(event).await;
+        }
+        queue.close();
+
+        let mut order = Vec::new();
+        while let Some(event) = queue.recv().await {
+            order.push(event.signature);
+        }
+        assert_eq!(order, vec!["e", "a", "c", "b", "d"]);
+    }
+
+    #[tokio::test]
+    async fn test_same_mint_events_are_handled_in_order() {
+        let handled = Arc::new(Mutex::new(Vec::new()));
+        let config = PipelineConfig { event_queue_capacity: 64, signal_queue_capacity: 1, workers: 4 };
+        let pipeline = EventPipeline::with_handler(&config, {
+            let handled = Arc::clone(&handled);
+            move |event: TokenEvent| {
+                let handled = Arc::clone(&handled);
+                async move {
+                    // 先到的事件处理得更慢，乱序执行时会被后到的超过
+                    if event.transaction_type() == TransactionType::Buy {
+                        tokio::time::sleep(Duration::from_millis(20)).await;
+                    }
+                    handled.lock().unwrap().push((event.mint(), event.signature));
+                }
+            }
+        });
+
+        let mints = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        for mint in mints {
+            for (signature, transaction_type) in [("create", TransactionType::TokenCreation), ("buy", TransactionType::Buy), ("sell", TransactionType::Sell)] {
+                pipeline.submit(event_for_mint(mint, &format!("{}-{}", mint, signature), transaction_type)).await;
+            }
+        }
+        let lanes = pipeline.lanes();
+        let mut probe = event_for_mint(mints[0], "probe", TransactionType::Buy);
+        let lane = lanes.lane_index(&probe);
+        probe.signature = "other".to_string();
+        assert_eq!(lanes.lane_index(&probe), lane);
+        pipeline.shutdown().await;
+
+        let handled = handled.lock().unwrap();
+        for mint in mints {
+            let order: Vec<&str> = handled.iter().filter(|(m, _)| *m == mint).map(|(_, signature)| signature.as_str()).collect();
+            assert_eq!(order, vec![format!("{}-create", mint), format!("{}-buy", mint), format!("{}-sell", mint)]);
+        }
+    }
+}
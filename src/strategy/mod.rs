@@ -7,6 +7,7 @@ pub mod position;
 pub mod optimized_token_filter;
 pub mod optimized_strategy_manager;
 pub mod optimized_trading_strategy;
+pub mod event_pipeline;
 
 use serde::{Deserialize, Serialize};
 
@@ -46,6 +47,10 @@ pub use trade_signal::{TradeSignal, TradeSignalType, SignalPriority};
 pub use position::{Position, PositionStatus, TradeRecord};
 
 // 优化后的导出
-pub use optimized_token_filter::{OptimizedTokenFilter, SimpleFilterResult, filter_token_optimized};
+pub use optimized_token_filter::{
+    Criterion, CriterionCheck, CriterionOutcome, FilterCriteria, OptimizedTokenFilter, SimpleFilterResult,
+    filter_token_optimized,
+};
 pub use optimized_strategy_manager::{OptimizedStrategyManager, OptimizedStrategyManagerStats};
-pub use optimized_trading_strategy::{OptimizedTradingStrategy, OptimizedPosition, OptimizedStrategyStatus, OptimizedPositionStatus};
\ No newline at end of file
+pub use optimized_trading_strategy::{OptimizedTradingStrategy, OptimizedPosition, OptimizedStrategyStatus, OptimizedPositionStatus, GraduatedPosition};
+pub use event_pipeline::{EventPipeline, EventLanes, EventQueue, OverflowPolicy, PushOutcome, StageSnapshot};
\ No newline at end of file
@@ -1,11 +1,12 @@
 use anyhow::Result;
 use dashmap::DashMap;
-use log::{info, warn, error};
+use log::{debug, info, warn, error};
 use solana_sdk::{pubkey::Pubkey, signer::Signer};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, RwLock};
 
+use crate::constant::accounts::TOKEN_PROGRAM;
 use crate::processors::TokenEvent;
 use crate::executor::ExecutionResult;
 use crate::executor::optimized_executor_manager::OptimizedExecutorManager;
@@ -13,10 +14,15 @@ use crate::executor::traits::TransactionExecutor;
 use crate::executor::compute_budget::{DynamicComputeBudgetManager, ComputeBudgetTier};
 use crate::executor::blockhash_cache::BlockhashCache;
 use crate::utils::TokenBalanceClient;
+use crate::streams::{AccountPriceStream, FeedFreshness, PriceUpdate, SharedStreamMetrics};
 use super::optimized_token_filter::OptimizedTokenFilter;
 use super::StrategyConfig;
 use super::{TradeSignal, TradeSignalType};
-use super::optimized_trading_strategy::{OptimizedTradingStrategy, OptimizedPosition};
+use super::optimized_trading_strategy::{GraduatedPosition, OptimizedTradingStrategy, OptimizedPosition};
+use super::event_pipeline::{StageSnapshot, StageStats};
+
+/// 默认的交易信号队列容量
+const DEFAULT_SIGNAL_QUEUE_CAPACITY: usize = 256;
 
 /// 优化后的策略管理器
 /// 
@@ -34,8 +40,11 @@ pub struct OptimizedStrategyManager {
     /// 策略计数器 - 原子操作，无锁
     strategy_count: Arc<AtomicUsize>,
     
-    /// 交易信号发送器
-    signal_sender: mpsc::UnboundedSender<TradeSignal>,
+    /// 交易信号发送器 - 有界队列，满时策略等待而不是无限堆积
+    signal_sender: mpsc::Sender<TradeSignal>,
+
+    /// 交易信号队列的处理计数
+    signal_stats: Arc<StageStats>,
     
     /// 默认策略配置
     default_config: StrategyConfig,
@@ -53,10 +62,22 @@ pub struct OptimizedStrategyManager {
     compute_budget_manager: Option<Arc<DynamicComputeBudgetManager>>,
     
     /// 🔧 修复：策略停止通知发送器 - 用于接收策略自动停止通知
-    strategy_stop_sender: mpsc::UnboundedSender<Pubkey>,
+    strategy_stop_sender: mpsc::Sender<Pubkey>,
+    
+    /// 毕业持仓通知发送器 - 策略把无法在 bonding curve 卖出的持仓交回管理器
+    graduation_sender: mpsc::Sender<GraduatedPosition>,
+    
+    /// 等待 AMM 路由卖出的毕业持仓
+    graduated_positions: Arc<DashMap<Pubkey, GraduatedPosition>>,
     
     /// 区块哈希缓存 - 用于区块对齐过滤
     blockhash_cache: Option<Arc<BlockhashCache>>,
+
+    /// 事件源的运行指标，用于查询数据新鲜度
+    feed_metrics: RwLock<Option<SharedStreamMetrics>>,
+
+    /// 持仓代币的曲线账户订阅，提供基于储备的实时价格
+    price_feed: RwLock<Option<Arc<AccountPriceStream>>>,
 }
 
 impl OptimizedStrategyManager {
@@ -68,11 +89,15 @@ impl OptimizedStrategyManager {
         token_filter: OptimizedTokenFilter,
         compute_budget_manager: Option<Arc<DynamicComputeBudgetManager>>, // 🆕 新增参数
         blockhash_cache: Option<Arc<BlockhashCache>>, // 区块哈希缓存参数
+        signal_queue_capacity: Option<usize>,
     ) -> Arc<Self> {
-        let (signal_tx, mut signal_rx) = mpsc::unbounded_channel();
+        let signal_queue_capacity = signal_queue_capacity.unwrap_or(DEFAULT_SIGNAL_QUEUE_CAPACITY).max(1);
+        let (signal_tx, mut signal_rx) = mpsc::channel(signal_queue_capacity);
+        let signal_stats = Arc::new(StageStats::default());
         
         // 🔧 修复：创建策略停止通知通道
-        let (strategy_stop_tx, mut strategy_stop_rx) = mpsc::unbounded_channel();
+        let (strategy_stop_tx, mut strategy_stop_rx) = mpsc::channel(signal_queue_capacity);
+        let (graduation_tx, mut graduation_rx) = mpsc::channel::<GraduatedPosition>(signal_queue_capacity);
         
         // 尝试创建代币余额查询客户端 - 增加详细的环境检查
         let token_balance_client = match TokenBalanceClient::from_env() {
@@ -122,13 +147,18 @@ impl OptimizedStrategyManager {
             strategies: Arc::new(DashMap::new()),
             strategy_count: Arc::new(AtomicUsize::new(0)),
             signal_sender: signal_tx,
+            signal_stats: signal_stats.clone(),
             default_config: default_config.unwrap_or_default(),
             max_concurrent_strategies: max_concurrent_strategies.unwrap_or(10),
             token_filter: Arc::new(token_filter),
             token_balance_client,
             compute_budget_manager, // 🆕 设置计算预算管理器
             strategy_stop_sender: strategy_stop_tx, // 🔧 修复：设置策略停止通知发送器
+            graduation_sender: graduation_tx,
+            graduated_positions: Arc::new(DashMap::new()),
             blockhash_cache, // 设置区块哈希缓存
+            feed_metrics: RwLock::new(None),
+            price_feed: RwLock::new(None),
         });
         
         // 启动信号处理循环
@@ -136,6 +166,9 @@ impl OptimizedStrategyManager {
         tokio::spawn(async move {
             info!("🔄 启动优化的交易信号处理循环");
             while let Some(signal) = signal_rx.recv().await {
+                signal_stats.record_depth(signal_rx.len() + 1);
+                signal_stats.record_processed();
+
                 // 🆕 在处理信号前应用计算预算设置
                 let enhanced_signal = manager_clone.apply_compute_budget_to_signal(signal);
                 
@@ -163,6 +196,21 @@ impl OptimizedStrategyManager {
             info!("🔚 策略停止通知处理循环已结束");
         });
         
+        // 毕业持仓处理循环：记录待 AMM 路由的持仓，并移除已无法交易的策略
+        let manager_clone_for_graduation = manager.clone();
+        tokio::spawn(async move {
+            info!("🔄 启动毕业持仓处理循环");
+            while let Some(position) = graduation_rx.recv().await {
+                info!("📨 毕业持仓待AMM路由: mint={}, 数量={}, 池={:?}", position.mint, position.token_amount, position.pool);
+                let mint = position.mint;
+                manager_clone_for_graduation.graduated_positions.insert(mint, position);
+                if let Err(e) = manager_clone_for_graduation.stop_strategy(&mint).await {
+                    error!("❌ 停止毕业代币策略失败: {}", e);
+                }
+            }
+            info!("🔚 毕业持仓处理循环已结束");
+        });
+        
         manager
     }
     
@@ -219,6 +267,7 @@ impl OptimizedStrategyManager {
         if let Some((_, strategy_arc)) = self.strategies.remove(mint) {
             // 原子减少计数器
             self.strategy_count.fetch_sub(1, Ordering::Release);
+            self.unwatch_price(mint).await;
             
             // 停止策略
             info!("⏹️ 停止优化策略: mint={}", mint);
@@ -243,17 +292,14 @@ impl OptimizedStrategyManager {
     /// 4. 🔧 新增：提取真实价格信息
     /// 5. 区块对齐过滤
     pub async fn handle_token_event(&self, event: &TokenEvent) -> Result<()> {
-        let mint = if let Some(mint_str) = &event.mint {
-            mint_str.parse::<Pubkey>()?
-        } else {
-            return Ok(()); // 没有mint信息，跳过
-        };
+        let mint = event.mint();
 
         // 🆕 区块对齐检查 - 在处理代币创建事件前进行区块对齐过滤
-        if matches!(event.transaction_type, crate::processors::TransactionType::TokenCreation) {
+        if event.is_creation() {
             if let Some(ref blockhash_cache) = self.blockhash_cache {
                 if let Some(event_block_height) = event.block_height {
-                    match blockhash_cache.get_current_slot().await {
+                    // 当前slot由缓存的订阅维护，直接读内存，不做RPC往返
+                    match blockhash_cache.get_cached_slot() {
                         Ok(current_slot) => {
                             let block_diff = current_slot.saturating_sub(event_block_height);
                             const MAX_BLOCK_DIFF: u64 = 1000; // 最大允许相差10个区块
@@ -279,7 +325,14 @@ impl OptimizedStrategyManager {
 
         // 🔧 新增：从事件中提取价格信息
         let price_info = self.extract_price_from_event(event);
-        info!("接收到代币事件: {:?}", event.mint);
+        info!("接收到代币事件: {} ({})", mint, event.kind.name());
+
+        // 迁移事件带来目标池，补全已上报的毕业持仓
+        if let Some(pool) = event.kind.migration_pool() {
+            if let Some(mut position) = self.graduated_positions.get_mut(&mint) {
+                position.pool = Some(pool);
+            }
+        }
 
         // 快速检查：是否已有该代币的策略
         if let Some(strategy_arc) = self.strategies.get(&mint) {
@@ -292,12 +345,13 @@ impl OptimizedStrategyManager {
         }
 
         // 仅处理代币创建事件
-        if !matches!(event.transaction_type, crate::processors::TransactionType::TokenCreation) {
+        if !event.is_creation() {
             return Ok(());
         }
 
-        // 使用无状态过滤器进行快速评估
-        let filter_result = self.token_filter.evaluate_token_fast(event);
+        // 使用无状态过滤器进行快速评估，有缓存slot时同时检查创建时长
+        let current_slot = self.blockhash_cache.as_ref().and_then(|cache| cache.get_cached_slot().ok());
+        let filter_result = self.token_filter.evaluate_token(event, current_slot);
         
         if filter_result.passed {
             info!("🎯 ✅ 代币通过优化筛选!");
@@ -316,26 +370,16 @@ impl OptimizedStrategyManager {
             }
             
             // 🔧 新增：提取创建者地址
-            let creator_addr = if let Some(creator_str) = &event.creator_wallet {
-                match creator_str.parse::<Pubkey>() {
-                    Ok(addr) => {
-                        info!("👤 找到代币创建者: {}", creator_str);
-                        Some(addr)
-                    }
-                    Err(e) => {
-                        warn!("⚠️ 解析创建者地址失败: {} - {}", creator_str, e);
-                        None
-                    }
-                }
-            } else {
-                warn!("⚠️ 事件中缺少创建者地址信息");
-                None
-            };
+            let creator_addr = event.wallet();
+            match creator_addr {
+                Some(creator) => info!("👤 找到代币创建者: {}", creator),
+                None => warn!("⚠️ 事件中缺少创建者地址信息"),
+            }
             
             // 克隆 price_info 用于后续使用
             let price_info_clone = price_info.clone();
             
-            match self.create_strategy_for_token(mint, Some(strategy_config), price_info_clone.clone(), creator_addr).await {
+            match self.create_strategy_for_token(mint, Some(strategy_config), price_info_clone.clone(), creator_addr, event.token_program).await {
                 Ok(_) => {
                     info!("🎉 ✅ 优化交易策略创建成功!");
                     info!("   🪙 代币地址: {}", mint);
@@ -355,58 +399,22 @@ impl OptimizedStrategyManager {
             }
         } else {
             info!("❌ 代币未通过优化筛选: mint={}, 原因={}", mint, filter_result.reason);
+            for check in filter_result.failed_checks() {
+                debug!("   ❌ {:?}: {:?}", check.criterion, check.outcome);
+            }
         }
         Ok(())
     }
 
     /// 🔧 新增：从 TokenEvent 中提取价格信息
+    ///
+    /// 已确认交易带有链上事件中的成交后储备，价格是精确的；
+    /// 未确认交易只有指令参数（含滑点上限），只作为建仓前的估算。
+    /// 持仓后由曲线账户订阅推送基于储备的价格。
     fn extract_price_from_event(&self, event: &TokenEvent) -> Option<(f64, String)> {
-        if let (Some(sol_amount), Some(token_amount)) = (event.sol_amount, event.token_amount) {
-            if token_amount > 0 {
-                let raw_price = sol_amount as f64 / token_amount as f64;
-                
-                // 根据检测方法和交易类型调整价格
-                let (adjusted_price, source) = match event.detection_method.as_str() {
-                    // PumpFun 协议
-                    s if s.contains("pumpfun") => {
-                        let price = match event.transaction_type {
-                            crate::processors::TransactionType::Buy => raw_price * 0.95,    // max_cost 的95%
-                            crate::processors::TransactionType::Sell => raw_price * 1.05,   // min_output 的105%
-                            _ => raw_price
-                        };
-                        (price, format!("PumpFun-{:?}", event.transaction_type))
-                    }
-                    // LetsBonk (Raydium Launchpad) 协议
-                    s if s.contains("Raydium Launchpad") => {
-                        let price = match event.transaction_type {
-                            crate::processors::TransactionType::Buy => {
-                                if s.contains("Exact In") {
-                                    raw_price * 1.02  // exact_in 稍微上调
-                                } else {
-                                    raw_price * 0.98  // exact_out 稍微下调
-                                }
-                            }
-                            crate::processors::TransactionType::Sell => {
-                                if s.contains("Exact In") {
-                                    raw_price * 0.98  // exact_in 稍微下调
-                                } else {
-                                    raw_price * 1.02  // exact_out 稍微上调
-                                }
-                            }
-                            _ => raw_price
-                        };
-                        (price, format!("Raydium-{:?}", event.transaction_type))
-                    }
-                    _ => (raw_price, "Unknown".to_string())
-                };
-                
-                Some((adjusted_price, source))
-            } else {
-                None
-            }
-        } else {
-            None
-        }
+        let price = event.price()?;
+        let source = if event.reserves.is_some() { "Reserves" } else { "Event" };
+        Some((price, format!("{}-{}", source, event.kind.name())))
     }
 
     /// 🔧 新增：创建带价格和创建者信息的策略
@@ -416,6 +424,7 @@ impl OptimizedStrategyManager {
         config: Option<StrategyConfig>,
         price_info: Option<(f64, String)>,
         creator: Option<Pubkey>,
+        token_program: Option<Pubkey>,
     ) -> Result<String> {
         // 原子检查策略数量限制，无锁操作
         let current_count = self.strategy_count.load(Ordering::Acquire);
@@ -444,6 +453,10 @@ impl OptimizedStrategyManager {
             price_info,
             creator,
         );
+        let strategy = match token_program {
+            Some(token_program) => strategy.with_token_program(token_program),
+            None => strategy,
+        };
 
         let strategy_id = strategy.id.clone();
         
@@ -458,6 +471,7 @@ impl OptimizedStrategyManager {
 
         // 🔧 修复：为策略设置停止通知发送器
         strategy.set_strategy_stop_notifier(self.strategy_stop_sender.clone()).await;
+        strategy.set_graduation_notifier(self.graduation_sender.clone()).await;
 
         // 启动策略
         strategy.run().await?;
@@ -474,6 +488,7 @@ impl OptimizedStrategyManager {
                 info!("✅ 优化策略 {} 已创建并启动", strategy_id);
             }
         }
+        self.watch_price(mint).await;
         
         Ok(strategy_id)
     }
@@ -519,6 +534,7 @@ impl OptimizedStrategyManager {
 
         // 🔧 修复：为策略设置停止通知发送器
         strategy.set_strategy_stop_notifier(self.strategy_stop_sender.clone()).await;
+        strategy.set_graduation_notifier(self.graduation_sender.clone()).await;
 
         // 启动策略
         strategy.run().await?;
@@ -535,10 +551,83 @@ impl OptimizedStrategyManager {
                 info!("✅ 优化策略 {} 已创建并启动", strategy_id);
             }
         }
+        self.watch_price(mint).await;
         
         Ok(strategy_id)
     }
 
+    /// 关联事件源指标，之后可通过 `feed_freshness` 查询数据是否新鲜
+    pub async fn attach_feed_metrics(&self, metrics: SharedStreamMetrics) {
+        *self.feed_metrics.write().await = Some(metrics);
+    }
+
+    /// 事件流新鲜度；未关联事件源时返回 None
+    pub async fn feed_freshness(&self) -> Option<FeedFreshness> {
+        let metrics = self.feed_metrics.read().await.clone()?;
+        let freshness = metrics.read().await.freshness();
+        Some(freshness)
+    }
+
+    /// 关联曲线账户订阅：为所有持仓代币订阅账户，并把价格推送给对应策略
+    pub async fn attach_price_feed(self: &Arc<Self>, feed: Arc<AccountPriceStream>) {
+        for entry in self.strategies.iter() {
+            feed.watch(*entry.key());
+        }
+        let mut updates = feed.start();
+        *self.price_feed.write().await = Some(feed);
+
+        let manager = Arc::clone(self);
+        tokio::spawn(async move {
+            info!("🔄 启动曲线账户价格处理循环");
+            while let Some(update) = updates.recv().await {
+                manager.apply_price_update(update).await;
+            }
+            info!("🔚 曲线账户价格处理循环已结束");
+        });
+    }
+
+    /// 把账户推送的价格交给对应策略
+    async fn apply_price_update(&self, update: PriceUpdate) {
+        // 先克隆出策略，避免跨 await 持有 DashMap 的读锁
+        let Some(strategy) = self.strategies.get(&update.mint).map(|entry| Arc::clone(entry.value())) else {
+            return;
+        };
+        if update.complete {
+            // 账户推送只知道曲线结束，目标池由后续的迁移事件补全
+            strategy.mark_graduated(None).await;
+        }
+        strategy.update_price(update.price, update.source).await;
+    }
+
+    async fn watch_price(&self, mint: Pubkey) {
+        if let Some(feed) = self.price_feed.read().await.as_ref() {
+            feed.watch(mint);
+        }
+    }
+
+    async fn unwatch_price(&self, mint: &Pubkey) {
+        if let Some(feed) = self.price_feed.read().await.as_ref() {
+            feed.unwatch(mint);
+        }
+    }
+
+    /// 等待 AMM 路由卖出的毕业持仓
+    pub fn graduated_positions(&self) -> Vec<GraduatedPosition> {
+        self.graduated_positions.iter().map(|entry| entry.value().clone()).collect()
+    }
+
+    /// 取走某个毕业持仓，由 AMM 路由负责卖出
+    pub fn take_graduated_position(&self, mint: &Pubkey) -> Option<GraduatedPosition> {
+        self.graduated_positions.remove(mint).map(|(_, position)| position)
+    }
+
+    /// 交易信号队列的统计快照
+    pub fn signal_queue_stats(&self) -> StageSnapshot {
+        let capacity = self.signal_sender.max_capacity();
+        let depth = capacity - self.signal_sender.capacity();
+        self.signal_stats.snapshot("信号队列", capacity, depth)
+    }
+
     /// 获取活跃策略数量 - 原子操作，无锁
     pub fn get_active_strategy_count(&self) -> usize {
         self.strategy_count.load(Ordering::Acquire)
@@ -576,6 +665,14 @@ impl OptimizedStrategyManager {
         info!("   💰 默认买入金额: {:.4} SOL", self.default_config.buy_amount_lamports as f64 / 1_000_000_000.0);
         info!("   ⏱️ 默认持仓时长: {}秒", self.default_config.holding_duration_seconds);
         info!("   🚀 使用优化架构: DashMap + 无状态过滤器");
+        if let Some(freshness) = self.feed_freshness().await {
+            info!("   📡 事件流: {} | 最近消息: {:?} 前 | {:.1} 条/秒",
+                  if freshness.is_connected { "已连接" } else { "未连接" },
+                  freshness.last_message_age,
+                  freshness.messages_per_sec);
+        }
+
+        self.signal_queue_stats().print();
 
         if strategy_count > 0 {
             info!("   📋 活跃策略列表:");
@@ -635,7 +732,7 @@ impl OptimizedStrategyManager {
                 if let Some(strategy_manager) = strategy_manager {
                     let token_amount = if is_buy {
                         // 🔧 重构：移除固定汇率回退，强制使用真实数据
-                        match strategy_manager.get_token_amount_from_buy_result(&result, &signal.mint, &executor).await {
+                        match strategy_manager.get_token_amount_from_buy_result(&result, &signal.mint, &signal.token_program.unwrap_or(TOKEN_PROGRAM), &executor).await {
                             Ok(actual_tokens) => {
                                 info!("✅ 获取实际代币数量成功: {} tokens", actual_tokens);
                                 actual_tokens
@@ -719,6 +816,7 @@ impl OptimizedStrategyManager {
         let mut stop_tasks = Vec::new();
         
         for (mint, strategy_arc) in strategies_to_stop {
+            self.unwatch_price(&mint).await;
             let stop_task = tokio::spawn(async move {
                 info!("⏹️ 停止优化策略: mint={}", mint);
                 if let Err(e) = strategy_arc.stop().await {
@@ -776,6 +874,7 @@ impl OptimizedStrategyManager {
         &self, 
         result: &ExecutionResult, 
         mint: &Pubkey, 
+        token_program: &Pubkey,
         executor: &Arc<OptimizedExecutorManager>
     ) -> Result<u64> {
         if let Some(balance_client) = &self.token_balance_client {
@@ -786,7 +885,7 @@ impl OptimizedStrategyManager {
                 info!("   钱包地址: {}", wallet_pubkey);
                 
                 // 🆕 关键修复：获取与买入交易使用完全相同的代币账户地址
-                match executor.get_user_token_account_for_mint(mint, &wallet_pubkey).await {
+                match executor.get_user_token_account_for_mint(mint, &wallet_pubkey, token_program).await {
                     Ok(token_account) => {
                         info!("   代币账户: {}", token_account);
                         
@@ -1,17 +1,26 @@
-use log::info;
+use aho_corasick::AhoCorasick;
+use anyhow::{Context, Result};
+use log::debug;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashSet;
 
 use crate::processors::{TokenEvent, TransactionType};
 
+/// 关键词中以该前缀开头的条目按正则表达式编译，其余按字面子串匹配
+pub const REGEX_KEYWORD_PREFIX: &str = "re:";
+
 /// 优化后的无状态代币过滤器
-/// 
+///
 /// 关键优化点：
 /// 1. 去除了 mint_scores 状态，避免写锁需求
-/// 2. 预编译关键词集合，提升匹配性能
+/// 2. 预编译关键词集合（aho-corasick 多模式 + 正则），提升匹配性能
 /// 3. 简化评估逻辑，专注于二元判断而非评分
 #[derive(Debug, Clone)]
 pub struct OptimizedTokenFilter {
-    pub criteria: FilterCriteria,
+    criteria: FilterCriteria,
+    matchers: CompiledCriteria,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,44 +28,213 @@ pub struct FilterCriteria {
     // 基础筛选条件
     pub min_sol_amount: Option<u64>,
     pub max_sol_amount: Option<u64>,
-    
+
     // 代币名称筛选
     pub required_name_keywords: Vec<String>,
     pub forbidden_name_keywords: Vec<String>,
     pub min_name_length: Option<usize>,
     pub max_name_length: Option<usize>,
-    
+
     // 代币符号筛选
     pub required_symbol_keywords: Vec<String>,
     pub forbidden_symbol_keywords: Vec<String>,
     pub min_symbol_length: Option<usize>,
     pub max_symbol_length: Option<usize>,
-    
+
     // 时间相关筛选
     pub max_creation_age_slots: Option<u64>,
-    
+
     // 交易类型筛选
     pub allowed_transaction_types: Vec<TransactionType>,
-    
+
     // 黑白名单
     pub whitelist_mints: Vec<String>,
     pub blacklist_mints: Vec<String>,
     pub blacklist_programs: Vec<String>,
 }
 
+/// 单项筛选条件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Criterion {
+    TransactionType,
+    BlacklistMint,
+    WhitelistMint,
+    BlacklistProgram,
+    SolAmount,
+    NameLength,
+    RequiredNameKeywords,
+    ForbiddenNameKeywords,
+    SymbolLength,
+    RequiredSymbolKeywords,
+    ForbiddenSymbolKeywords,
+    CreationAge,
+}
+
+/// 单项条件的判定结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CriterionOutcome {
+    Passed(String),
+    Failed(String),
+    /// 未配置或事件缺少所需信息
+    Skipped(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CriterionCheck {
+    pub criterion: Criterion,
+    pub outcome: CriterionOutcome,
+}
+
+impl CriterionCheck {
+    pub fn is_failed(&self) -> bool {
+        matches!(self.outcome, CriterionOutcome::Failed(_))
+    }
+}
+
 /// 简化的过滤结果，只关注通过/不通过
 #[derive(Debug, Clone)]
 pub struct SimpleFilterResult {
     pub passed: bool,
+    /// 第一个未通过条件的原因
     pub reason: String,
     pub matched_criteria: Vec<String>,
+    /// 每一项条件的判定结果
+    pub checks: Vec<CriterionCheck>,
+}
+
+impl SimpleFilterResult {
+    pub fn failed_checks(&self) -> impl Iterator<Item = &CriterionCheck> {
+        self.checks.iter().filter(|check| check.is_failed())
+    }
+}
+
+/// 预编译的关键词匹配器：字面关键词走 aho-corasick，`re:` 前缀的条目走正则
+#[derive(Debug, Clone, Default)]
+struct KeywordMatcher {
+    literals: Option<AhoCorasick>,
+    literal_keywords: Vec<String>,
+    patterns: Vec<(Regex, String)>,
+}
+
+impl KeywordMatcher {
+    fn new(keywords: &[String]) -> Result<Self> {
+        let mut literal_keywords = Vec::new();
+        let mut patterns = Vec::new();
+
+        for keyword in keywords {
+            if let Some(pattern) = keyword.strip_prefix(REGEX_KEYWORD_PREFIX) {
+                // 正则同样作用于规范化后的文本，默认不区分大小写
+                let regex = Regex::new(&format!("(?i){}", pattern))
+                    .with_context(|| format!("无效的关键词正则: {}", keyword))?;
+                patterns.push((regex, keyword.clone()));
+            } else {
+                let normalized = normalize_text(keyword);
+                if !normalized.is_empty() {
+                    literal_keywords.push(normalized);
+                }
+            }
+        }
+
+        let literals = if literal_keywords.is_empty() {
+            None
+        } else {
+            Some(AhoCorasick::new(&literal_keywords).context("构建关键词自动机失败")?)
+        };
+
+        Ok(Self { literals, literal_keywords, patterns })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.literals.is_none() && self.patterns.is_empty()
+    }
+
+    /// 在规范化文本中查找第一个命中的关键词
+    fn find(&self, normalized: &str) -> Option<&str> {
+        if let Some(found) = self.literals.as_ref().and_then(|literals| literals.find(normalized)) {
+            return Some(&self.literal_keywords[found.pattern().as_usize()]);
+        }
+        self.patterns
+            .iter()
+            .find(|(regex, _)| regex.is_match(normalized))
+            .map(|(_, keyword)| keyword.as_str())
+    }
+}
+
+/// 构造时编译好的条件，评估时只读
+#[derive(Debug, Clone, Default)]
+struct CompiledCriteria {
+    required_name: KeywordMatcher,
+    forbidden_name: KeywordMatcher,
+    required_symbol: KeywordMatcher,
+    forbidden_symbol: KeywordMatcher,
+    whitelist_mints: HashSet<Pubkey>,
+    blacklist_mints: HashSet<Pubkey>,
+    blacklist_programs: HashSet<Pubkey>,
+}
+
+impl CompiledCriteria {
+    fn new(criteria: &FilterCriteria) -> Result<Self> {
+        let pubkeys = |list: &[String], what: &str| -> Result<HashSet<Pubkey>> {
+            list.iter()
+                .map(|address| address.trim().parse::<Pubkey>().with_context(|| format!("{}中的地址无效: {}", what, address)))
+                .collect()
+        };
+
+        Ok(Self {
+            required_name: KeywordMatcher::new(&criteria.required_name_keywords)?,
+            forbidden_name: KeywordMatcher::new(&criteria.forbidden_name_keywords)?,
+            required_symbol: KeywordMatcher::new(&criteria.required_symbol_keywords)?,
+            forbidden_symbol: KeywordMatcher::new(&criteria.forbidden_symbol_keywords)?,
+            whitelist_mints: pubkeys(&criteria.whitelist_mints, "mint白名单")?,
+            blacklist_mints: pubkeys(&criteria.blacklist_mints, "mint黑名单")?,
+            blacklist_programs: pubkeys(&criteria.blacklist_programs, "程序黑名单")?,
+        })
+    }
+}
+
+/// 名称/符号规范化：全角字符转半角、去掉零宽与控制字符、合并空白并转小写
+///
+/// 用于抵御 "ＳＣＡＭ"、"sc\u{200b}am" 之类绕过关键词的写法。
+pub fn normalize_text(text: &str) -> String {
+    let mut normalized = String::with_capacity(text.len());
+    let mut pending_space = false;
+
+    for c in text.chars() {
+        let c = match c {
+            // 全角 ASCII 区段
+            '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+            '\u{3000}' => ' ',
+            _ => c,
+        };
+        if matches!(c, '\u{200B}'..='\u{200D}' | '\u{2060}' | '\u{FEFF}' | '\u{00AD}') || (c.is_control() && !c.is_whitespace()) {
+            continue;
+        }
+        if c.is_whitespace() {
+            pending_space = !normalized.is_empty();
+            continue;
+        }
+        if pending_space {
+            normalized.push(' ');
+            pending_space = false;
+        }
+        normalized.extend(c.to_lowercase());
+    }
+
+    normalized
 }
 
 impl OptimizedTokenFilter {
-    pub fn new(criteria: FilterCriteria) -> Self {
-        Self {
+    /// 编译条件中的关键词与地址列表；正则或地址无效时返回错误
+    pub fn new(criteria: FilterCriteria) -> Result<Self> {
+        let matchers = CompiledCriteria::new(&criteria)?;
+        Ok(Self {
             criteria,
-        }
+            matchers,
+        })
+    }
+
+    pub fn criteria(&self) -> &FilterCriteria {
+        &self.criteria
     }
 
     /// 默认狙击策略（高性能版本）
@@ -64,7 +242,7 @@ impl OptimizedTokenFilter {
         let criteria = FilterCriteria {
             min_sol_amount: Some(1000_000_000),     // 1 SOL
             max_sol_amount: Some(10_000_000_000),  // 100 SOL
-            
+
             required_name_keywords: vec![],
             forbidden_name_keywords: vec![
                 "test".to_string(), "fake".to_string(), "scam".to_string(),
@@ -73,79 +251,218 @@ impl OptimizedTokenFilter {
             ],
             min_name_length: Some(2),
             max_name_length: Some(30),
-            
+
             required_symbol_keywords: vec![],
             forbidden_symbol_keywords: vec![
                 "test".to_string(), "fake".to_string(), "scam".to_string(),
             ],
             min_symbol_length: Some(1),
             max_symbol_length: Some(10),
-            
+
             max_creation_age_slots: Some(100),
-            
+
             allowed_transaction_types: vec![TransactionType::TokenCreation],
-            
+
             whitelist_mints: vec![],
             blacklist_mints: vec![],
             blacklist_programs: vec![],
         };
 
-        Self::new(criteria)
+        Self::new(criteria).expect("默认狙击策略条件应当有效")
     }
 
-    /// 快速评估代币 - 无状态，仅需读取访问
-    /// 
+    /// 快速评估代币 - 无状态，仅需读取访问；不检查创建时长
+    pub fn evaluate_token_fast(&self, token_event: &TokenEvent) -> SimpleFilterResult {
+        self.evaluate_token(token_event, None)
+    }
+
+    /// 评估代币的所有条件
+    ///
     /// 优化点：
-    /// 1. 不再计算复杂评分，只做二元判断
+    /// 1. 关键词和地址在构造时预编译，评估时不分配正则或自动机
     /// 2. 不更新任何状态，完全无锁
-    /// 3. 短路求值，快速排除不符合条件的代币
-    pub fn evaluate_token_fast(&self, token_event: &TokenEvent) -> SimpleFilterResult {
-        let mut matched_criteria = Vec::new();
-
-        // 1. 快速检查：交易类型
-        if !self.criteria.allowed_transaction_types.contains(&token_event.transaction_type) {
-            return SimpleFilterResult {
-                passed: false,
-                reason: format!("交易类型不匹配: {:?}", token_event.transaction_type),
-                matched_criteria,
-            };
+    /// 3. 所有条件都会被评估，结果中列出每一项的判定
+    ///
+    /// 白名单中的 mint 只需满足交易类型和黑名单；`current_slot` 为空时跳过创建时长检查。
+    pub fn evaluate_token(&self, token_event: &TokenEvent, current_slot: Option<u64>) -> SimpleFilterResult {
+        let mint = token_event.mint();
+        let whitelisted = self.matchers.whitelist_mints.contains(&mint);
+        let metadata = token_event.kind.metadata();
+        let name = metadata.map(|metadata| normalize_text(&metadata.name));
+        let symbol = metadata.map(|metadata| normalize_text(&metadata.symbol));
+
+        let mut checks = vec![
+            self.check_transaction_type(token_event),
+            self.check_blacklist_mint(&mint),
+            CriterionCheck {
+                criterion: Criterion::WhitelistMint,
+                outcome: if whitelisted {
+                    CriterionOutcome::Passed("mint在白名单中，跳过内容检查".to_string())
+                } else {
+                    CriterionOutcome::Skipped("mint不在白名单中".to_string())
+                },
+            },
+            self.check_blacklist_programs(token_event),
+        ];
+
+        if !whitelisted {
+            checks.push(self.check_sol_amount(token_event));
+            checks.push(check_length(Criterion::NameLength, "名称", name.as_deref(), self.criteria.min_name_length, self.criteria.max_name_length));
+            checks.push(check_required(Criterion::RequiredNameKeywords, "名称", name.as_deref(), &self.matchers.required_name));
+            checks.push(check_forbidden(Criterion::ForbiddenNameKeywords, "名称", name.as_deref(), &self.matchers.forbidden_name));
+            checks.push(check_length(Criterion::SymbolLength, "符号", symbol.as_deref(), self.criteria.min_symbol_length, self.criteria.max_symbol_length));
+            checks.push(check_required(Criterion::RequiredSymbolKeywords, "符号", symbol.as_deref(), &self.matchers.required_symbol));
+            checks.push(check_forbidden(Criterion::ForbiddenSymbolKeywords, "符号", symbol.as_deref(), &self.matchers.forbidden_symbol));
+            checks.push(self.check_creation_age(token_event, current_slot));
         }
-        matched_criteria.push("交易类型匹配".to_string());
-
-        // 3. SOL金额检查
-        if let Some(sol_amount) = token_event.sol_amount {
-            info!("SOL金额: {}", sol_amount);
-            info!("min_sol_amount: {:?}", self.criteria.min_sol_amount);
-            if let Some(min) = self.criteria.min_sol_amount {
-                if sol_amount < min {
-                    return SimpleFilterResult {
-                        passed: false,
-                        reason: format!("SOL金额过低: {} < {}", sol_amount, min),
-                        matched_criteria,
-                    };
+
+        let first_failure = checks.iter().find_map(|check| match &check.outcome {
+            CriterionOutcome::Failed(reason) => Some(reason.clone()),
+            _ => None,
+        });
+        let matched_criteria = checks
+            .iter()
+            .filter_map(|check| match &check.outcome {
+                CriterionOutcome::Passed(detail) => Some(detail.clone()),
+                _ => None,
+            })
+            .collect();
+
+        match first_failure {
+            Some(reason) => {
+                debug!("❌ 代币未通过快速筛选: mint={}, 原因={}", mint, reason);
+                SimpleFilterResult { passed: false, reason, matched_criteria, checks }
+            }
+            None => {
+                debug!("✅ 代币通过快速筛选: mint={}", mint);
+                SimpleFilterResult {
+                    passed: true,
+                    reason: "所有条件满足".to_string(),
+                    matched_criteria,
+                    checks,
                 }
             }
-            
-            if let Some(max) = self.criteria.max_sol_amount {
-                if sol_amount > max {
-                    return SimpleFilterResult {
-                        passed: false,
-                        reason: format!("SOL金额过高: {} > {}", sol_amount, max),
-                        matched_criteria,
-                    };
+        }
+    }
+
+    fn check_transaction_type(&self, token_event: &TokenEvent) -> CriterionCheck {
+        let transaction_type = token_event.transaction_type();
+        let outcome = if self.criteria.allowed_transaction_types.contains(&transaction_type) {
+            CriterionOutcome::Passed("交易类型匹配".to_string())
+        } else {
+            CriterionOutcome::Failed(format!("交易类型不匹配: {:?}", transaction_type))
+        };
+        CriterionCheck { criterion: Criterion::TransactionType, outcome }
+    }
+
+    fn check_blacklist_mint(&self, mint: &Pubkey) -> CriterionCheck {
+        let outcome = if self.matchers.blacklist_mints.is_empty() {
+            CriterionOutcome::Skipped("未配置mint黑名单".to_string())
+        } else if self.matchers.blacklist_mints.contains(mint) {
+            CriterionOutcome::Failed(format!("mint在黑名单中: {}", mint))
+        } else {
+            CriterionOutcome::Passed("mint不在黑名单中".to_string())
+        };
+        CriterionCheck { criterion: Criterion::BlacklistMint, outcome }
+    }
+
+    fn check_blacklist_programs(&self, token_event: &TokenEvent) -> CriterionCheck {
+        let outcome = if self.matchers.blacklist_programs.is_empty() {
+            CriterionOutcome::Skipped("未配置程序黑名单".to_string())
+        } else if let Some(program) = token_event
+            .account_keys
+            .iter()
+            .find(|key| self.matchers.blacklist_programs.contains(key))
+        {
+            CriterionOutcome::Failed(format!("交易涉及黑名单程序: {}", program))
+        } else {
+            CriterionOutcome::Passed("未涉及黑名单程序".to_string())
+        };
+        CriterionCheck { criterion: Criterion::BlacklistProgram, outcome }
+    }
+
+    fn check_sol_amount(&self, token_event: &TokenEvent) -> CriterionCheck {
+        // 创建事件按开发者买入金额判断，没有开发者买入视为 0
+        let sol_amount = if token_event.is_creation() {
+            Some(token_event.dev_buy().map_or(0, |buy| buy.sol_amount))
+        } else {
+            token_event.sol_amount()
+        };
+
+        let outcome = match (sol_amount, self.criteria.min_sol_amount, self.criteria.max_sol_amount) {
+            (_, None, None) => CriterionOutcome::Skipped("未配置SOL金额范围".to_string()),
+            (None, _, _) => CriterionOutcome::Skipped("事件没有SOL金额".to_string()),
+            (Some(sol_amount), Some(min), _) if sol_amount < min => {
+                CriterionOutcome::Failed(format!("SOL金额过低: {} < {}", sol_amount, min))
+            }
+            (Some(sol_amount), _, Some(max)) if sol_amount > max => {
+                CriterionOutcome::Failed(format!("SOL金额过高: {} > {}", sol_amount, max))
+            }
+            (Some(_), _, _) => CriterionOutcome::Passed("SOL金额在范围内".to_string()),
+        };
+        CriterionCheck { criterion: Criterion::SolAmount, outcome }
+    }
+
+    fn check_creation_age(&self, token_event: &TokenEvent, current_slot: Option<u64>) -> CriterionCheck {
+        let outcome = match (self.criteria.max_creation_age_slots, current_slot) {
+            (None, _) => CriterionOutcome::Skipped("未配置创建时长上限".to_string()),
+            (Some(_), None) => CriterionOutcome::Skipped("当前slot未知".to_string()),
+            (Some(max_age), Some(current_slot)) => {
+                let age = current_slot.saturating_sub(token_event.slot);
+                if age > max_age {
+                    CriterionOutcome::Failed(format!("创建时间过久: {} slots > {}", age, max_age))
+                } else {
+                    CriterionOutcome::Passed(format!("创建于 {} slots 内", age))
                 }
             }
-            matched_criteria.push("SOL金额在范围内".to_string());
+        };
+        CriterionCheck { criterion: Criterion::CreationAge, outcome }
+    }
+}
+
+/// 按字符数检查长度
+fn check_length(criterion: Criterion, field: &str, text: Option<&str>, min: Option<usize>, max: Option<usize>) -> CriterionCheck {
+    let outcome = match (text, min, max) {
+        (_, None, None) => CriterionOutcome::Skipped(format!("未配置{}长度范围", field)),
+        (None, _, _) => CriterionOutcome::Skipped(format!("事件没有{}", field)),
+        (Some(text), min, max) => {
+            let length = text.chars().count();
+            if min.is_some_and(|min| length < min) {
+                CriterionOutcome::Failed(format!("{}过短: {} < {}", field, length, min.unwrap_or_default()))
+            } else if max.is_some_and(|max| length > max) {
+                CriterionOutcome::Failed(format!("{}过长: {} > {}", field, length, max.unwrap_or_default()))
+            } else {
+                CriterionOutcome::Passed(format!("{}长度在范围内", field))
+            }
         }
+    };
+    CriterionCheck { criterion, outcome }
+}
 
-        // 所有检查通过
-        info!("✅ 代币通过快速筛选: mint={:?}", token_event.mint);
-        SimpleFilterResult {
-            passed: true,
-            reason: "所有条件满足".to_string(),
-            matched_criteria,
+/// 配置了必需关键词时至少命中一个；事件没有名称/符号视为不满足
+fn check_required(criterion: Criterion, field: &str, text: Option<&str>, matcher: &KeywordMatcher) -> CriterionCheck {
+    let outcome = if matcher.is_empty() {
+        CriterionOutcome::Skipped(format!("未配置{}必需关键词", field))
+    } else {
+        match text.and_then(|text| matcher.find(text)) {
+            Some(keyword) => CriterionOutcome::Passed(format!("{}包含关键词: {}", field, keyword)),
+            None if text.is_none() => CriterionOutcome::Failed(format!("事件没有{}，无法匹配必需关键词", field)),
+            None => CriterionOutcome::Failed(format!("{}不含任何必需关键词", field)),
         }
-    }
+    };
+    CriterionCheck { criterion, outcome }
+}
+
+fn check_forbidden(criterion: Criterion, field: &str, text: Option<&str>, matcher: &KeywordMatcher) -> CriterionCheck {
+    let outcome = match text {
+        _ if matcher.is_empty() => CriterionOutcome::Skipped(format!("未配置{}禁用关键词", field)),
+        None => CriterionOutcome::Skipped(format!("事件没有{}", field)),
+        Some(text) => match matcher.find(text) {
+            Some(keyword) => CriterionOutcome::Failed(format!("{}包含禁用关键词: {}", field, keyword)),
+            None => CriterionOutcome::Passed(format!("{}不含禁用关键词", field)),
+        },
+    };
+    CriterionCheck { criterion, outcome }
 }
 
 impl Default for OptimizedTokenFilter {
@@ -158,4 +475,107 @@ impl Default for OptimizedTokenFilter {
 pub fn filter_token_optimized(token_event: &TokenEvent) -> SimpleFilterResult {
     let filter = OptimizedTokenFilter::default_sniper_strategy();
     filter.evaluate_token_fast(token_event)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processors::{DevBuy, ProtocolEvent, TokenMetadata};
+
+    fn criteria() -> FilterCriteria {
+        FilterCriteria {
+            min_sol_amount: Some(100),
+            max_sol_amount: None,
+            required_name_keywords: vec![],
+            forbidden_name_keywords: vec!["scam".to_string(), "re:^test\\b".to_string()],
+            min_name_length: Some(3),
+            max_name_length: Some(12),
+            required_symbol_keywords: vec![],
+            forbidden_symbol_keywords: vec![],
+            min_symbol_length: None,
+            max_symbol_length: None,
+            max_creation_age_slots: Some(10),
+            allowed_transaction_types: vec![TransactionType::TokenCreation],
+            whitelist_mints: vec![],
+            blacklist_mints: vec![],
+            blacklist_programs: vec![],
+        }
+    }
+
+    fn creation(name: &str, symbol: &str, dev_buy_sol: u64) -> TokenEvent {
+        let mut event = TokenEvent::new(
+            "sig",
+            100,
+            ProtocolEvent::PumpFunCreate {
+                mint: Pubkey::new_unique(),
+                creator: Pubkey::new_unique(),
+                metadata: Some(TokenMetadata { name: name.to_string(), symbol: symbol.to_string(), uri: String::new() }),
+                dev_buy: Some(DevBuy { sol_amount: dev_buy_sol, token_amount: 1 }),
+            },
+        );
+        event.account_keys = vec![Pubkey::new_unique()];
+        event
+    }
+
+    fn outcome(result: &SimpleFilterResult, criterion: Criterion) -> &CriterionOutcome {
+        &result.checks.iter().find(|check| check.criterion == criterion).unwrap().outcome
+    }
+
+    #[test]
+    fn test_normalization_defeats_fullwidth_and_zero_width() {
+        assert_eq!(normalize_text("  ＳＣＡＭ\u{200B}  Coin "), "scam coin");
+
+        let filter = OptimizedTokenFilter::new(criteria()).unwrap();
+        let result = filter.evaluate_token_fast(&creation("Sc\u{200B}am Inu", "SI", 500));
+        assert!(!result.passed);
+        assert!(matches!(outcome(&result, Criterion::ForbiddenNameKeywords), CriterionOutcome::Failed(_)));
+
+        // 正则关键词只匹配开头的独立单词
+        assert!(filter.evaluate_token_fast(&creation("Contest", "C", 500)).passed);
+        assert!(!filter.evaluate_token_fast(&creation("TEST coin", "T", 500)).passed);
+    }
+
+    #[test]
+    fn test_every_criterion_is_reported() {
+        let filter = OptimizedTokenFilter::new(criteria()).unwrap();
+        let result = filter.evaluate_token(&creation("A very long token name", "X", 50), Some(200));
+
+        assert!(!result.passed);
+        let failed: Vec<Criterion> = result.failed_checks().map(|check| check.criterion).collect();
+        assert_eq!(failed, vec![Criterion::SolAmount, Criterion::NameLength, Criterion::CreationAge]);
+        assert!(matches!(outcome(&result, Criterion::RequiredSymbolKeywords), CriterionOutcome::Skipped(_)));
+        assert_eq!(result.reason, "SOL金额过低: 50 < 100");
+    }
+
+    #[test]
+    fn test_black_and_white_lists() {
+        let event = creation("scam", "S", 0);
+        let mut criteria = criteria();
+        criteria.whitelist_mints = vec![event.mint().to_string()];
+        let filter = OptimizedTokenFilter::new(criteria.clone()).unwrap();
+        assert!(filter.evaluate_token(&event, Some(1_000)).passed);
+
+        criteria.blacklist_mints = vec![event.mint().to_string()];
+        assert!(!OptimizedTokenFilter::new(criteria.clone()).unwrap().evaluate_token_fast(&event).passed);
+
+        // 涉及黑名单程序的交易只因该项失败；无关交易照常通过
+        let involved = creation("good", "G", 500);
+        let mut criteria = self::criteria();
+        criteria.blacklist_programs = vec![involved.account_keys[0].to_string()];
+        let filter = OptimizedTokenFilter::new(criteria).unwrap();
+        let result = filter.evaluate_token_fast(&involved);
+        assert!(!result.passed);
+        assert!(matches!(outcome(&result, Criterion::BlacklistProgram), CriterionOutcome::Failed(_)));
+        assert_eq!(result.failed_checks().count(), 1);
+        assert!(filter.evaluate_token_fast(&creation("good", "G", 500)).passed);
+        let result = OptimizedTokenFilter::new(self::criteria()).unwrap().evaluate_token_fast(&event);
+        assert!(matches!(outcome(&result, Criterion::BlacklistProgram), CriterionOutcome::Skipped(_)));
+    }
+
+    #[test]
+    fn test_invalid_regex_is_rejected() {
+        let mut criteria = criteria();
+        criteria.required_symbol_keywords = vec!["re:(".to_string()];
+        assert!(OptimizedTokenFilter::new(criteria).is_err());
+    }
+}
@@ -1,11 +1,12 @@
 use anyhow::Result;
 use log::{info, warn, error, debug};
 use solana_sdk::pubkey::Pubkey;
-use std::sync::atomic::{AtomicU8, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use tokio::time::Duration;
 
+use crate::constant::accounts::TOKEN_PROGRAM;
 use crate::processors::TokenEvent;
 use crate::executor::ExecutionResult;
 use super::{TradeSignal, SignalPriority, StrategyConfig};
@@ -239,7 +240,7 @@ pub struct OptimizedTradingStrategy {
     position: Arc<OptimizedPosition>,
     
     /// 交易信号发送器
-    signal_sender: mpsc::UnboundedSender<TradeSignal>,
+    signal_sender: mpsc::Sender<TradeSignal>,
     
     /// 策略开始时间戳 (epoch millis)
     start_time_ms: Arc<AtomicU64>,
@@ -262,8 +263,74 @@ pub struct OptimizedTradingStrategy {
     /// 🔧 新增：代币创建者地址
     creator: Arc<tokio::sync::RwLock<Option<Pubkey>>>,
     
+    /// 代币 mint 所属的代币程序 (SPL Token 或 Token-2022)
+    token_program: Pubkey,
+    
+    /// 曲线毕业状态 - 毕业后不再发送 bonding curve 买卖信号
+    graduation: Arc<GraduationState>,
+    
     /// 🔧 修复：策略停止通知发送器 - 用于通知策略管理器移除策略
-    strategy_stop_notifier: Arc<tokio::sync::Mutex<Option<mpsc::UnboundedSender<Pubkey>>>>,
+    strategy_stop_notifier: Arc<tokio::sync::Mutex<Option<mpsc::Sender<Pubkey>>>>,
+}
+
+/// 曲线已毕业的持仓：bonding curve 不再接受卖出，需要改走 AMM
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraduatedPosition {
+    pub strategy_id: String,
+    pub mint: Pubkey,
+    /// 迁移目标池，只有收到迁移事件时才知道
+    pub pool: Option<Pubkey>,
+    pub token_amount: u64,
+    pub token_program: Pubkey,
+}
+
+/// 策略与监控任务共享的毕业状态
+#[derive(Debug, Default)]
+struct GraduationState {
+    graduated: AtomicBool,
+    /// 持仓是否已上报给 AMM 路由，每个策略只上报一次
+    reported: AtomicBool,
+    pool: tokio::sync::RwLock<Option<Pubkey>>,
+    notifier: tokio::sync::Mutex<Option<mpsc::Sender<GraduatedPosition>>>,
+}
+
+impl GraduationState {
+    fn is_graduated(&self) -> bool {
+        self.graduated.load(Ordering::Acquire)
+    }
+
+    /// 标记毕业；返回是否为首次标记。已知的目标池不会被 None 覆盖
+    async fn mark(&self, pool: Option<Pubkey>) -> bool {
+        if pool.is_some() {
+            *self.pool.write().await = pool;
+        }
+        !self.graduated.swap(true, Ordering::AcqRel)
+    }
+
+    /// 代替 bonding curve 卖出：把持仓交给 AMM 路由
+    async fn report(&self, strategy_id: &str, mint: Pubkey, token_amount: u64, token_program: Pubkey) {
+        if self.reported.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let position = GraduatedPosition {
+            strategy_id: strategy_id.to_string(),
+            mint,
+            pool: *self.pool.read().await,
+            token_amount,
+            token_program,
+        };
+        warn!("🎓 策略 {} 的代币 {} 已毕业，跳过bonding curve卖出，{} tokens 交由AMM路由 (池: {:?})",
+              strategy_id, mint, token_amount, position.pool);
+
+        match self.notifier.lock().await.as_ref() {
+            Some(notifier) => {
+                if let Err(e) = notifier.send(position).await {
+                    error!("❌ 上报毕业持仓失败: {}", e);
+                }
+            }
+            None => warn!("   ⚠️ 未设置毕业持仓通知，需要手动处理"),
+        }
+    }
 }
 
 /// 优化策略性能统计 - 原子计数器
@@ -298,7 +365,7 @@ impl OptimizedTradingStrategy {
     pub fn new(
         mint: Pubkey,
         config: StrategyConfig,
-        signal_sender: mpsc::UnboundedSender<TradeSignal>,
+        signal_sender: mpsc::Sender<TradeSignal>,
     ) -> Self {
         let strategy_id = format!("opt_strategy_{}_{}", 
             mint.to_string()[..8].to_string(),
@@ -319,6 +386,8 @@ impl OptimizedTradingStrategy {
             current_price: Arc::new(tokio::sync::RwLock::new(None)),
             price_source: Arc::new(tokio::sync::RwLock::new(None)),
             creator: Arc::new(tokio::sync::RwLock::new(None)),
+            token_program: TOKEN_PROGRAM,
+            graduation: Arc::new(GraduationState::default()),
             strategy_stop_notifier: Arc::new(tokio::sync::Mutex::new(None)),
         }
     }
@@ -327,7 +396,7 @@ impl OptimizedTradingStrategy {
     pub fn new_with_price_and_creator(
         mint: Pubkey,
         config: StrategyConfig,
-        signal_sender: mpsc::UnboundedSender<TradeSignal>,
+        signal_sender: mpsc::Sender<TradeSignal>,
         price_info: Option<(f64, String)>,
         creator: Option<Pubkey>,
     ) -> Self {
@@ -356,13 +425,15 @@ impl OptimizedTradingStrategy {
             current_price: Arc::new(tokio::sync::RwLock::new(initial_price)),
             price_source: Arc::new(tokio::sync::RwLock::new(initial_source)),
             creator: Arc::new(tokio::sync::RwLock::new(creator)),
+            token_program: TOKEN_PROGRAM,
+            graduation: Arc::new(GraduationState::default()),
             strategy_stop_notifier: Arc::new(tokio::sync::Mutex::new(None)),
         }
     }
     pub fn new_with_price(
         mint: Pubkey,
         config: StrategyConfig,
-        signal_sender: mpsc::UnboundedSender<TradeSignal>,
+        signal_sender: mpsc::Sender<TradeSignal>,
         price_info: Option<(f64, String)>,
     ) -> Self {
         let strategy_id = format!("opt_strategy_{}_{}", 
@@ -390,10 +461,18 @@ impl OptimizedTradingStrategy {
             current_price: Arc::new(tokio::sync::RwLock::new(initial_price)),
             price_source: Arc::new(tokio::sync::RwLock::new(initial_source)),
             creator: Arc::new(tokio::sync::RwLock::new(None)),
+            token_program: TOKEN_PROGRAM,
+            graduation: Arc::new(GraduationState::default()),
             strategy_stop_notifier: Arc::new(tokio::sync::Mutex::new(None)),
         }
     }
 
+    /// 设置代币程序，Token-2022 代币的买卖需要
+    pub fn with_token_program(mut self, token_program: Pubkey) -> Self {
+        self.token_program = token_program;
+        self
+    }
+
     /// 🔧 新增：更新价格信息
     pub async fn update_price(&self, price: f64, source: String) {
         {
@@ -404,7 +483,8 @@ impl OptimizedTradingStrategy {
             let mut price_source = self.price_source.write().await;
             *price_source = Some(source);
         }
-        info!("📊 策略 {} 价格更新: {:.9} SOL/token", self.id, price);
+        // 曲线账户每笔交易都会推送，使用 debug 级别避免刷屏
+        debug!("📊 策略 {} 价格更新: {:.9} SOL/token", self.id, price);
     }
 
     /// 🔧 新增：获取当前价格信息
@@ -438,8 +518,25 @@ impl OptimizedTradingStrategy {
         info!("👤 策略 {} 设置创建者地址: {}", self.id, creator);
     }
 
+    /// 设置毕业持仓通知发送器 - 毕业后的持仓通过它交给 AMM 路由
+    pub async fn set_graduation_notifier(&self, notifier: mpsc::Sender<GraduatedPosition>) {
+        *self.graduation.notifier.lock().await = Some(notifier);
+    }
+
+    /// 标记曲线已毕业（PumpFun complete 或迁移到 AMM）
+    pub async fn mark_graduated(&self, pool: Option<Pubkey>) {
+        if self.graduation.mark(pool).await {
+            warn!("🎓 策略 {} 的代币 {} 曲线已毕业 (池: {:?})，停止bonding curve交易", self.id, self.mint, pool);
+            self.performance_stats.state_changes.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn is_graduated(&self) -> bool {
+        self.graduation.is_graduated()
+    }
+
     /// 🔧 修复：设置策略停止通知发送器
-    pub async fn set_strategy_stop_notifier(&self, notifier: mpsc::UnboundedSender<Pubkey>) {
+    pub async fn set_strategy_stop_notifier(&self, notifier: mpsc::Sender<Pubkey>) {
         let mut notifier_lock = self.strategy_stop_notifier.lock().await;
         *notifier_lock = Some(notifier);
         info!("📨 策略 {} 设置停止通知发送器", self.id);
@@ -486,6 +583,8 @@ impl OptimizedTradingStrategy {
             price_source: self.price_source.clone(),
             // 🔧 修复：传递创建者地址
             creator: self.creator.clone(),
+            token_program: self.token_program,
+            graduation: self.graduation.clone(),
         });
 
         tokio::spawn(async move {
@@ -543,7 +642,10 @@ impl OptimizedTradingStrategy {
         // 如果还有持仓，发送紧急卖出信号
         if self.position.has_position() {
             let status = self.position.get_status_snapshot();
-            if matches!(status, OptimizedPositionStatus::Holding) {
+            if matches!(status, OptimizedPositionStatus::Holding) && self.graduation.is_graduated() {
+                let token_amount = self.position.token_amount.load(Ordering::Acquire);
+                self.graduation.report(&self.id, self.mint, token_amount, self.token_program).await;
+            } else if matches!(status, OptimizedPositionStatus::Holding) {
                 warn!("⚠️ 优化策略停止时仍有持仓，发送紧急卖出信号");
                 
                 let token_amount = self.position.token_amount.load(Ordering::Acquire);
@@ -565,7 +667,7 @@ impl OptimizedTradingStrategy {
                     if let Some(creator) = self.get_creator().await {
                         signal = signal.with_creator(creator);
                     }
-                    signal
+                    signal.with_token_program(self.token_program)
                 } else {
                     // ✅ 没有价格信息时直接创建无价格紧急卖出信号
                     warn!("⚠️ 策略停止时无法获取价格信息，创建无价格紧急卖出信号");
@@ -581,10 +683,10 @@ impl OptimizedTradingStrategy {
                     if let Some(creator) = self.get_creator().await {
                         signal = signal.with_creator(creator);
                     }
-                    signal
+                    signal.with_token_program(self.token_program)
                 };
 
-                if let Err(e) = self.signal_sender.send(emergency_signal) {
+                if let Err(e) = self.signal_sender.send(emergency_signal).await {
                     error!("❌ 发送紧急卖出信号失败: {}", e);
                 } else {
                     self.performance_stats.signals_sent.fetch_add(1, Ordering::Relaxed);
@@ -693,23 +795,24 @@ impl OptimizedTradingStrategy {
     /// 接收代币事件 (用于价格监控等) - 优化版本
     pub async fn handle_token_event(&self, event: &TokenEvent) -> Result<()> {
         // 只处理与本策略代币相关的事件
-        if let Some(event_mint) = &event.mint {
-            if event_mint != &self.mint.to_string() {
-                return Ok(()); // 不是本策略的代币，忽略
-            }
-        } else {
-            return Ok(());
+        if event.mint() != self.mint {
+            return Ok(()); // 不是本策略的代币，忽略
         }
 
         self.performance_stats.events_processed.fetch_add(1, Ordering::Relaxed);
         self.performance_stats.lock_free_operations.fetch_add(1, Ordering::Relaxed);
 
         debug!("📊 优化策略 {} 监控到相关代币事件", self.id);
-        debug!("   🔍 事件类型: {:?}", event.transaction_type);
-        if let Some(sol_amount) = event.sol_amount {
+        debug!("   🔍 事件类型: {}", event.kind.name());
+        if let Some(sol_amount) = event.sol_amount() {
             debug!("   💰 涉及金额: {:.4} SOL", sol_amount as f64 / 1_000_000_000.0);
         }
 
+        if event.kind.is_migration() {
+            self.mark_graduated(event.kind.migration_pool()).await;
+            return Ok(());
+        }
+
         // 这里可以根据事件类型和金额大小做出反应
         // 例如：如果检测到大额卖出，可能触发紧急卖出
         // 由于是无锁架构，可以高频处理这类事件
@@ -719,6 +822,10 @@ impl OptimizedTradingStrategy {
 
     /// 发送买入信号 - 🔧 改进版：统一使用真实价格信息和创建者信息
     async fn send_buy_signal_atomic(&self, reason: &str) -> Result<()> {
+        if self.graduation.is_graduated() {
+            return Err(anyhow::anyhow!("代币 {} 曲线已毕业，无法在bonding curve上买入", self.mint));
+        }
+
         // 获取价格信息
         let price_info = self.get_current_price().await;
         // 获取创建者信息
@@ -753,9 +860,11 @@ impl OptimizedTradingStrategy {
         };
 
         // 设置为高优先级 - 新币狙击需要快速执行
-        let buy_signal = buy_signal.with_priority(SignalPriority::High);
+        let buy_signal = buy_signal
+            .with_priority(SignalPriority::High)
+            .with_token_program(self.token_program);
 
-        self.signal_sender.send(buy_signal)
+        self.signal_sender.send(buy_signal).await
             .map_err(|e| anyhow::anyhow!("发送买入信号失败: {}", e))?;
 
         self.performance_stats.signals_sent.fetch_add(1, Ordering::Relaxed);
@@ -831,7 +940,7 @@ struct OptimizedStrategyHandle {
     config: StrategyConfig,
     status: Arc<AtomicU8>,
     position: Arc<OptimizedPosition>,
-    signal_sender: mpsc::UnboundedSender<TradeSignal>,
+    signal_sender: mpsc::Sender<TradeSignal>,
     buy_completed_at_ms: Arc<AtomicU64>,
     performance_stats: Arc<OptimizedStrategyStats>,
     // 🔧 新增：价格信息访问
@@ -839,6 +948,8 @@ struct OptimizedStrategyHandle {
     price_source: Arc<tokio::sync::RwLock<Option<String>>>,
     // 🔧 修复：新增创建者地址访问
     creator: Arc<tokio::sync::RwLock<Option<Pubkey>>>,
+    token_program: Pubkey,
+    graduation: Arc<GraduationState>,
 }
 
 impl OptimizedStrategyHandle {
@@ -877,6 +988,12 @@ impl OptimizedStrategyHandle {
                 return;
             }
             
+            // 曲线已毕业时 bonding curve 卖出必然失败
+            if self.graduation.is_graduated() {
+                self.graduation.report(&self.id, self.mint, token_amount, self.token_program).await;
+                return;
+            }
+            
             info!("📊 准备紧急卖出: {} tokens", token_amount);
             
             // 🔧 修改：持仓时间到期后直接触发紧急卖出，不需要等待价格信息
@@ -896,7 +1013,7 @@ impl OptimizedStrategyHandle {
                 if let Some(creator) = self.get_creator().await {
                     signal = signal.with_creator(creator);
                 }
-                signal
+                signal.with_token_program(self.token_program)
             } else {
                 // ✅ 没有价格信息时直接创建无价格紧急卖出信号
                 warn!("⚠️ 策略 {} 缺少价格信息，创建无价格紧急卖出信号", self.id);
@@ -912,10 +1029,10 @@ impl OptimizedStrategyHandle {
                 if let Some(creator) = self.get_creator().await {
                     signal = signal.with_creator(creator);
                 }
-                signal
+                signal.with_token_program(self.token_program)
             };
 
-            if let Err(e) = self.signal_sender.send(sell_signal) {
+            if let Err(e) = self.signal_sender.send(sell_signal).await {
                 error!("❌ 发送紧急卖出信号失败: {}", e);
             } else {
                 self.performance_stats.signals_sent.fetch_add(1, Ordering::Relaxed);
@@ -947,21 +1064,20 @@ struct OptimizedStrategyStopHandle {
     status: Arc<AtomicU8>,
     cancel_sender: Arc<tokio::sync::Mutex<Option<mpsc::UnboundedSender<()>>>>,
     /// 策略停止通知发送器 - 用于通知策略管理器移除策略
-    strategy_stop_notifier: Option<mpsc::UnboundedSender<Pubkey>>,
+    strategy_stop_notifier: Option<mpsc::Sender<Pubkey>>,
 }
 
 impl OptimizedStrategyStopHandle {
     async fn trigger_stop(&self) {
         self.status.store(OptimizedStrategyStatus::Stopping as u8, Ordering::Release);
         
-        let cancel_sender = self.cancel_sender.lock().await;
-        if let Some(sender) = cancel_sender.as_ref() {
+        if let Some(sender) = self.cancel_sender.lock().await.as_ref() {
             let _ = sender.send(());
         }
         
-        // 🔧 修复：通知策略管理器移除策略
+        // 🔧 修复：通知策略管理器移除策略（通知队列有界，满时等待）
         if let Some(ref notifier) = self.strategy_stop_notifier {
-            if let Err(e) = notifier.send(self.mint) {
+            if let Err(e) = notifier.send(self.mint).await {
                 error!("❌ 发送策略停止通知失败: {}", e);
             } else {
                 info!("📨 已通知策略管理器移除策略: {}", self.mint);
@@ -969,3 +1085,48 @@ impl OptimizedStrategyStopHandle {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_graduated_position_is_reported_instead_of_sold() {
+        let (signal_tx, mut signal_rx) = mpsc::channel(4);
+        let (graduation_tx, mut graduation_rx) = mpsc::channel(4);
+        let mint = Pubkey::new_unique();
+        let pool = Pubkey::new_unique();
+        let strategy = OptimizedTradingStrategy::new(mint, StrategyConfig::default(), signal_tx);
+        strategy.set_graduation_notifier(graduation_tx).await;
+
+        strategy.position.token_amount.store(1_000, Ordering::Release);
+        strategy.position.set_status(OptimizedPositionStatus::Holding);
+
+        // 账户推送先标记毕业，迁移事件再补全目标池
+        strategy.mark_graduated(None).await;
+        let migration = TokenEvent::new("sig", 1, crate::processors::ProtocolEvent::Migration {
+            protocol: crate::processors::Protocol::PumpFun,
+            mint,
+            pool: Some(pool),
+        });
+        strategy.handle_token_event(&migration).await.unwrap();
+        assert!(strategy.is_graduated());
+
+        strategy.stop().await.unwrap();
+        assert_eq!(
+            graduation_rx.try_recv().unwrap(),
+            GraduatedPosition {
+                strategy_id: strategy.id.clone(),
+                mint,
+                pool: Some(pool),
+                token_amount: 1_000,
+                token_program: TOKEN_PROGRAM,
+            }
+        );
+        assert!(signal_rx.try_recv().is_err(), "毕业后不应发送bonding curve卖出信号");
+
+        // 只上报一次
+        strategy.stop().await.unwrap();
+        assert!(graduation_rx.try_recv().is_err());
+    }
+}
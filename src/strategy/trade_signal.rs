@@ -58,6 +58,9 @@ pub struct TradeSignal {
     pub price_source: Option<String>,
     /// 🔧 新增：代币创建者地址 - 用于 PumpFun creator_vault
     pub creator: Option<Pubkey>,
+    /// 代币 mint 所属的代币程序，None 时按 SPL Token 处理
+    #[serde(default)]
+    pub token_program: Option<Pubkey>,
     
     // 🆕 新增计算预算字段
     /// 计算单元数 - 区分买入/卖出操作
@@ -94,6 +97,7 @@ impl TradeSignal {
             current_price: Some(current_price),
             price_source: Some(price_source),
             creator: None, // 需要在外部设置
+            token_program: None,
             // 默认计算预算设置 - 将由StrategyManager设置
             compute_units: 0, // 占位值，在strategy层设置
             priority_fee_tier: ComputeBudgetTier::default(),
@@ -127,6 +131,7 @@ impl TradeSignal {
             current_price: Some(current_price),
             price_source: Some(price_source),
             creator: Some(creator),
+            token_program: None,
             // 默认计算预算设置 - 将由StrategyManager设置
             compute_units: 0, // 占位值，在strategy层设置
             priority_fee_tier: ComputeBudgetTier::default(),
@@ -163,6 +168,7 @@ impl TradeSignal {
             current_price: Some(current_price),
             price_source: Some(price_source),
             creator: None, // 需要在外部设置
+            token_program: None,
             // 默认计算预算设置 - 将由StrategyManager设置
             compute_units: 0, // 占位值，在strategy层设置
             priority_fee_tier: ComputeBudgetTier::default(),
@@ -197,6 +203,7 @@ impl TradeSignal {
             current_price: Some(current_price),
             price_source: Some(price_source),
             creator: None, // 紧急卖出时可能没有创建者信息
+            token_program: None,
             // 紧急卖出默认使用最高档位 - 将由StrategyManager设置
             compute_units: 0, // 占位值，在strategy层设置
             priority_fee_tier: ComputeBudgetTier::Lightning, // 紧急卖出优先使用闪电档
@@ -229,6 +236,7 @@ impl TradeSignal {
             current_price: None, // 明确标记无价格信息
             price_source: Some("NO_PRICE_EMERGENCY".to_string()),
             creator: None, // 紧急卖出时可能没有创建者信息
+            token_program: None,
             // 紧急卖出默认使用最高档位 - 将由StrategyManager设置
             compute_units: 0, // 占位值，在strategy层设置
             priority_fee_tier: ComputeBudgetTier::Lightning, // 紧急卖出优先使用闪电档
@@ -286,6 +294,12 @@ impl TradeSignal {
         self
     }
 
+    /// 设置代币程序（Token-2022 代币需要）
+    pub fn with_token_program(mut self, token_program: Pubkey) -> Self {
+        self.token_program = Some(token_program);
+        self
+    }
+
     /// 🔧 新增：设置价格信息
     pub fn with_price(mut self, current_price: f64, price_source: String) -> Self {
         self.current_price = Some(current_price);
@@ -460,6 +474,7 @@ impl TradeSignal {
             max_slippage_bps: self.max_slippage_bps,
             is_buy: matches!(self.signal_type, TradeSignalType::Buy),
             creator: self.creator, // ✅ 传递创建者地址
+            token_program: self.token_program,
         }
     }
 }
@@ -0,0 +1,406 @@
+use anyhow::Result;
+use backoff::future::retry;
+use backoff::ExponentialBackoff;
+use borsh::BorshDeserialize;
+use dashmap::DashMap;
+use futures_util::SinkExt;
+use futures_util::StreamExt;
+use log::{debug, error, info, warn};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::{mpsc, Notify};
+use yellowstone_grpc_client::GeyserGrpcClient;
+use yellowstone_grpc_proto::geyser::{
+    SubscribeRequest, SubscribeRequestFilterAccounts, SubscribeRequestPing, SubscribeUpdateAccount,
+};
+use yellowstone_grpc_proto::prelude::subscribe_update::UpdateOneof;
+
+use crate::config::StreamShyftConfig;
+use crate::constant::{accounts::PUMPFUN, seeds::BONDING_CURVE_SEED};
+use crate::streams::geyser::connect_client;
+use crate::streams::geyser::stream::commitment_level;
+use crate::streams::source::{SharedStreamMetrics, StreamMetrics};
+use crate::utils::BondingCurveAccount;
+
+/// 价格更新通道容量
+const PRICE_CHANNEL_CAPACITY: usize = 1024;
+
+/// 账户更新使用的过滤器名
+const ACCOUNTS_FILTER_NAME: &str = "positions";
+
+/// Raydium Launchpad 程序
+const RAYDIUM_LAUNCHPAD_PROGRAM: Pubkey = Pubkey::from_str_const("LanMV9sAd7wArD4vJFi2qDdfnVhFxYSUg6eADduJ3uj");
+
+/// Launchpad 池子的报价币种 (WSOL)
+const WSOL_MINT: Pubkey = Pubkey::from_str_const("So11111111111111111111111111111111111111112");
+
+/// Launchpad PoolState PDA 种子
+const POOL_SEED: &[u8] = b"pool";
+
+/// Anchor 账户头部的判别符长度
+const ACCOUNT_DISCRIMINATOR_LEN: usize = 8;
+
+/// Launchpad `PoolState` 中到储备为止的固定前缀
+///
+/// 与 `raydium_launchpad_interface::PoolState` 字段顺序一致；该 crate 使用 borsh 0.10，
+/// 这里只解码计算价格所需的部分。
+#[derive(Debug, BorshDeserialize)]
+struct PoolStateReserves {
+    _epoch: u64,
+    _auth_bump: u8,
+    status: u8,
+    _base_decimals: u8,
+    _quote_decimals: u8,
+    _migrate_type: u8,
+    _supply: u64,
+    _total_base_sell: u64,
+    virtual_base: u64,
+    virtual_quote: u64,
+    real_base: u64,
+    real_quote: u64,
+}
+
+/// 被订阅的曲线账户类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CurveAccount {
+    /// PumpFun bonding curve
+    PumpFunBondingCurve,
+    /// Raydium Launchpad PoolState
+    LaunchpadPoolState,
+}
+
+impl CurveAccount {
+    /// 持仓代币对应的所有曲线账户地址
+    pub fn addresses_for(mint: &Pubkey) -> Vec<(Pubkey, CurveAccount)> {
+        let (bonding_curve, _) = Pubkey::find_program_address(&[BONDING_CURVE_SEED, mint.as_ref()], &PUMPFUN);
+        let (pool_state, _) = Pubkey::find_program_address(
+            &[POOL_SEED, mint.as_ref(), WSOL_MINT.as_ref()],
+            &RAYDIUM_LAUNCHPAD_PROGRAM,
+        );
+
+        vec![
+            (bonding_curve, CurveAccount::PumpFunBondingCurve),
+            (pool_state, CurveAccount::LaunchpadPoolState),
+        ]
+    }
+
+    fn source(&self) -> &'static str {
+        match self {
+            CurveAccount::PumpFunBondingCurve => "PumpFun-BondingCurve",
+            CurveAccount::LaunchpadPoolState => "Raydium-PoolState",
+        }
+    }
+
+    /// 从账户数据解码储备并计算价格（lamports / 最小代币单位）
+    ///
+    /// 返回 `(价格, 曲线是否已结束)`；数据无法解码或储备为0时返回 None。
+    pub fn decode_price(&self, data: &[u8]) -> Option<(f64, bool)> {
+        match self {
+            CurveAccount::PumpFunBondingCurve => {
+                // 账户实际长度大于结构体，只解码前缀
+                let curve = BondingCurveAccount::deserialize(&mut &data[..]).ok()?;
+                if curve.virtual_token_reserves == 0 {
+                    return None;
+                }
+                let price = curve.virtual_sol_reserves as f64 / curve.virtual_token_reserves as f64;
+                Some((price, curve.complete))
+            }
+            CurveAccount::LaunchpadPoolState => {
+                let mut body = data.get(ACCOUNT_DISCRIMINATOR_LEN..)?;
+                let pool = PoolStateReserves::deserialize(&mut body).ok()?;
+                // 恒定乘积曲线：报价储备 = 虚拟 + 已募集，基础储备 = 虚拟 - 已售出
+                let quote_reserve = pool.virtual_quote.checked_add(pool.real_quote)?;
+                let base_reserve = pool.virtual_base.checked_sub(pool.real_base)?;
+                if base_reserve == 0 {
+                    return None;
+                }
+                // status: 0=募集中，其余为迁移中/已迁移
+                Some((quote_reserve as f64 / base_reserve as f64, pool.status != 0))
+            }
+        }
+    }
+}
+
+/// 由曲线账户计算出的实时价格
+#[derive(Debug, Clone)]
+pub struct PriceUpdate {
+    pub mint: Pubkey,
+    /// lamports / 最小代币单位，与事件推算的价格单位一致
+    pub price: f64,
+    pub source: String,
+    pub slot: u64,
+    /// 曲线已结束（PumpFun complete / Launchpad 已迁移）
+    pub complete: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct WatchedAccount {
+    mint: Pubkey,
+    kind: CurveAccount,
+}
+
+/// 持仓代币的曲线账户订阅
+///
+/// 通过 Yellowstone 账户过滤器订阅每个持仓代币的 bonding curve / PoolState，
+/// 每次账户变化都解码储备并输出精确价格。`watch` / `unwatch` 会在连接上原地替换订阅，
+/// 无需重连。
+pub struct AccountPriceStream {
+    config: StreamShyftConfig,
+    watched: DashMap<Pubkey, WatchedAccount>,
+    changed: Notify,
+    metrics: SharedStreamMetrics,
+}
+
+impl AccountPriceStream {
+    pub fn new(config: StreamShyftConfig) -> Self {
+        Self {
+            config,
+            watched: DashMap::new(),
+            changed: Notify::new(),
+            metrics: StreamMetrics::shared(),
+        }
+    }
+
+    /// 开始跟踪某个代币的曲线账户
+    pub fn watch(&self, mint: Pubkey) {
+        for (address, kind) in CurveAccount::addresses_for(&mint) {
+            self.watched.insert(address, WatchedAccount { mint, kind });
+        }
+        debug!("👀 订阅曲线账户: mint={}", mint);
+        self.changed.notify_one();
+    }
+
+    /// 停止跟踪某个代币
+    pub fn unwatch(&self, mint: &Pubkey) {
+        self.watched.retain(|_, watched| &watched.mint != mint);
+        debug!("🙈 取消曲线账户订阅: mint={}", mint);
+        self.changed.notify_one();
+    }
+
+    /// 当前跟踪的代币数量
+    pub fn watched_mints(&self) -> usize {
+        let mut mints: Vec<Pubkey> = self.watched.iter().map(|entry| entry.mint).collect();
+        mints.sort_unstable();
+        mints.dedup();
+        mints.len()
+    }
+
+    pub fn metrics(&self) -> SharedStreamMetrics {
+        Arc::clone(&self.metrics)
+    }
+
+    /// 在后台启动订阅，返回价格更新流
+    pub fn start(self: &Arc<Self>) -> mpsc::Receiver<PriceUpdate> {
+        let (sender, receiver) = mpsc::channel(PRICE_CHANNEL_CAPACITY);
+        let this = Arc::clone(self);
+        tokio::spawn(async move {
+            if let Err(e) = this.run(sender).await {
+                error!("❌ 曲线账户订阅失败: {}", e);
+            }
+        });
+        receiver
+    }
+
+    /// 当前跟踪账户对应的订阅请求；没有跟踪账户时不带账户过滤器（空过滤器会匹配所有账户）
+    fn build_request(&self) -> SubscribeRequest {
+        let account: Vec<String> = self.watched.iter().map(|entry| entry.key().to_string()).collect();
+        let accounts = if account.is_empty() {
+            HashMap::new()
+        } else {
+            HashMap::from([(
+                ACCOUNTS_FILTER_NAME.to_string(),
+                SubscribeRequestFilterAccounts {
+                    account,
+                    ..Default::default()
+                },
+            )])
+        };
+
+        SubscribeRequest {
+            accounts,
+            commitment: Some(commitment_level(&self.config) as i32),
+            ..Default::default()
+        }
+    }
+
+    /// 解码一次账户更新；不再跟踪或无法解码时返回 None
+    fn price_update(&self, update: &SubscribeUpdateAccount) -> Option<PriceUpdate> {
+        let account = update.account.as_ref()?;
+        let address = Pubkey::try_from(account.pubkey.as_slice()).ok()?;
+        let watched = *self.watched.get(&address)?;
+
+        let Some((price, complete)) = watched.kind.decode_price(&account.data) else {
+            warn!("⚠️ 无法解码曲线账户 {} (mint={})", address, watched.mint);
+            return None;
+        };
+
+        Some(PriceUpdate {
+            mint: watched.mint,
+            price,
+            source: watched.kind.source().to_string(),
+            slot: update.slot,
+            complete,
+        })
+    }
+
+    /// 带指数退避重连的订阅循环
+    async fn run(&self, sender: mpsc::Sender<PriceUpdate>) -> Result<()> {
+        info!("🚀 Starting curve account subscription...");
+
+        // 价格订阅需要与进程同生命周期，不限制总重试时长
+        let backoff = ExponentialBackoff { max_elapsed_time: None, ..Default::default() };
+        retry(backoff, || {
+            let sender = sender.clone();
+            async move {
+                {
+                    let mut metrics = self.metrics.write().await;
+                    metrics.reconnection_attempts += 1;
+                    metrics.last_connection_time = Some(Instant::now());
+                }
+                let mut client = connect_client(&self.config).await.map_err(backoff::Error::transient)?;
+
+                match self.subscribe(&mut client, &sender).await {
+                    Err(e) if sender.is_closed() => Err(backoff::Error::Permanent(e)),
+                    Err(e) => Err(backoff::Error::transient(e)),
+                    Ok(()) => Err(backoff::Error::transient(anyhow::anyhow!("curve account stream closed"))),
+                }
+            }
+        })
+        .await
+    }
+
+    async fn subscribe(
+        &self,
+        client: &mut GeyserGrpcClient<impl yellowstone_grpc_client::Interceptor>,
+        sender: &mpsc::Sender<PriceUpdate>,
+    ) -> Result<()> {
+        let (mut subscribe_tx, mut stream) = client.subscribe_with_request(Some(self.build_request())).await?;
+        self.metrics.write().await.connection_opened();
+        info!("✅ 曲线账户订阅已打开，跟踪 {} 个代币", self.watched_mints());
+
+        let result = async {
+            loop {
+                tokio::select! {
+                    // 持仓变化：在同一连接上替换订阅
+                    _ = self.changed.notified() => {
+                        subscribe_tx.send(self.build_request()).await?;
+                        debug!("🔄 曲线账户订阅已更新，跟踪 {} 个代币", self.watched_mints());
+                    }
+                    message = stream.next() => {
+                        let Some(message) = message else { break };
+                        let msg = match message {
+                            Ok(msg) => msg,
+                            Err(error) => {
+                                self.metrics.write().await.connection_errors += 1;
+                                return Err(anyhow::anyhow!("Stream error: {:?}", error));
+                            }
+                        };
+                        self.metrics.write().await.record_message();
+
+                        match msg.update_oneof {
+                            Some(UpdateOneof::Account(update)) => {
+                                let Some(price_update) = self.price_update(&update) else { continue };
+                                self.metrics.write().await.transactions_processed += 1;
+                                if sender.send(price_update).await.is_err() {
+                                    return Err(anyhow::anyhow!("price update receiver dropped"));
+                                }
+                            }
+                            Some(UpdateOneof::Ping(_)) => {
+                                self.metrics.write().await.last_ping_at = Some(Instant::now());
+                                subscribe_tx
+                                    .send(SubscribeRequest {
+                                        ping: Some(SubscribeRequestPing { id: 1 }),
+                                        ..Default::default()
+                                    })
+                                    .await?;
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+
+            info!("curve account stream closed");
+            Ok(())
+        }
+        .await;
+
+        self.metrics.write().await.connection_closed();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yellowstone_grpc_proto::geyser::SubscribeUpdateAccountInfo;
+
+    fn account_update(address: Pubkey, data: Vec<u8>) -> SubscribeUpdateAccount {
+        SubscribeUpdateAccount {
+            account: Some(SubscribeUpdateAccountInfo {
+                pubkey: address.to_bytes().to_vec(),
+                data,
+                ..Default::default()
+            }),
+            slot: 7,
+            is_startup: false,
+        }
+    }
+
+    #[test]
+    fn test_watch_updates_filter_and_decodes_bonding_curve() {
+        let stream = AccountPriceStream::new(StreamShyftConfig::new("http://localhost".to_string(), String::new()));
+        assert!(stream.build_request().accounts.is_empty());
+
+        let mint = Pubkey::new_unique();
+        stream.watch(mint);
+        let addresses = CurveAccount::addresses_for(&mint);
+        let request = stream.build_request();
+        assert_eq!(request.accounts[ACCOUNTS_FILTER_NAME].account.len(), addresses.len());
+
+        let curve = BondingCurveAccount {
+            discriminator: 0,
+            virtual_token_reserves: 1_000_000_000_000,
+            virtual_sol_reserves: 30_000_000_000,
+            real_token_reserves: 0,
+            real_sol_reserves: 0,
+            token_total_supply: 1_000_000_000_000_000,
+            complete: false,
+            creator: Pubkey::new_unique(),
+        };
+        let mut data = borsh::to_vec(&curve).unwrap();
+        data.extend_from_slice(&[0; 32]);
+
+        let (bonding_curve, _) = addresses[0];
+        let update = stream.price_update(&account_update(bonding_curve, data.clone())).unwrap();
+        assert_eq!(update.mint, mint);
+        assert_eq!(update.slot, 7);
+        assert!((update.price - 0.03).abs() < 1e-12);
+        assert!(!update.complete);
+
+        stream.unwatch(&mint);
+        assert!(stream.build_request().accounts.is_empty());
+        assert!(stream.price_update(&account_update(bonding_curve, data)).is_none());
+    }
+
+    #[test]
+    fn test_decode_launchpad_pool_state() {
+        // 判别符 + epoch + 5 个 u8 + supply + total_base_sell，随后是四个储备，再接其余字段
+        let mut data = vec![0u8; ACCOUNT_DISCRIMINATOR_LEN];
+        data.extend_from_slice(&1u64.to_le_bytes());
+        data.extend_from_slice(&[255, 0, 6, 9, 1]);
+        data.extend_from_slice(&1_000_000_000_000_000u64.to_le_bytes());
+        data.extend_from_slice(&793_100_000_000_000u64.to_le_bytes());
+        for reserve in [1_073_025_605_596_382u64, 30_000_852_951, 73_025_605_596_382, 10_000_000_000] {
+            data.extend_from_slice(&reserve.to_le_bytes());
+        }
+        data.extend_from_slice(&[0; 64]);
+
+        let (price, complete) = CurveAccount::LaunchpadPoolState.decode_price(&data).unwrap();
+        let expected = 40_000_852_951.0 / 1_000_000_000_000_000.0;
+        assert!((price - expected).abs() < 1e-15);
+        assert!(!complete);
+    }
+}
@@ -0,0 +1,160 @@
+use lru::LruCache;
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 默认去重窗口：最近的签名数量
+pub const DEFAULT_DEDUP_CAPACITY: usize = 100_000;
+
+/// 一个签名的到达结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arrival {
+    /// 首次到达，应当处理
+    First,
+    /// 重复到达；`delay` 为相对首次到达的延迟
+    Duplicate { first_endpoint: usize, delay: Duration },
+}
+
+/// 单个端点的竞速统计
+#[derive(Debug, Clone, Default)]
+pub struct EndpointStats {
+    pub name: String,
+    /// 收到的交易总数（含重复）
+    pub received: u64,
+    /// 首先到达的次数
+    pub wins: u64,
+    /// 落后于其他端点的次数
+    pub losses: u64,
+    /// 落后时的累计延迟
+    pub total_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl EndpointStats {
+    /// 胜率：首先到达次数 / 收到总数
+    pub fn win_rate(&self) -> f64 {
+        if self.received == 0 {
+            0.0
+        } else {
+            self.wins as f64 / self.received as f64
+        }
+    }
+
+    /// 落后时的平均延迟
+    pub fn average_delay(&self) -> Duration {
+        if self.losses == 0 {
+            Duration::ZERO
+        } else {
+            self.total_delay / self.losses as u32
+        }
+    }
+}
+
+struct FirstSeen {
+    endpoint: usize,
+    at: Instant,
+}
+
+struct DedupState {
+    seen: LruCache<Vec<u8>, FirstSeen>,
+    endpoints: Vec<EndpointStats>,
+}
+
+/// 按交易签名去重，并统计每个端点的到达先后
+///
+/// 只保留最近 `capacity` 个签名，窗口外的重复交易会被当作首次到达。
+pub struct SignatureDeduplicator {
+    state: Mutex<DedupState>,
+}
+
+impl SignatureDeduplicator {
+    pub fn new(capacity: usize, endpoint_names: Vec<String>) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        let endpoints = endpoint_names
+            .into_iter()
+            .map(|name| EndpointStats { name, ..Default::default() })
+            .collect();
+
+        Self {
+            state: Mutex::new(DedupState {
+                seen: LruCache::new(capacity),
+                endpoints,
+            }),
+        }
+    }
+
+    /// 记录 `endpoint` 收到的签名并返回是否首次到达
+    pub fn observe(&self, signature: &[u8], endpoint: usize) -> Arrival {
+        self.observe_at(signature, endpoint, Instant::now())
+    }
+
+    fn observe_at(&self, signature: &[u8], endpoint: usize, now: Instant) -> Arrival {
+        let mut state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let DedupState { seen, endpoints } = &mut *state;
+
+        if let Some(stats) = endpoints.get_mut(endpoint) {
+            stats.received += 1;
+        }
+
+        if let Some(first) = seen.get(signature) {
+            let delay = now.saturating_duration_since(first.at);
+            let first_endpoint = first.endpoint;
+
+            // 同一端点重复推送（如重放）不计入落后统计
+            if first_endpoint != endpoint {
+                if let Some(stats) = endpoints.get_mut(endpoint) {
+                    stats.losses += 1;
+                    stats.total_delay += delay;
+                    stats.max_delay = stats.max_delay.max(delay);
+                }
+            }
+            return Arrival::Duplicate { first_endpoint, delay };
+        }
+
+        seen.put(signature.to_vec(), FirstSeen { endpoint, at: now });
+        if let Some(stats) = endpoints.get_mut(endpoint) {
+            stats.wins += 1;
+        }
+        Arrival::First
+    }
+
+    /// 各端点统计快照
+    pub fn endpoint_stats(&self) -> Vec<EndpointStats> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .endpoints
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_arrival_wins_and_delay_recorded() {
+        let dedup = SignatureDeduplicator::new(16, vec!["ny".to_string(), "fra".to_string()]);
+        let start = Instant::now();
+
+        assert_eq!(dedup.observe_at(b"sig1", 0, start), Arrival::First);
+        assert_eq!(
+            dedup.observe_at(b"sig1", 1, start + Duration::from_millis(30)),
+            Arrival::Duplicate { first_endpoint: 0, delay: Duration::from_millis(30) }
+        );
+        assert_eq!(dedup.observe_at(b"sig2", 1, start), Arrival::First);
+        assert_eq!(
+            dedup.observe_at(b"sig2", 0, start + Duration::from_millis(10)),
+            Arrival::Duplicate { first_endpoint: 1, delay: Duration::from_millis(10) }
+        );
+        assert_eq!(dedup.observe_at(b"sig3", 0, start), Arrival::First);
+
+        let stats = dedup.endpoint_stats();
+        assert_eq!(stats[0].received, 3);
+        assert_eq!(stats[0].wins, 2);
+        assert_eq!(stats[1].wins, 1);
+        assert_eq!(stats[1].max_delay, Duration::from_millis(30));
+        assert_eq!(stats[0].average_delay(), Duration::from_millis(10));
+        assert!((stats[0].win_rate() - 2.0 / 3.0).abs() < f64::EPSILON);
+    }
+}
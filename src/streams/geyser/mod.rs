@@ -0,0 +1,3 @@
+pub mod stream;
+
+pub use stream::{GeyserTokenStream, TransactionFilter, build_subscribe_request, connect_client, extract_log_message};
\ No newline at end of file
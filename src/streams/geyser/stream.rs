@@ -0,0 +1,720 @@
+use anyhow::Result;
+use backoff::backoff::Backoff;
+use backoff::ExponentialBackoff;
+use futures::Stream;
+use futures_util::SinkExt;
+use futures_util::StreamExt;
+use log::{debug, info, warn, error};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::transport::ClientTlsConfig;
+use yellowstone_grpc_client::GeyserGrpcClient;
+use yellowstone_grpc_proto::geyser::{
+    SubscribeRequest, SubscribeRequestFilterSlots, SubscribeRequestFilterTransactions, SubscribeRequestPing,
+    SubscribeUpdateTransactionInfo,
+};
+use yellowstone_grpc_proto::prelude::{
+    subscribe_update::UpdateOneof, CommitmentLevel
+};
+
+use crate::config::StreamShyftConfig;
+use crate::processors::{InstructionPrefilter, TokenEvent, TradeDirection, TransactionEventDetector};
+use crate::streams::dedup::{Arrival, SignatureDeduplicator, DEFAULT_DEDUP_CAPACITY};
+use crate::streams::replay::UpdateRecorder;
+use crate::streams::source::{ConnectionStatus, SharedStreamMetrics, StreamMetrics, TokenEventSource};
+
+/// 事件通道容量
+const EVENT_CHANNEL_CAPACITY: usize = 4096;
+
+/// slot 更新使用的过滤器名
+const SLOTS_FILTER_NAME: &str = "slots";
+
+/// 命名的交易过滤器：订阅哪些程序，以及命中后交给哪个检测器
+#[derive(Clone)]
+pub struct TransactionFilter {
+    pub name: String,
+    pub program_ids: Vec<String>,
+    pub detector: Arc<dyn TransactionEventDetector>,
+    /// 可选的原始字节预过滤器，未命中的交易不交给检测器解码
+    pub prefilter: Option<InstructionPrefilter>,
+}
+
+impl TransactionFilter {
+    pub fn new(
+        name: impl Into<String>,
+        program_ids: Vec<String>,
+        detector: Arc<dyn TransactionEventDetector>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            program_ids,
+            detector,
+            prefilter: None,
+        }
+    }
+
+    pub fn with_prefilter(mut self, prefilter: InstructionPrefilter) -> Self {
+        self.prefilter = Some(prefilter);
+        self
+    }
+
+    /// 交易是否需要交给检测器；没有预过滤器时全部接受
+    pub fn accepts(&self, txn_info: &SubscribeUpdateTransactionInfo) -> bool {
+        self.prefilter.is_none_or(|prefilter| prefilter.matches(txn_info))
+    }
+}
+
+/// 通用的 Yellowstone gRPC 事件源：一个订阅可包含多个命名过滤器，
+/// 每条交易按服务端返回的过滤器名分发给对应的检测器
+#[derive(Clone)]
+pub struct GeyserTokenStream {
+    name: String,
+    config: StreamShyftConfig,
+    filters: Vec<TransactionFilter>,
+    metrics: SharedStreamMetrics,
+    /// 签名去重器及本端点序号：过滤断线重放的交易，竞速时多个端点共享
+    dedup: (Arc<SignatureDeduplicator>, usize),
+    /// 可选的原始更新录制器
+    recorder: Option<Arc<UpdateRecorder>>,
+}
+
+/// 断线续订状态：记录处理进度，用于 `from_slot` 重放
+#[derive(Debug, Default)]
+struct ResumeState {
+    /// 最近收到交易的slot；更早的slot已完整处理
+    current_slot: Option<u64>,
+    /// 上次请求的重放不可用，下次直接实时订阅
+    replay_unavailable: bool,
+    /// 未能重放的缺口起点，等待第一条实时slot更新计算缺口大小
+    gap_from: Option<u64>,
+}
+
+impl ResumeState {
+    /// 下一次订阅的 `from_slot`
+    fn resume_slot(&mut self) -> Option<u64> {
+        if std::mem::take(&mut self.replay_unavailable) {
+            self.gap_from = self.current_slot;
+            return None;
+        }
+        self.current_slot
+    }
+
+    /// 记录交易所在slot，slot前进时返回已完整处理的slot
+    fn observe_transaction(&mut self, slot: u64) -> Option<u64> {
+        match self.current_slot {
+            Some(current) if slot <= current => None,
+            previous => {
+                self.current_slot = Some(slot);
+                previous
+            }
+        }
+    }
+
+    /// 收到实时slot更新时，返回无法重放的slot数量
+    fn take_gap(&mut self, live_slot: u64) -> Option<u64> {
+        self.gap_from.take().map(|from| live_slot.saturating_sub(from))
+    }
+}
+
+impl GeyserTokenStream {
+    pub fn new(name: impl Into<String>, config: StreamShyftConfig) -> Self {
+        let name = name.into();
+        let dedup = Arc::new(SignatureDeduplicator::new(DEFAULT_DEDUP_CAPACITY, vec![name.clone()]));
+
+        Self {
+            name,
+            config,
+            filters: Vec::new(),
+            metrics: StreamMetrics::shared(),
+            dedup: (dedup, 0),
+            recorder: None,
+        }
+    }
+
+    /// 添加一个命名过滤器
+    pub fn with_filter(mut self, filter: TransactionFilter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// 与其他流共享运行指标
+    pub fn with_metrics(mut self, metrics: SharedStreamMetrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// 与其他流共享签名去重器：只处理在 `dedup` 中首次出现的交易
+    pub fn with_deduplicator(mut self, dedup: Arc<SignatureDeduplicator>, endpoint: usize) -> Self {
+        self.dedup = (dedup, endpoint);
+        self
+    }
+
+    /// 录制收到的每条原始订阅更新，用于离线重放
+    pub fn with_recorder(mut self, recorder: Arc<UpdateRecorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    pub async fn get_metrics(&self) -> StreamMetrics {
+        self.metrics.read().await.clone()
+    }
+
+    /// Get connection status information
+    pub async fn get_connection_status(&self) -> ConnectionStatus {
+        self.metrics.read().await.connection_status()
+    }
+
+    /// 回调形式的流式处理，直到接收端关闭或遇到永久错误
+    pub async fn start_streaming<F>(&self, callback: F) -> Result<()>
+    where
+        F: Fn(TokenEvent) -> Result<()> + Send + Sync + 'static,
+    {
+        let (sender, mut receiver) = mpsc::channel(EVENT_CHANNEL_CAPACITY);
+
+        let consumer = async move {
+            while let Some(event) = receiver.recv().await {
+                if let Err(e) = callback(event) {
+                    warn!("Callback error: {}", e);
+                }
+            }
+        };
+
+        let (result, _) = tokio::join!(self.run(sender), consumer);
+        result
+    }
+
+    /// 带指数退避重连的订阅循环，事件写入 `sender`
+    ///
+    /// 重连时通过 `from_slot` 从未处理完的slot开始重放，已处理的签名由去重器过滤；
+    /// 服务端无法重放时改为实时订阅，并在指标中记录丢失的slot数量。
+    /// 不限制总重连时长，收到过消息的会话结束后退避从头计算。
+    pub(crate) async fn run(&self, sender: mpsc::Sender<TokenEvent>) -> Result<()> {
+        info!("🚀 Starting {} gRPC stream...", self.name);
+
+        let resume = Mutex::new(ResumeState::default());
+        let mut backoff = ExponentialBackoff { max_elapsed_time: None, ..Default::default() };
+        let mut attempt = 0u64;
+        loop {
+            attempt += 1;
+            if attempt > 1 {
+                info!("Retry to connect to the server for {}", self.name);
+            }
+
+            let mut received_any = false;
+            let error = match self.connect_and_subscribe(&sender, &resume, &mut received_any).await {
+                backoff::Error::Permanent(e) => return Err(e),
+                backoff::Error::Transient { err, .. } => err,
+            };
+
+            if received_any {
+                backoff.reset();
+            }
+            let Some(delay) = backoff.next_backoff() else {
+                return Err(error);
+            };
+            warn!("{} 连接中断: {}，{:?} 后重连", self.name, error, delay);
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// 建立一次连接并订阅，会话总是以错误结束：服务端正常关闭流也按可重试错误处理
+    async fn connect_and_subscribe(
+        &self,
+        sender: &mpsc::Sender<TokenEvent>,
+        resume: &Mutex<ResumeState>,
+        received_any: &mut bool,
+    ) -> backoff::Error<anyhow::Error> {
+        {
+            let mut metrics = self.metrics.write().await;
+            metrics.reconnection_attempts += 1;
+            metrics.last_connection_time = Some(Instant::now());
+        }
+        let mut client = match connect_client(&self.config).await {
+            Ok(client) => client,
+            Err(e) => return backoff::Error::transient(e),
+        };
+        info!("✅ Connected to Shyft gRPC for {}", self.name);
+
+        let mut request = match build_subscribe_request(&self.config, &self.filters) {
+            Ok(request) => request,
+            Err(e) => return backoff::Error::Permanent(e),
+        };
+        request.from_slot = lock(resume).resume_slot();
+        if let Some(from_slot) = request.from_slot {
+            info!("⏪ {} 从 slot {} 开始重放", self.name, from_slot);
+        }
+
+        match self.geyser_subscribe(&mut client, request, sender, resume, received_any).await {
+            // 接收端已关闭，不再重连
+            Err(e) if sender.is_closed() => backoff::Error::Permanent(e),
+            Err(e) => backoff::Error::transient(e),
+            Ok(()) => backoff::Error::transient(anyhow::anyhow!("{} stream closed", self.name)),
+        }
+    }
+
+    async fn geyser_subscribe(
+        &self,
+        client: &mut GeyserGrpcClient<impl yellowstone_grpc_client::Interceptor>,
+        request: SubscribeRequest,
+        sender: &mpsc::Sender<TokenEvent>,
+        resume: &Mutex<ResumeState>,
+        received_any: &mut bool,
+    ) -> Result<()> {
+        let replaying = request.from_slot.is_some();
+        let (mut subscribe_tx, mut stream) = client.subscribe_with_request(Some(request)).await?;
+        self.metrics.write().await.connection_opened();
+        info!("✅ {} gRPC stream opened", self.name);
+
+        // 看门狗：连接未断但长时间没有任何消息（含slot更新和ping）时强制重连
+        let stall_timeout = Duration::from_secs(self.config.stall_timeout_seconds.max(1));
+        let result = async {
+            loop {
+                let message = match tokio::time::timeout(stall_timeout, stream.next()).await {
+                    Ok(Some(message)) => message,
+                    Ok(None) => break,
+                    Err(_) => {
+                        warn!("⏰ {} 超过 {:?} 未收到任何消息，强制重连", self.name, stall_timeout);
+                        self.metrics.write().await.stall_reconnects += 1;
+                        return Err(anyhow::anyhow!("{} stream stalled", self.name));
+                    }
+                };
+
+                if message.is_ok() {
+                    let mut metrics = self.metrics.write().await;
+                    metrics.record_message();
+                    if !*received_any && replaying {
+                        metrics.resumed_subscriptions += 1;
+                    }
+                    *received_any = true;
+                }
+
+                if let (Ok(msg), Some(recorder)) = (&message, &self.recorder) {
+                    recorder.record(msg);
+                }
+
+                match message {
+                    Ok(msg) => match msg.update_oneof {
+                        Some(UpdateOneof::Transaction(update)) => {
+                            let Some(txn_info) = update.transaction else { continue };
+
+                            let completed = lock(resume).observe_transaction(update.slot);
+                            if let Some(completed) = completed {
+                                self.metrics.write().await.last_processed_slot = Some(completed);
+                            }
+
+                            let (dedup, endpoint) = &self.dedup;
+                            if let Arrival::Duplicate { .. } = dedup.observe(&txn_info.signature, *endpoint) {
+                                continue;
+                            }
+
+                            {
+                                let mut metrics = self.metrics.write().await;
+                                metrics.transactions_processed += 1;
+
+                                // 每处理500个交易打印一次简化的统计信息
+                                if metrics.transactions_processed % 500 == 0 {
+                                    info!("📊 [{}] 已处理 {} 笔交易，发现 {} 个新代币",
+                                         self.name,
+                                         metrics.transactions_processed,
+                                         metrics.tokens_detected);
+                                }
+                            }
+
+                            for (filter_name, token_event) in detect_with_filters(&self.filters, &msg.filters, &txn_info, update.slot).await {
+                                log_token_event(filter_name, &token_event);
+                                self.metrics.write().await.record_event(&token_event);
+
+                                if sender.send(token_event).await.is_err() {
+                                    return Err(anyhow::anyhow!("{} event receiver dropped", self.name));
+                                }
+                            }
+                        }
+                        Some(UpdateOneof::Slot(slot_update)) => {
+                            let gap = lock(resume).take_gap(slot_update.slot);
+                            if let Some(gap) = gap {
+                                warn!("⚠️ {} 无法重放断线期间的 {} 个slot，可能错过新代币", self.name, gap);
+                                self.metrics.write().await.unreplayed_slots += gap;
+                            }
+                        }
+                        Some(UpdateOneof::Ping(_)) => {
+                            self.metrics.write().await.last_ping_at = Some(Instant::now());
+                            // 响应ping
+                            if let Err(e) = subscribe_tx
+                                .send(SubscribeRequest {
+                                    ping: Some(SubscribeRequestPing { id: 1 }),
+                                    ..Default::default()
+                                })
+                                .await
+                            {
+                                error!("Failed to send pong: {}", e);
+                            }
+                        }
+                        Some(UpdateOneof::Pong(_)) => {
+                            debug!("Received pong from server");
+                        }
+                        None => {
+                            error!("Update not found in the message");
+                            break;
+                        }
+                        _ => {
+                            debug!("Received other update type");
+                        }
+                    },
+                    Err(error) => {
+                        error!("Stream error: {:?}", error);
+                        if replaying && !*received_any {
+                            // 重放请求被拒绝（slot已超出服务端保留范围），下次改为实时订阅
+                            lock(resume).replay_unavailable = true;
+                        }
+                        self.metrics.write().await.connection_errors += 1;
+                        return Err(anyhow::anyhow!("Stream error: {:?}", error));
+                    }
+                }
+            }
+
+            info!("{} stream closed", self.name);
+            Ok(())
+        }
+        .await;
+
+        self.metrics.write().await.connection_closed();
+        result
+    }
+}
+
+impl TokenEventSource for GeyserTokenStream {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn metrics(&self) -> SharedStreamMetrics {
+        Arc::clone(&self.metrics)
+    }
+
+    fn event_stream(&self) -> impl Stream<Item = TokenEvent> + Send + 'static {
+        let (sender, receiver) = mpsc::channel(EVENT_CHANNEL_CAPACITY);
+        let this = self.clone();
+
+        tokio::spawn(async move {
+            if let Err(e) = this.run(sender).await {
+                error!("❌ {} 事件源终止: {}", this.name, e);
+            }
+        });
+
+        ReceiverStream::new(receiver)
+    }
+}
+
+fn lock(resume: &Mutex<ResumeState>) -> std::sync::MutexGuard<'_, ResumeState> {
+    resume.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// 按服务端返回的过滤器名，把通过预过滤的交易分发给命中的检测器
+pub(crate) async fn detect_with_filters<'a>(
+    filters: &'a [TransactionFilter],
+    matched_filters: &[String],
+    txn_info: &SubscribeUpdateTransactionInfo,
+    slot: u64,
+) -> Vec<(&'a str, TokenEvent)> {
+    let mut events = Vec::new();
+    for filter in filters.iter().filter(|filter| matched_filters.contains(&filter.name) && filter.accepts(txn_info)) {
+        if let Some(token_event) = filter.detector.detect(txn_info, slot).await {
+            events.push((filter.name.as_str(), token_event));
+        }
+    }
+    events
+}
+
+pub(crate) fn log_token_event(name: &str, token_event: &TokenEvent) {
+    let mint = token_event.mint();
+    match token_event.kind.direction() {
+        _ if token_event.is_creation() => {
+            info!("🚀 NEW TOKEN [{}] | {} | Slot: {}", name, mint, token_event.slot);
+            debug!("   Signature: {}", token_event.signature);
+            debug!("   Event: {}", token_event.kind.name());
+        }
+        Some(TradeDirection::Buy) => {
+            debug!("💰 BUY [{}] | {} | Slot: {}", name, mint, token_event.slot);
+        }
+        Some(TradeDirection::Sell) => {
+            debug!("💸 SELL [{}] | {} | Slot: {}", name, mint, token_event.slot);
+        }
+        None => {
+            debug!("其他事件: {} | {}", token_event.kind.name(), mint);
+        }
+    }
+}
+
+/// Extract log message for event parsing
+pub fn extract_log_message(logs: &[String]) -> Option<String> {
+    logs.iter()
+        .find_map(|message| {
+            if message.starts_with("Program data: ") {
+                let encoded = message.trim_start_matches("Program data: ").trim();
+                Some(encoded.to_string())
+            } else {
+                None
+            }
+        })
+}
+
+/// Create a gRPC client connection from config
+pub async fn connect_client(config: &StreamShyftConfig) -> Result<GeyserGrpcClient<impl yellowstone_grpc_client::Interceptor>> {
+    GeyserGrpcClient::build_from_shared(config.endpoint.clone())?
+        .x_token(Some(config.x_token.clone()))?
+        .connect_timeout(Duration::from_secs(config.timeout_seconds))
+        .timeout(Duration::from_secs(config.timeout_seconds))
+        .tls_config(ClientTlsConfig::new().with_native_roots())?
+        .max_decoding_message_size(1024 * 1024 * 1024)  // 1GB max
+        .connect()
+        .await
+        .map_err(Into::into)
+}
+
+/// Map the configured commitment level
+pub fn commitment_level(config: &StreamShyftConfig) -> CommitmentLevel {
+    match config.commitment_level.as_str() {
+        "processed" => CommitmentLevel::Processed,
+        "confirmed" => CommitmentLevel::Confirmed,
+        "finalized" => CommitmentLevel::Finalized,
+        _ => CommitmentLevel::Processed,
+    }
+}
+
+/// Build subscription request with one named transaction filter per protocol
+pub fn build_subscribe_request(config: &StreamShyftConfig, filters: &[TransactionFilter]) -> Result<SubscribeRequest> {
+    if filters.is_empty() {
+        return Err(anyhow::anyhow!("No transaction filters configured"));
+    }
+
+    let mut transactions = HashMap::new();
+
+    for filter in filters {
+        transactions.insert(
+            filter.name.clone(),
+            SubscribeRequestFilterTransactions {
+                vote: Some(false),
+                failed: Some(false),
+                account_include: filter.program_ids.clone(),
+                account_exclude: vec![],
+                account_required: vec![],
+                signature: None,
+            },
+        );
+    }
+
+    // slot 更新用于计算无法重放的缺口
+    let slots = HashMap::from([(
+        SLOTS_FILTER_NAME.to_string(),
+        SubscribeRequestFilterSlots {
+            filter_by_commitment: Some(true),
+            ..Default::default()
+        },
+    )]);
+
+    Ok(SubscribeRequest {
+        accounts: HashMap::default(),
+        slots,
+        transactions,
+        transactions_status: HashMap::default(),
+        blocks: HashMap::default(),
+        blocks_meta: HashMap::default(),
+        entry: HashMap::default(),
+        commitment: Some(commitment_level(config) as i32),
+        accounts_data_slice: Vec::default(),
+        ping: None,
+        from_slot: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::processors::{LetsbonkDetector, TransactionProcessor};
+    use std::collections::VecDeque;
+    use std::pin::Pin;
+    use tokio_stream::wrappers::TcpListenerStream;
+    use tonic::{Request, Response, Status, Streaming};
+    use yellowstone_grpc_proto::geyser::geyser_server::{Geyser, GeyserServer};
+    use yellowstone_grpc_proto::geyser::{
+        GetBlockHeightRequest, GetBlockHeightResponse, GetLatestBlockhashRequest, GetLatestBlockhashResponse,
+        GetSlotRequest, GetSlotResponse, GetVersionRequest, GetVersionResponse, IsBlockhashValidRequest,
+        IsBlockhashValidResponse, PingRequest, PongResponse, SubscribeUpdate, SubscribeUpdateTransaction,
+    };
+
+    /// 一次订阅会话：先推送这些slot上的交易，然后关闭流或保持沉默
+    struct Session {
+        slots: Vec<u64>,
+        close: bool,
+    }
+
+    /// 按脚本应答订阅请求的 Geyser 服务端，记录每次订阅的 `from_slot`
+    struct ScriptedGeyser {
+        sessions: std::sync::Mutex<VecDeque<Session>>,
+        from_slots: mpsc::UnboundedSender<Option<u64>>,
+    }
+
+    fn transaction_update(slot: u64) -> SubscribeUpdate {
+        SubscribeUpdate {
+            update_oneof: Some(UpdateOneof::Transaction(SubscribeUpdateTransaction {
+                transaction: Some(SubscribeUpdateTransactionInfo { signature: vec![slot as u8; 64], ..Default::default() }),
+                slot,
+            })),
+            ..Default::default()
+        }
+    }
+
+    #[tonic::async_trait]
+    impl Geyser for ScriptedGeyser {
+        type SubscribeStream = Pin<Box<dyn Stream<Item = std::result::Result<SubscribeUpdate, Status>> + Send>>;
+
+        async fn subscribe(
+            &self,
+            request: Request<Streaming<SubscribeRequest>>,
+        ) -> std::result::Result<Response<Self::SubscribeStream>, Status> {
+            let subscribe = request.into_inner().message().await?.unwrap_or_default();
+            let _ = self.from_slots.send(subscribe.from_slot);
+
+            // 脚本用完后保持沉默
+            let session = self.sessions.lock().unwrap().pop_front().unwrap_or(Session { slots: vec![], close: false });
+            let updates = futures::stream::iter(session.slots.into_iter().map(transaction_update).map(Ok));
+            if session.close {
+                Ok(Response::new(Box::pin(updates)))
+            } else {
+                Ok(Response::new(Box::pin(updates.chain(futures::stream::pending()))))
+            }
+        }
+
+        async fn ping(&self, _request: Request<PingRequest>) -> std::result::Result<Response<PongResponse>, Status> {
+            Err(Status::unimplemented("ping"))
+        }
+
+        async fn get_latest_blockhash(
+            &self,
+            _request: Request<GetLatestBlockhashRequest>,
+        ) -> std::result::Result<Response<GetLatestBlockhashResponse>, Status> {
+            Err(Status::unimplemented("get_latest_blockhash"))
+        }
+
+        async fn get_block_height(
+            &self,
+            _request: Request<GetBlockHeightRequest>,
+        ) -> std::result::Result<Response<GetBlockHeightResponse>, Status> {
+            Err(Status::unimplemented("get_block_height"))
+        }
+
+        async fn get_slot(&self, _request: Request<GetSlotRequest>) -> std::result::Result<Response<GetSlotResponse>, Status> {
+            Err(Status::unimplemented("get_slot"))
+        }
+
+        async fn is_blockhash_valid(
+            &self,
+            _request: Request<IsBlockhashValidRequest>,
+        ) -> std::result::Result<Response<IsBlockhashValidResponse>, Status> {
+            Err(Status::unimplemented("is_blockhash_valid"))
+        }
+
+        async fn get_version(&self, _request: Request<GetVersionRequest>) -> std::result::Result<Response<GetVersionResponse>, Status> {
+            Err(Status::unimplemented("get_version"))
+        }
+    }
+
+    /// 启动脚本服务端和订阅循环，返回订阅请求的 `from_slot` 接收端
+    async fn run_against(sessions: Vec<Session>) -> (GeyserTokenStream, mpsc::UnboundedReceiver<Option<u64>>, tokio::task::JoinHandle<Result<()>>) {
+        let (from_slots, requests) = mpsc::unbounded_channel();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(GeyserServer::new(ScriptedGeyser { sessions: std::sync::Mutex::new(sessions.into()), from_slots }))
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+
+        let config = StreamShyftConfig::new(format!("http://{}", addr), String::new()).with_stall_timeout(1);
+        let stream = GeyserTokenStream::new("test", config)
+            .with_filter(TransactionFilter::new("pumpfun", vec!["pump".to_string()], Arc::new(TransactionProcessor::default())));
+        let (sender, receiver) = mpsc::channel(16);
+        let runner = stream.clone();
+        let handle = tokio::spawn(async move {
+            let result = runner.run(sender).await;
+            drop(receiver);
+            result
+        });
+        (stream, requests, handle)
+    }
+
+    async fn next_from_slot(requests: &mut mpsc::UnboundedReceiver<Option<u64>>) -> Option<u64> {
+        tokio::time::timeout(Duration::from_secs(10), requests.recv()).await.expect("no resubscribe").unwrap()
+    }
+
+    #[test]
+    fn test_build_subscribe_request_named_filters() {
+        let config = StreamShyftConfig::new("https://example.com".to_string(), "token".to_string())
+            .with_commitment("confirmed".to_string());
+        let filters = vec![
+            TransactionFilter::new("pumpfun", vec!["pump".to_string()], Arc::new(TransactionProcessor::default())),
+            TransactionFilter::new("letsbonk", vec!["launchpad".to_string()], Arc::new(LetsbonkDetector::default())),
+        ];
+        let request = build_subscribe_request(&config, &filters).unwrap();
+
+        assert_eq!(request.transactions.len(), 2);
+        assert_eq!(request.transactions["pumpfun"].account_include, vec!["pump".to_string()]);
+        assert_eq!(request.transactions["letsbonk"].account_include, vec!["launchpad".to_string()]);
+        assert_eq!(request.commitment, Some(CommitmentLevel::Confirmed as i32));
+        assert!(build_subscribe_request(&config, &[]).is_err());
+        assert!(request.slots.contains_key(SLOTS_FILTER_NAME));
+        assert_eq!(request.from_slot, None);
+    }
+
+    #[test]
+    fn test_resume_state_tracks_progress_and_gap() {
+        let mut state = ResumeState::default();
+        assert_eq!(state.resume_slot(), None);
+
+        assert_eq!(state.observe_transaction(100), None);
+        assert_eq!(state.observe_transaction(100), None);
+        assert_eq!(state.observe_transaction(102), Some(100));
+        assert_eq!(state.observe_transaction(101), None);
+        assert_eq!(state.resume_slot(), Some(102));
+
+        // 重放被拒绝：下一次实时订阅，缺口从102算起
+        state.replay_unavailable = true;
+        assert_eq!(state.resume_slot(), None);
+        assert_eq!(state.take_gap(150), Some(48));
+        assert_eq!(state.take_gap(151), None);
+        assert_eq!(state.resume_slot(), Some(102));
+    }
+
+    #[tokio::test]
+    async fn test_clean_close_resubscribes_from_last_slot() {
+        let (stream, mut requests, handle) = run_against(vec![Session { slots: vec![100, 101], close: true }]).await;
+
+        assert_eq!(next_from_slot(&mut requests).await, None);
+        // 服务端正常关闭流后重新订阅，并从未处理完的slot开始重放
+        assert_eq!(next_from_slot(&mut requests).await, Some(101));
+        assert!(!handle.is_finished());
+        assert_eq!(stream.get_metrics().await.reconnection_attempts, 2);
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_stall_after_first_session_resubscribes() {
+        let sessions = vec![
+            Session { slots: vec![100], close: true },
+            // 第二个会话推送后保持沉默，看门狗强制重连
+            Session { slots: vec![105], close: false },
+        ];
+        let (stream, mut requests, handle) = run_against(sessions).await;
+
+        assert_eq!(next_from_slot(&mut requests).await, None);
+        assert_eq!(next_from_slot(&mut requests).await, Some(100));
+        assert_eq!(next_from_slot(&mut requests).await, Some(105));
+        assert!(!handle.is_finished());
+        assert_eq!(stream.get_metrics().await.stall_reconnects, 1);
+        handle.abort();
+    }
+}
@@ -1,3 +1,3 @@
 pub mod stream;
 
-pub use stream::{LetsbonkStream, LetsbonkMetrics, extract_log_message};
\ No newline at end of file
+pub use stream::{LetsbonkStream, letsbonk_filter};
\ No newline at end of file
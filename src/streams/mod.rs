@@ -1,5 +1,23 @@
+pub mod source;
+pub mod dedup;
+pub mod geyser;
 pub mod shyft;
 pub mod letsbonk;
+pub mod shredstream;
+pub mod multi;
+pub mod racing;
+pub mod replay;
+pub mod pubsub;
+pub mod accounts;
 
+pub use source::{TokenEventSource, StreamMetrics, ConnectionStatus, SharedStreamMetrics, FeedFreshness};
+pub use geyser::GeyserTokenStream;
 pub use shyft::ShyftStream;
-pub use letsbonk::{LetsbonkStream, LetsbonkMetrics};
\ No newline at end of file
+pub use letsbonk::LetsbonkStream;
+pub use shredstream::ShredStream;
+pub use multi::MultiProtocolStream;
+pub use racing::RacingTokenStream;
+pub use replay::{ReplayStream, ReplayPace, UpdateRecorder};
+pub use pubsub::RpcPubsubStream;
+pub use accounts::{AccountPriceStream, PriceUpdate};
+pub use dedup::{SignatureDeduplicator, EndpointStats};
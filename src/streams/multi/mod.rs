@@ -0,0 +1,3 @@
+pub mod stream;
+
+pub use stream::MultiProtocolStream;
\ No newline at end of file
@@ -0,0 +1,65 @@
+use anyhow::Result;
+use futures::Stream;
+use std::sync::Arc;
+
+use crate::config::StreamShyftConfig;
+use crate::processors::TokenEvent;
+use crate::streams::geyser::GeyserTokenStream;
+use crate::streams::letsbonk::stream::letsbonk_filter;
+use crate::streams::shyft::stream::pumpfun_filter;
+use crate::streams::replay::UpdateRecorder;
+use crate::streams::source::{ConnectionStatus, SharedStreamMetrics, StreamMetrics, TokenEventSource};
+
+/// 单订阅多协议事件源：PumpFun + Raydium Launchpad (LetsBonk)
+///
+/// 同一个 gRPC 订阅中包含 "pumpfun" 与 "letsbonk" 两个命名过滤器，
+/// 交易按命中的过滤器分发到各自的检测器。
+pub struct MultiProtocolStream {
+    inner: GeyserTokenStream,
+}
+
+impl MultiProtocolStream {
+    pub fn new(config: StreamShyftConfig) -> Self {
+        Self {
+            inner: GeyserTokenStream::new("PumpFun+Letsbonk", config)
+                .with_filter(pumpfun_filter())
+                .with_filter(letsbonk_filter()),
+        }
+    }
+
+    /// 录制收到的每条原始订阅更新
+    pub fn with_recorder(mut self, recorder: Arc<UpdateRecorder>) -> Self {
+        self.inner = self.inner.with_recorder(recorder);
+        self
+    }
+
+    pub async fn get_metrics(&self) -> StreamMetrics {
+        self.inner.get_metrics().await
+    }
+
+    /// Get connection status information
+    pub async fn get_connection_status(&self) -> ConnectionStatus {
+        self.inner.get_connection_status().await
+    }
+
+    pub async fn start_streaming<F>(&self, callback: F) -> Result<()>
+    where
+        F: Fn(TokenEvent) -> Result<()> + Send + Sync + 'static,
+    {
+        self.inner.start_streaming(callback).await
+    }
+}
+
+impl TokenEventSource for MultiProtocolStream {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn metrics(&self) -> SharedStreamMetrics {
+        self.inner.metrics()
+    }
+
+    fn event_stream(&self) -> impl Stream<Item = TokenEvent> + Send + 'static {
+        self.inner.event_stream()
+    }
+}
@@ -0,0 +1,3 @@
+pub mod stream;
+
+pub use stream::{RpcPubsubStream, TransactionFetcher, transaction_info_from_encoded};
@@ -0,0 +1,592 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use backoff::future::retry;
+use backoff::ExponentialBackoff;
+use futures::stream::{select_all, BoxStream, FuturesUnordered};
+use futures::{Stream, StreamExt};
+use log::{debug, error, info, warn};
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcTransactionConfig, RpcTransactionLogsConfig, RpcTransactionLogsFilter};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::{EncodedTransactionWithStatusMeta, UiInstruction, UiTransactionEncoding};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use yellowstone_grpc_proto::geyser::SubscribeUpdateTransactionInfo;
+use yellowstone_grpc_proto::solana::storage::confirmed_block as proto;
+
+use crate::config::StreamPubsubConfig;
+use crate::processors::TokenEvent;
+use crate::streams::dedup::{Arrival, SignatureDeduplicator, DEFAULT_DEDUP_CAPACITY};
+use crate::streams::geyser::stream::{detect_with_filters, log_token_event};
+use crate::streams::geyser::TransactionFilter;
+use crate::streams::source::{ConnectionStatus, SharedStreamMetrics, StreamMetrics, TokenEventSource};
+
+/// 事件通道容量
+const EVENT_CHANNEL_CAPACITY: usize = 4096;
+
+/// 同时进行的 getTransaction 请求数
+const FETCH_CONCURRENCY: usize = 16;
+
+/// getTransaction 查询不到交易时的重试间隔
+const FETCH_RETRY_DELAY: Duration = Duration::from_millis(300);
+
+/// 创建和迁移类指令的日志（PumpFun Create / Launchpad Initialize / Migrate）
+const LIFECYCLE_LOG_MARKERS: [&str; 3] = ["Instruction: Create", "Instruction: Initialize", "Instruction: Migrate"];
+
+/// 日志通知：(过滤器序号, 签名, slot, 日志)
+type LogNotification = (usize, String, u64, Vec<String>);
+
+/// 按签名拉取完整交易（含meta）
+#[async_trait]
+pub trait TransactionFetcher: Send + Sync {
+    /// 返回交易所在slot和转换后的交易；交易尚不可见时返回 `Ok(None)`
+    async fn fetch_transaction(&self, signature: &Signature) -> Result<Option<(u64, SubscribeUpdateTransactionInfo)>>;
+}
+
+/// 通过 HTTP RPC `getTransaction` 拉取交易
+struct RpcTransactionFetcher {
+    client: RpcClient,
+    commitment: CommitmentConfig,
+}
+
+#[async_trait]
+impl TransactionFetcher for RpcTransactionFetcher {
+    async fn fetch_transaction(&self, signature: &Signature) -> Result<Option<(u64, SubscribeUpdateTransactionInfo)>> {
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: Some(self.commitment),
+            max_supported_transaction_version: Some(0),
+        };
+
+        // 刚确认的交易可能还查询不到，由调用方重试
+        match self.client.get_transaction_with_config(signature, config).await {
+            Ok(confirmed) => Ok(transaction_info_from_encoded(signature, &confirmed.transaction)
+                .map(|txn_info| (confirmed.slot, txn_info))),
+            Err(e) => {
+                debug!("getTransaction {} 失败: {}", signature, e);
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// 标准 Solana RPC WebSocket 事件源（gRPC 不可用时的降级方案）
+///
+/// 对每个过滤器的程序发起 `logsSubscribe`（mentions），命中后用 `getTransaction`
+/// 拉取完整交易，转换为 gRPC 交易结构后交给同一套检测器，因此产生的 `TokenEvent`
+/// 与 gRPC 源一致，只是延迟更高。
+#[derive(Clone)]
+pub struct RpcPubsubStream {
+    config: StreamPubsubConfig,
+    filters: Vec<TransactionFilter>,
+    fetcher: Arc<dyn TransactionFetcher>,
+    dedup: Arc<SignatureDeduplicator>,
+    metrics: SharedStreamMetrics,
+}
+
+impl RpcPubsubStream {
+    pub fn new(config: StreamPubsubConfig) -> Self {
+        let fetcher = Arc::new(RpcTransactionFetcher {
+            client: RpcClient::new(config.rpc_endpoint.clone()),
+            commitment: fetch_commitment(&config),
+        });
+        Self::with_fetcher(config, fetcher)
+    }
+
+    /// 使用自定义的交易拉取方式
+    pub fn with_fetcher(config: StreamPubsubConfig, fetcher: Arc<dyn TransactionFetcher>) -> Self {
+        Self {
+            config,
+            filters: Vec::new(),
+            fetcher,
+            dedup: Arc::new(SignatureDeduplicator::new(DEFAULT_DEDUP_CAPACITY, vec!["pubsub".to_string()])),
+            metrics: StreamMetrics::shared(),
+        }
+    }
+
+    /// 添加一个命名过滤器
+    pub fn with_filter(mut self, filter: TransactionFilter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    pub async fn get_metrics(&self) -> StreamMetrics {
+        self.metrics.read().await.clone()
+    }
+
+    /// Get connection status information
+    pub async fn get_connection_status(&self) -> ConnectionStatus {
+        self.metrics.read().await.connection_status()
+    }
+
+    /// 回调形式的流式处理，直到连接永久失败
+    pub async fn start_streaming<F>(&self, callback: F) -> Result<()>
+    where
+        F: Fn(TokenEvent) -> Result<()> + Send + Sync + 'static,
+    {
+        let (sender, mut receiver) = mpsc::channel(EVENT_CHANNEL_CAPACITY);
+
+        let consumer = async move {
+            while let Some(event) = receiver.recv().await {
+                if let Err(e) = callback(event) {
+                    warn!("Callback error: {}", e);
+                }
+            }
+        };
+
+        let (result, _) = tokio::join!(self.run(sender), consumer);
+        result
+    }
+
+    /// 带指数退避重连的订阅循环，事件写入 `sender`
+    async fn run(&self, sender: mpsc::Sender<TokenEvent>) -> Result<()> {
+        info!("🚀 Starting RPC WebSocket stream: {}", self.config.ws_endpoint);
+        if self.filters.is_empty() {
+            return Err(anyhow::anyhow!("No transaction filters configured"));
+        }
+
+        // 事件源需要与进程同生命周期，不限制总重试时长
+        let backoff = ExponentialBackoff { max_elapsed_time: None, ..Default::default() };
+        let mut attempt = 0u64;
+        retry(backoff, || {
+            attempt += 1;
+            let attempt = attempt;
+            let sender = sender.clone();
+
+            async move {
+                if attempt > 1 {
+                    info!("Retry to connect to the RPC WebSocket");
+                }
+
+                {
+                    let mut metrics = self.metrics.write().await;
+                    metrics.reconnection_attempts += 1;
+                    metrics.last_connection_time = Some(Instant::now());
+                }
+                let client = PubsubClient::new(&self.config.ws_endpoint)
+                    .await
+                    .map_err(|e| backoff::Error::transient(anyhow::anyhow!("WebSocket connect failed: {}", e)))?;
+                info!("✅ Connected to RPC WebSocket");
+
+                let result = self.subscribe_logs(&client, &sender).await;
+
+                match result {
+                    // 接收端已关闭，不再重连
+                    Err(e) if sender.is_closed() => Err(backoff::Error::Permanent(e)),
+                    Err(e) => Err(backoff::Error::transient(e)),
+                    // WebSocket 被服务端关闭，重连
+                    Ok(()) => Err(backoff::Error::transient(anyhow::anyhow!("RPC WebSocket closed"))),
+                }
+            }
+        })
+        .await
+    }
+
+    async fn subscribe_logs(&self, client: &PubsubClient, sender: &mpsc::Sender<TokenEvent>) -> Result<()> {
+        let config = RpcTransactionLogsConfig {
+            commitment: Some(subscribe_commitment(&self.config)),
+        };
+
+        // 每个程序一个 mentions 订阅，通知带上过滤器序号
+        let mut subscriptions: Vec<BoxStream<'_, LogNotification>> = Vec::new();
+        for (index, filter) in self.filters.iter().enumerate() {
+            for program_id in &filter.program_ids {
+                let (notifications, _unsubscribe) = client
+                    .logs_subscribe(RpcTransactionLogsFilter::Mentions(vec![program_id.clone()]), config.clone())
+                    .await
+                    .map_err(|e| anyhow::anyhow!("logsSubscribe {} failed: {}", program_id, e))?;
+                info!("📡 logsSubscribe [{}] {}", filter.name, program_id);
+
+                subscriptions.push(
+                    notifications
+                        .filter_map(move |response| async move {
+                            // 失败的交易不产生事件
+                            if response.value.err.is_some() {
+                                return None;
+                            }
+                            Some((index, response.value.signature, response.context.slot, response.value.logs))
+                        })
+                        .boxed(),
+                );
+            }
+        }
+
+        self.metrics.write().await.connection_opened();
+        let result = self.process_notifications(select_all(subscriptions), sender).await;
+        self.metrics.write().await.connection_closed();
+        result
+    }
+
+    async fn process_notifications(
+        &self,
+        mut notifications: impl Stream<Item = LogNotification> + Unpin,
+        sender: &mpsc::Sender<TokenEvent>,
+    ) -> Result<()> {
+        let stall_timeout = Duration::from_secs(self.config.stall_timeout_seconds.max(1));
+        let mut fetches = FuturesUnordered::new();
+        let mut last_notification = Instant::now();
+
+        loop {
+            tokio::select! {
+                Some(events) = fetches.next(), if !fetches.is_empty() => {
+                    for (filter_name, token_event) in events {
+                        log_token_event(filter_name, &token_event);
+                        self.metrics.write().await.record_event(&token_event);
+
+                        if sender.send(token_event).await.is_err() {
+                            return Err(anyhow::anyhow!("RPC WebSocket event receiver dropped"));
+                        }
+                    }
+                }
+                notification = notifications.next(), if fetches.len() < FETCH_CONCURRENCY => {
+                    let Some((index, signature, slot, logs)) = notification else { break };
+                    last_notification = Instant::now();
+                    self.metrics.write().await.record_message();
+
+                    if let Some(signature) = self.should_fetch(&signature, &logs) {
+                        fetches.push(self.fetch_and_detect(index, signature, slot));
+                    }
+                }
+                // 看门狗：WebSocket 未断但长时间没有日志通知时强制重连
+                _ = tokio::time::sleep_until((last_notification + stall_timeout).into()) => {
+                    warn!("⏰ RPC WebSocket 超过 {:?} 未收到任何通知，强制重连", stall_timeout);
+                    self.metrics.write().await.stall_reconnects += 1;
+                    return Err(anyhow::anyhow!("RPC WebSocket stalled"));
+                }
+            }
+        }
+
+        info!("RPC WebSocket stream closed");
+        Ok(())
+    }
+
+    /// 日志预过滤 + 签名去重，返回需要拉取的交易签名
+    fn should_fetch(&self, signature: &str, logs: &[String]) -> Option<Signature> {
+        if self.config.creations_only
+            && !logs.iter().any(|log| LIFECYCLE_LOG_MARKERS.iter().any(|marker| log.contains(marker)))
+        {
+            return None;
+        }
+
+        let signature = Signature::from_str(signature).ok()?;
+        match self.dedup.observe(signature.as_ref(), 0) {
+            Arrival::First => Some(signature),
+            Arrival::Duplicate { .. } => None,
+        }
+    }
+
+    /// 拉取交易并交给日志所属过滤器的检测器
+    async fn fetch_and_detect(&self, index: usize, signature: Signature, log_slot: u64) -> Vec<(&str, TokenEvent)> {
+        let filter = &self.filters[index];
+
+        for attempt in 0..=self.config.fetch_retries {
+            if attempt > 0 {
+                tokio::time::sleep(FETCH_RETRY_DELAY).await;
+            }
+
+            match self.fetcher.fetch_transaction(&signature).await {
+                Ok(Some((slot, txn_info))) => {
+                    self.metrics.write().await.transactions_processed += 1;
+                    let slot = if slot == 0 { log_slot } else { slot };
+                    return detect_with_filters(std::slice::from_ref(filter), std::slice::from_ref(&filter.name), &txn_info, slot).await;
+                }
+                Ok(None) => continue,
+                Err(e) => {
+                    warn!("❌ 拉取交易失败 {}: {}", signature, e);
+                    break;
+                }
+            }
+        }
+
+        self.metrics.write().await.decode_errors += 1;
+        debug!("⚠️ 未能获取交易 {}", signature);
+        Vec::new()
+    }
+}
+
+impl TokenEventSource for RpcPubsubStream {
+    fn name(&self) -> &str {
+        "RpcPubsub"
+    }
+
+    fn metrics(&self) -> SharedStreamMetrics {
+        Arc::clone(&self.metrics)
+    }
+
+    fn event_stream(&self) -> impl Stream<Item = TokenEvent> + Send + 'static {
+        let (sender, receiver) = mpsc::channel(EVENT_CHANNEL_CAPACITY);
+        let this = self.clone();
+
+        tokio::spawn(async move {
+            if let Err(e) = this.run(sender).await {
+                error!("❌ {} 事件源终止: {}", this.name(), e);
+            }
+        });
+
+        ReceiverStream::new(receiver)
+    }
+}
+
+fn subscribe_commitment(config: &StreamPubsubConfig) -> CommitmentConfig {
+    match config.commitment_level.as_str() {
+        "processed" => CommitmentConfig::processed(),
+        "finalized" => CommitmentConfig::finalized(),
+        _ => CommitmentConfig::confirmed(),
+    }
+}
+
+/// getTransaction 不支持 processed，最低使用 confirmed
+fn fetch_commitment(config: &StreamPubsubConfig) -> CommitmentConfig {
+    match config.commitment_level.as_str() {
+        "finalized" => CommitmentConfig::finalized(),
+        _ => CommitmentConfig::confirmed(),
+    }
+}
+
+/// 把 RPC 返回的交易转换为 gRPC 交易结构，失败的交易返回 None
+pub fn transaction_info_from_encoded(
+    signature: &Signature,
+    encoded: &EncodedTransactionWithStatusMeta,
+) -> Option<SubscribeUpdateTransactionInfo> {
+    let transaction = encoded.transaction.decode()?;
+    let meta = encoded.meta.as_ref()?;
+    if meta.err.is_some() {
+        return None;
+    }
+
+    let message = &transaction.message;
+    let header = message.header();
+    let proto_message = proto::Message {
+        header: Some(proto::MessageHeader {
+            num_required_signatures: header.num_required_signatures as u32,
+            num_readonly_signed_accounts: header.num_readonly_signed_accounts as u32,
+            num_readonly_unsigned_accounts: header.num_readonly_unsigned_accounts as u32,
+        }),
+        account_keys: message.static_account_keys().iter().map(|key| key.to_bytes().to_vec()).collect(),
+        recent_blockhash: message.recent_blockhash().to_bytes().to_vec(),
+        instructions: message
+            .instructions()
+            .iter()
+            .map(|ix| proto::CompiledInstruction {
+                program_id_index: ix.program_id_index as u32,
+                accounts: ix.accounts.clone(),
+                data: ix.data.clone(),
+            })
+            .collect(),
+        versioned: matches!(message, VersionedMessage::V0(_)),
+        address_table_lookups: message
+            .address_table_lookups()
+            .unwrap_or_default()
+            .iter()
+            .map(|lookup| proto::MessageAddressTableLookup {
+                account_key: lookup.account_key.to_bytes().to_vec(),
+                writable_indexes: lookup.writable_indexes.clone(),
+                readonly_indexes: lookup.readonly_indexes.clone(),
+            })
+            .collect(),
+    };
+
+    let inner_instructions = match &meta.inner_instructions {
+        OptionSerializer::Some(inner) => inner
+            .iter()
+            .map(|inner| proto::InnerInstructions {
+                index: inner.index as u32,
+                instructions: inner
+                    .instructions
+                    .iter()
+                    .filter_map(|ix| match ix {
+                        UiInstruction::Compiled(ix) => Some(proto::InnerInstruction {
+                            program_id_index: ix.program_id_index as u32,
+                            accounts: ix.accounts.clone(),
+                            data: bs58::decode(&ix.data).into_vec().ok()?,
+                            stack_height: ix.stack_height,
+                        }),
+                        UiInstruction::Parsed(_) => None,
+                    })
+                    .collect(),
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let (loaded_writable_addresses, loaded_readonly_addresses) = match &meta.loaded_addresses {
+        OptionSerializer::Some(loaded) => (decode_addresses(&loaded.writable), decode_addresses(&loaded.readonly)),
+        _ => (Vec::new(), Vec::new()),
+    };
+
+    Some(SubscribeUpdateTransactionInfo {
+        signature: signature.as_ref().to_vec(),
+        is_vote: false,
+        transaction: Some(proto::Transaction {
+            signatures: transaction.signatures.iter().map(|sig| sig.as_ref().to_vec()).collect(),
+            message: Some(proto_message),
+        }),
+        meta: Some(proto::TransactionStatusMeta {
+            fee: meta.fee,
+            pre_balances: meta.pre_balances.clone(),
+            post_balances: meta.post_balances.clone(),
+            inner_instructions_none: !matches!(meta.inner_instructions, OptionSerializer::Some(_)),
+            inner_instructions,
+            log_messages_none: !matches!(meta.log_messages, OptionSerializer::Some(_)),
+            log_messages: Option::from(meta.log_messages.clone()).unwrap_or_default(),
+            loaded_writable_addresses,
+            loaded_readonly_addresses,
+            compute_units_consumed: Option::from(meta.compute_units_consumed.clone()),
+            ..Default::default()
+        }),
+        index: 0,
+    })
+}
+
+fn decode_addresses(addresses: &[String]) -> Vec<Vec<u8>> {
+    addresses
+        .iter()
+        .filter_map(|address| bs58::decode(address).into_vec().ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::SinkExt;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio_tungstenite::tungstenite::Message;
+    use solana_sdk::pubkey::Pubkey;
+
+    use crate::processors::{ProtocolEvent, TransactionEventDetector};
+    use crate::streams::shyft::stream::PUMPFUN_PROGRAM_ID;
+
+    /// 返回空交易并记录调用次数
+    #[derive(Default)]
+    struct StubFetcher {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl TransactionFetcher for StubFetcher {
+        async fn fetch_transaction(&self, signature: &Signature) -> Result<Option<(u64, SubscribeUpdateTransactionInfo)>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(Some((99, SubscribeUpdateTransactionInfo {
+                signature: signature.as_ref().to_vec(),
+                ..Default::default()
+            })))
+        }
+    }
+
+    /// 每笔交易都产生一个创建事件的检测器
+    struct EveryTransaction;
+
+    #[async_trait]
+    impl TransactionEventDetector for EveryTransaction {
+        async fn detect(&self, txn_info: &SubscribeUpdateTransactionInfo, slot: u64) -> Option<TokenEvent> {
+            Some(TokenEvent::new(
+                bs58::encode(&txn_info.signature).into_string(),
+                slot,
+                ProtocolEvent::PumpFunCreate {
+                    mint: Pubkey::new_unique(),
+                    creator: Pubkey::new_unique(),
+                    metadata: None,
+                    dev_buy: None,
+                },
+            ))
+        }
+    }
+
+    fn logs_notification(signature: &Signature, log: &str) -> Message {
+        Message::Text(
+            json!({
+                "jsonrpc": "2.0",
+                "method": "logsNotification",
+                "params": {
+                    "result": {
+                        "context": { "slot": 5 },
+                        "value": { "signature": signature.to_string(), "err": null, "logs": [log] }
+                    },
+                    "subscription": 1
+                }
+            })
+            .to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_logs_notification_fetches_creations_only() {
+        let buy = Signature::from([1u8; 64]);
+        let create = Signature::from([2u8; 64]);
+
+        // 本地 WebSocket 替身：确认 logsSubscribe 后推送一笔买入和一笔创建
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(socket).await.unwrap();
+            while let Some(Ok(message)) = ws.next().await {
+                let Message::Text(text) = message else { continue };
+                let request: serde_json::Value = serde_json::from_str(&text).unwrap();
+                if request["method"] != "logsSubscribe" {
+                    continue;
+                }
+                assert_eq!(request["params"][0]["mentions"][0], PUMPFUN_PROGRAM_ID);
+
+                let response = json!({ "jsonrpc": "2.0", "result": 1, "id": request["id"] });
+                ws.send(Message::Text(response.to_string())).await.unwrap();
+                ws.send(logs_notification(&buy, "Program log: Instruction: Buy")).await.unwrap();
+                ws.send(logs_notification(&create, "Program log: Instruction: Create")).await.unwrap();
+                ws.send(logs_notification(&create, "Program log: Instruction: Create")).await.unwrap();
+            }
+        });
+
+        let fetcher = Arc::new(StubFetcher::default());
+        let config = StreamPubsubConfig::from_ws_endpoint(format!("ws://{}", addr)).with_creations_only(true);
+        assert_eq!(config.rpc_endpoint, format!("http://{}", addr));
+        let stream = RpcPubsubStream::with_fetcher(config, fetcher.clone())
+            .with_filter(TransactionFilter::new("pumpfun", vec![PUMPFUN_PROGRAM_ID.to_string()], Arc::new(EveryTransaction)));
+
+        let mut events = Box::pin(stream.event_stream());
+        let event = tokio::time::timeout(Duration::from_secs(5), events.next())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(event.signature, create.to_string());
+        assert_eq!(event.slot, 99);
+        // 买入被日志预过滤跳过，重复通知被去重
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(fetcher.calls.load(Ordering::SeqCst), 1);
+        assert!(stream.get_connection_status().await.is_connected);
+    }
+
+    #[test]
+    fn test_default_config_fetches_trades() {
+        let stream = RpcPubsubStream::with_fetcher(
+            StreamPubsubConfig::from_ws_endpoint("ws://127.0.0.1:1".to_string()),
+            Arc::new(StubFetcher::default()),
+        );
+
+        // 默认不做日志预过滤，买卖交易也要拉取，策略才能收到买卖事件
+        let buy = Signature::from([3u8; 64]);
+        assert_eq!(stream.should_fetch(&buy.to_string(), &["Program log: Instruction: Buy".to_string()]), Some(buy));
+    }
+
+    #[test]
+    fn test_creations_only_still_fetches_migrations() {
+        let stream = RpcPubsubStream::with_fetcher(
+            StreamPubsubConfig::from_ws_endpoint("ws://127.0.0.1:1".to_string()).with_creations_only(true),
+            Arc::new(StubFetcher::default()),
+        );
+
+        let migrate = Signature::from([4u8; 64]);
+        assert_eq!(stream.should_fetch(&migrate.to_string(), &["Program log: Instruction: Migrate".to_string()]), Some(migrate));
+        let sell = Signature::from([5u8; 64]);
+        assert_eq!(stream.should_fetch(&sell.to_string(), &["Program log: Instruction: Sell".to_string()]), None);
+    }
+}
@@ -0,0 +1,229 @@
+use anyhow::Result;
+use futures::future::join_all;
+use futures::Stream;
+use log::{error, info, warn};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+
+use crate::config::StreamShyftConfig;
+use crate::processors::TokenEvent;
+use crate::streams::dedup::{EndpointStats, SignatureDeduplicator, DEFAULT_DEDUP_CAPACITY};
+use crate::streams::geyser::{GeyserTokenStream, TransactionFilter};
+use crate::streams::replay::UpdateRecorder;
+use crate::streams::source::{ConnectionStatus, SharedStreamMetrics, StreamMetrics, TokenEventSource};
+
+/// 事件通道容量
+const EVENT_CHANNEL_CAPACITY: usize = 4096;
+
+/// 端点竞速统计的打印间隔
+const STATS_REPORT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 多端点竞速事件源
+///
+/// 同时订阅多个 gRPC 端点（使用相同的过滤器），按交易签名去重，
+/// 只有最先到达的副本会被检测并发出。任一端点断线时其余端点继续工作，
+/// 同时记录各端点的胜率和落后延迟，便于挑选最快的区域。
+#[derive(Clone)]
+pub struct RacingTokenStream {
+    name: String,
+    endpoints: Vec<GeyserTokenStream>,
+    dedup: Arc<SignatureDeduplicator>,
+    metrics: SharedStreamMetrics,
+}
+
+impl RacingTokenStream {
+    /// `endpoints` 为 (区域名, 连接配置) 列表
+    pub fn new(
+        name: impl Into<String>,
+        endpoints: Vec<(String, StreamShyftConfig)>,
+        filters: Vec<TransactionFilter>,
+    ) -> Self {
+        Self::with_dedup_capacity(name, endpoints, filters, DEFAULT_DEDUP_CAPACITY)
+    }
+
+    pub fn with_dedup_capacity(
+        name: impl Into<String>,
+        endpoints: Vec<(String, StreamShyftConfig)>,
+        filters: Vec<TransactionFilter>,
+        dedup_capacity: usize,
+    ) -> Self {
+        let name = name.into();
+        let metrics = StreamMetrics::shared();
+        let dedup = Arc::new(SignatureDeduplicator::new(
+            dedup_capacity,
+            endpoints.iter().map(|(region, _)| region.clone()).collect(),
+        ));
+
+        let endpoints = endpoints
+            .into_iter()
+            .enumerate()
+            .map(|(index, (region, config))| {
+                filters.iter().cloned().fold(
+                    GeyserTokenStream::new(format!("{}@{}", name, region), config)
+                        .with_metrics(Arc::clone(&metrics))
+                        .with_deduplicator(Arc::clone(&dedup), index),
+                    GeyserTokenStream::with_filter,
+                )
+            })
+            .collect();
+
+        Self {
+            name,
+            endpoints,
+            dedup,
+            metrics,
+        }
+    }
+
+    /// 录制所有端点收到的原始订阅更新（含重复交易，重放时按签名去重）
+    pub fn with_recorder(mut self, recorder: Arc<UpdateRecorder>) -> Self {
+        self.endpoints = self
+            .endpoints
+            .into_iter()
+            .map(|endpoint| endpoint.with_recorder(Arc::clone(&recorder)))
+            .collect();
+        self
+    }
+
+    /// 各端点的胜率与延迟统计
+    pub fn endpoint_stats(&self) -> Vec<EndpointStats> {
+        self.dedup.endpoint_stats()
+    }
+
+    pub async fn get_metrics(&self) -> StreamMetrics {
+        self.metrics.read().await.clone()
+    }
+
+    /// Get connection status information
+    pub async fn get_connection_status(&self) -> ConnectionStatus {
+        self.metrics.read().await.connection_status()
+    }
+
+    pub async fn start_streaming<F>(&self, callback: F) -> Result<()>
+    where
+        F: Fn(TokenEvent) -> Result<()> + Send + Sync + 'static,
+    {
+        let (sender, mut receiver) = mpsc::channel(EVENT_CHANNEL_CAPACITY);
+
+        let consumer = async move {
+            while let Some(event) = receiver.recv().await {
+                if let Err(e) = callback(event) {
+                    warn!("Callback error: {}", e);
+                }
+            }
+        };
+
+        let (result, _) = tokio::join!(self.run(sender), consumer);
+        result
+    }
+
+    /// 并发运行所有端点，直到全部端点结束；任一端点成功结束即视为成功
+    async fn run(&self, sender: mpsc::Sender<TokenEvent>) -> Result<()> {
+        if self.endpoints.is_empty() {
+            return Err(anyhow::anyhow!("No gRPC endpoints configured for {}", self.name));
+        }
+        info!("🏁 {} 竞速订阅 {} 个端点", self.name, self.endpoints.len());
+
+        let runs = join_all(self.endpoints.iter().map(|endpoint| endpoint.run(sender.clone())));
+        drop(sender);
+
+        let report = async {
+            let mut interval = tokio::time::interval(STATS_REPORT_INTERVAL);
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                self.log_endpoint_stats();
+            }
+        };
+
+        let results = tokio::select! {
+            results = runs => results,
+            _ = report => unreachable!(),
+        };
+        self.log_endpoint_stats();
+
+        let mut last_error = None;
+        for (endpoint, result) in self.endpoints.iter().zip(results) {
+            match result {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    error!("❌ {} 端点终止: {}", endpoint.name(), e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("{} stopped", self.name)))
+    }
+
+    fn log_endpoint_stats(&self) {
+        for stats in self.endpoint_stats() {
+            info!(
+                "📡 [{}] {} | 收到 {} | 领先 {} ({:.1}%) | 平均落后 {:?} | 最大落后 {:?}",
+                self.name,
+                stats.name,
+                stats.received,
+                stats.wins,
+                stats.win_rate() * 100.0,
+                stats.average_delay(),
+                stats.max_delay
+            );
+        }
+    }
+}
+
+impl TokenEventSource for RacingTokenStream {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn metrics(&self) -> SharedStreamMetrics {
+        Arc::clone(&self.metrics)
+    }
+
+    fn event_stream(&self) -> impl Stream<Item = TokenEvent> + Send + 'static {
+        let (sender, receiver) = mpsc::channel(EVENT_CHANNEL_CAPACITY);
+        let this = self.clone();
+
+        tokio::spawn(async move {
+            if let Err(e) = this.run(sender).await {
+                error!("❌ {} 事件源终止: {}", this.name, e);
+            }
+        });
+
+        ReceiverStream::new(receiver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::processors::TransactionProcessor;
+
+    #[test]
+    fn test_endpoints_share_metrics_and_dedup() {
+        let config = |endpoint: &str| StreamShyftConfig::new(endpoint.to_string(), "token".to_string());
+        let filters = vec![TransactionFilter::new(
+            "pumpfun",
+            vec!["pump".to_string()],
+            Arc::new(TransactionProcessor::default()),
+        )];
+        let stream = RacingTokenStream::new(
+            "PumpFun",
+            vec![
+                ("ny".to_string(), config("https://grpc.ny.example")),
+                ("fra".to_string(), config("https://grpc.fra.example")),
+            ],
+            filters,
+        );
+
+        assert_eq!(stream.endpoints.len(), 2);
+        assert_eq!(stream.endpoints[1].name(), "PumpFun@fra");
+        assert!(Arc::ptr_eq(&stream.endpoints[0].metrics(), &stream.metrics));
+
+        let names: Vec<String> = stream.endpoint_stats().into_iter().map(|stats| stats.name).collect();
+        assert_eq!(names, vec!["ny".to_string(), "fra".to_string()]);
+    }
+}
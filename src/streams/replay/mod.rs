@@ -0,0 +1,5 @@
+pub mod recorder;
+pub mod stream;
+
+pub use recorder::{RecordedUpdate, RecordingReader, UpdateRecorder};
+pub use stream::{ReplayPace, ReplayStream};
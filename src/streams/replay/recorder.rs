@@ -0,0 +1,231 @@
+use anyhow::Result;
+use log::{error, info, warn};
+use prost::Message;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt, AsyncWriteExt, BufReader, BufWriter};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use yellowstone_grpc_proto::geyser::SubscribeUpdate;
+
+/// 录制文件中的一条记录：接收时间 + 原始订阅更新
+///
+/// 文件由连续的长度前缀（varint）记录组成，与 `prost::Message::encode_length_delimited` 一致。
+#[derive(Clone, PartialEq, Message)]
+pub struct RecordedUpdate {
+    /// 接收时间（Unix 微秒）
+    #[prost(uint64, tag = "1")]
+    pub received_at_micros: u64,
+    #[prost(message, optional, tag = "2")]
+    pub update: Option<SubscribeUpdate>,
+}
+
+/// 录制写入队列的默认容量（条）
+pub const DEFAULT_RECORDER_CAPACITY: usize = 8192;
+
+/// 订阅更新录制器：把 `geyser_subscribe` 收到的每条消息写入文件
+///
+/// 编码在调用方完成（不克隆消息），写盘在后台任务中进行，不阻塞订阅循环。
+/// 写入队列有界：磁盘跟不上时直接丢弃新记录并计数，而不是在订阅循环里无限占用内存。
+pub struct UpdateRecorder {
+    sender: mpsc::Sender<Vec<u8>>,
+    writer: JoinHandle<()>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl UpdateRecorder {
+    /// 创建（覆盖）录制文件并启动写入任务
+    pub async fn create(path: impl AsRef<Path>) -> Result<Self> {
+        Self::with_capacity(path, DEFAULT_RECORDER_CAPACITY).await
+    }
+
+    /// 以指定的写入队列容量创建录制器
+    pub async fn with_capacity(path: impl AsRef<Path>, capacity: usize) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = File::create(&path).await?;
+        let (sender, mut receiver) = mpsc::channel::<Vec<u8>>(capacity.max(1));
+        let dropped = Arc::new(AtomicU64::new(0));
+        let dropped_total = Arc::clone(&dropped);
+
+        let writer = tokio::spawn(async move {
+            let mut writer = BufWriter::new(file);
+            let mut records = 0u64;
+
+            while let Some(record) = receiver.recv().await {
+                if let Err(e) = writer.write_all(&record).await {
+                    error!("❌ 写入录制文件失败 {}: {}", path.display(), e);
+                    return;
+                }
+                records += 1;
+
+                // 队列已清空时落盘，进程意外退出也只丢失少量记录
+                if receiver.is_empty() {
+                    if let Err(e) = writer.flush().await {
+                        error!("❌ 刷新录制文件失败 {}: {}", path.display(), e);
+                        return;
+                    }
+                }
+            }
+
+            if let Err(e) = writer.flush().await {
+                error!("❌ 刷新录制文件失败 {}: {}", path.display(), e);
+            }
+            info!("📼 录制结束: {} 条记录 (丢弃 {}) -> {}",
+                  records, dropped_total.load(Ordering::Relaxed), path.display());
+        });
+
+        Ok(Self { sender, writer, dropped })
+    }
+
+    /// 以当前时间为接收时间录制一条更新
+    ///
+    /// 写入队列已满时丢弃该记录，不阻塞调用方。
+    pub fn record(&self, update: &SubscribeUpdate) {
+        if let Err(mpsc::error::TrySendError::Full(_)) = self.sender.try_send(encode_record(now_micros(), update)) {
+            let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+            if dropped == 1 || dropped.is_multiple_of(1000) {
+                warn!("⚠️ 录制写入队列已满，已丢弃 {} 条记录", dropped);
+            }
+        }
+    }
+
+    /// 因写入队列已满而丢弃的记录数
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// 停止录制并等待所有记录写入磁盘
+    pub async fn finish(self) {
+        let Self { sender, writer, .. } = self;
+        drop(sender);
+        let _ = writer.await;
+    }
+}
+
+/// 按 `RecordedUpdate` 的格式编码一条带长度前缀的记录
+pub fn encode_record(received_at_micros: u64, update: &SubscribeUpdate) -> Vec<u8> {
+    use prost::encoding::{encode_varint, encoded_len_varint, message, uint64};
+
+    let body_len = uint64::encoded_len(1, &received_at_micros) + message::encoded_len(2, update);
+    let mut buf = Vec::with_capacity(encoded_len_varint(body_len as u64) + body_len);
+    encode_varint(body_len as u64, &mut buf);
+    uint64::encode(1, &received_at_micros, &mut buf);
+    message::encode(2, update, &mut buf);
+    buf
+}
+
+fn now_micros() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_micros() as u64)
+        .unwrap_or_default()
+}
+
+/// 顺序读取录制文件
+pub struct RecordingReader {
+    reader: BufReader<File>,
+}
+
+impl RecordingReader {
+    pub async fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let file = File::open(path).await?;
+        Ok(Self { reader: BufReader::new(file) })
+    }
+
+    /// 读取下一条记录，文件结束时返回 `None`
+    pub async fn next_record(&mut self) -> Result<Option<RecordedUpdate>> {
+        let Some(len) = self.read_length().await? else {
+            return Ok(None);
+        };
+
+        let mut body = vec![0u8; len];
+        self.reader.read_exact(&mut body).await?;
+        Ok(Some(RecordedUpdate::decode(body.as_slice())?))
+    }
+
+    /// 读取 varint 长度前缀；在记录边界处遇到 EOF 时返回 `None`
+    async fn read_length(&mut self) -> Result<Option<usize>> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = match self.reader.read_u8().await {
+                Ok(byte) => byte,
+                Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof && shift == 0 => return Ok(None),
+                Err(e) => return Err(e.into()),
+            };
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(Some(value as usize));
+            }
+        }
+        Err(anyhow::anyhow!("Invalid record length prefix"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use yellowstone_grpc_proto::geyser::{SubscribeUpdatePing, SubscribeUpdateSlot};
+    use yellowstone_grpc_proto::prelude::subscribe_update::UpdateOneof;
+
+    #[tokio::test]
+    async fn test_recorded_updates_round_trip() {
+        let path = std::env::temp_dir().join(format!("recording-{}.bin", std::process::id()));
+        let slot_update = SubscribeUpdate {
+            filters: vec!["slots".to_string()],
+            update_oneof: Some(UpdateOneof::Slot(SubscribeUpdateSlot { slot: 42, ..Default::default() })),
+            ..Default::default()
+        };
+        let ping = SubscribeUpdate {
+            update_oneof: Some(UpdateOneof::Ping(SubscribeUpdatePing {})),
+            ..Default::default()
+        };
+
+        // 编码格式必须与 prost 生成的 RecordedUpdate 一致
+        let record = encode_record(7, &slot_update);
+        let decoded = RecordedUpdate::decode_length_delimited(record.as_slice()).unwrap();
+        assert_eq!(decoded.received_at_micros, 7);
+        assert_eq!(decoded.update.as_ref(), Some(&slot_update));
+
+        let recorder = UpdateRecorder::create(&path).await.unwrap();
+        recorder.record(&slot_update);
+        recorder.record(&ping);
+        recorder.finish().await;
+
+        let mut reader = RecordingReader::open(&path).await.unwrap();
+        let first = reader.next_record().await.unwrap().unwrap();
+        let second = reader.next_record().await.unwrap().unwrap();
+        assert_eq!(first.update, Some(slot_update));
+        assert_eq!(second.update, Some(ping));
+        assert!(second.received_at_micros >= first.received_at_micros);
+        assert!(reader.next_record().await.unwrap().is_none());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_full_queue_drops_and_counts_records() {
+        let path = std::env::temp_dir().join(format!("recording-full-{}.bin", std::process::id()));
+        let ping = SubscribeUpdate {
+            update_oneof: Some(UpdateOneof::Ping(SubscribeUpdatePing {})),
+            ..Default::default()
+        };
+
+        // 单线程运行时下写入任务尚未运行，队列只能容纳一条记录
+        let recorder = UpdateRecorder::with_capacity(&path, 1).await.unwrap();
+        recorder.record(&ping);
+        recorder.record(&ping);
+        recorder.record(&ping);
+        assert_eq!(recorder.dropped(), 2);
+        recorder.finish().await;
+
+        let mut reader = RecordingReader::open(&path).await.unwrap();
+        assert_eq!(reader.next_record().await.unwrap().unwrap().update, Some(ping));
+        assert!(reader.next_record().await.unwrap().is_none());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,222 @@
+use anyhow::Result;
+use futures::Stream;
+use log::{error, info, warn};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use yellowstone_grpc_proto::prelude::subscribe_update::UpdateOneof;
+
+use crate::processors::TokenEvent;
+use crate::streams::dedup::{Arrival, SignatureDeduplicator, DEFAULT_DEDUP_CAPACITY};
+use crate::streams::geyser::stream::{detect_with_filters, log_token_event};
+use crate::streams::geyser::TransactionFilter;
+use crate::streams::replay::recorder::RecordingReader;
+use crate::streams::source::{SharedStreamMetrics, StreamMetrics, TokenEventSource};
+
+/// 事件通道容量
+const EVENT_CHANNEL_CAPACITY: usize = 4096;
+
+/// 重放速度
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayPace {
+    /// 按录制时的接收间隔重放
+    Recorded,
+    /// 尽可能快地重放
+    AsFastAsPossible,
+}
+
+/// 录制文件重放源：把录制的订阅更新重新交给检测器，产生与线上相同的事件
+///
+/// 过滤器按录制时服务端返回的过滤器名匹配（"pumpfun" / "letsbonk"），
+/// 竞速模式录下的重复交易按签名去重。文件读完后事件流结束。
+#[derive(Clone)]
+pub struct ReplayStream {
+    path: PathBuf,
+    pace: ReplayPace,
+    filters: Vec<TransactionFilter>,
+    metrics: SharedStreamMetrics,
+}
+
+impl ReplayStream {
+    pub fn new(path: impl Into<PathBuf>, pace: ReplayPace) -> Self {
+        Self {
+            path: path.into(),
+            pace,
+            filters: Vec::new(),
+            metrics: StreamMetrics::shared(),
+        }
+    }
+
+    /// 添加一个命名过滤器
+    pub fn with_filter(mut self, filter: TransactionFilter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    pub async fn get_metrics(&self) -> StreamMetrics {
+        self.metrics.read().await.clone()
+    }
+
+    /// 回调形式的重放，直到文件读完
+    pub async fn start_streaming<F>(&self, callback: F) -> Result<()>
+    where
+        F: Fn(TokenEvent) -> Result<()> + Send + Sync + 'static,
+    {
+        let (sender, mut receiver) = mpsc::channel(EVENT_CHANNEL_CAPACITY);
+
+        let consumer = async move {
+            while let Some(event) = receiver.recv().await {
+                if let Err(e) = callback(event) {
+                    warn!("Callback error: {}", e);
+                }
+            }
+        };
+
+        let (result, _) = tokio::join!(self.run(sender), consumer);
+        result
+    }
+
+    async fn run(&self, sender: mpsc::Sender<TokenEvent>) -> Result<()> {
+        info!("📼 开始重放 {} ({:?})", self.path.display(), self.pace);
+
+        let mut reader = RecordingReader::open(&self.path).await?;
+        let dedup = SignatureDeduplicator::new(DEFAULT_DEDUP_CAPACITY, vec![self.name().to_string()]);
+        self.metrics.write().await.connection_opened();
+
+        // 录制时间轴与重放时间轴的起点
+        let mut timeline: Option<(u64, Instant)> = None;
+        let result = async {
+            while let Some(record) = reader.next_record().await? {
+                if self.pace == ReplayPace::Recorded {
+                    let (first_micros, started) = *timeline.get_or_insert_with(|| (record.received_at_micros, Instant::now()));
+                    let offset = Duration::from_micros(record.received_at_micros.saturating_sub(first_micros));
+                    tokio::time::sleep_until((started + offset).into()).await;
+                }
+
+                let Some(msg) = record.update else { continue };
+                self.metrics.write().await.record_message();
+
+                let Some(UpdateOneof::Transaction(update)) = msg.update_oneof else { continue };
+                let Some(txn_info) = update.transaction else { continue };
+                if let Arrival::Duplicate { .. } = dedup.observe(&txn_info.signature, 0) {
+                    continue;
+                }
+                self.metrics.write().await.transactions_processed += 1;
+
+                for (filter_name, token_event) in detect_with_filters(&self.filters, &msg.filters, &txn_info, update.slot).await {
+                    log_token_event(filter_name, &token_event);
+                    self.metrics.write().await.record_event(&token_event);
+
+                    if sender.send(token_event).await.is_err() {
+                        return Err(anyhow::anyhow!("Replay event receiver dropped"));
+                    }
+                }
+            }
+            Ok::<(), anyhow::Error>(())
+        }
+        .await;
+
+        self.metrics.write().await.connection_closed();
+        let metrics = self.get_metrics().await;
+        info!("📼 重放结束: {} 笔交易，{} 个事件", metrics.transactions_processed, metrics.events_emitted);
+        result
+    }
+}
+
+impl TokenEventSource for ReplayStream {
+    fn name(&self) -> &str {
+        "Replay"
+    }
+
+    fn metrics(&self) -> SharedStreamMetrics {
+        Arc::clone(&self.metrics)
+    }
+
+    fn event_stream(&self) -> impl Stream<Item = TokenEvent> + Send + 'static {
+        let (sender, receiver) = mpsc::channel(EVENT_CHANNEL_CAPACITY);
+        let this = self.clone();
+
+        tokio::spawn(async move {
+            if let Err(e) = this.run(sender).await {
+                error!("❌ {} 事件源终止: {}", this.name(), e);
+            }
+        });
+
+        ReceiverStream::new(receiver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use async_trait::async_trait;
+    use futures::StreamExt;
+    use yellowstone_grpc_proto::geyser::{
+        SubscribeUpdate, SubscribeUpdateTransaction, SubscribeUpdateTransactionInfo,
+    };
+    use solana_sdk::pubkey::Pubkey;
+
+    use crate::processors::{ProtocolEvent, TransactionEventDetector};
+    use crate::streams::replay::UpdateRecorder;
+
+    /// 每笔交易都产生一个创建事件的检测器
+    struct EveryTransaction;
+
+    #[async_trait]
+    impl TransactionEventDetector for EveryTransaction {
+        async fn detect(&self, txn_info: &SubscribeUpdateTransactionInfo, slot: u64) -> Option<TokenEvent> {
+            Some(TokenEvent::new(
+                bs58::encode(&txn_info.signature).into_string(),
+                slot,
+                ProtocolEvent::PumpFunCreate {
+                    mint: Pubkey::new_unique(),
+                    creator: Pubkey::new_unique(),
+                    metadata: None,
+                    dev_buy: None,
+                },
+            ))
+        }
+    }
+
+    fn transaction_update(filter: &str, signature: u8, slot: u64) -> SubscribeUpdate {
+        SubscribeUpdate {
+            filters: vec![filter.to_string()],
+            update_oneof: Some(UpdateOneof::Transaction(SubscribeUpdateTransaction {
+                transaction: Some(SubscribeUpdateTransactionInfo {
+                    signature: vec![signature; 64],
+                    ..Default::default()
+                }),
+                slot,
+            })),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replay_dispatches_by_filter_and_dedups() {
+        let path = std::env::temp_dir().join(format!("replay-{}.bin", std::process::id()));
+        let recorder = UpdateRecorder::create(&path).await.unwrap();
+        recorder.record(&transaction_update("pumpfun", 1, 10));
+        // 竞速录制下的重复交易
+        recorder.record(&transaction_update("pumpfun", 1, 10));
+        // 未配置的过滤器
+        recorder.record(&transaction_update("letsbonk", 2, 11));
+        recorder.record(&transaction_update("pumpfun", 3, 12));
+        recorder.finish().await;
+
+        let replay = ReplayStream::new(&path, ReplayPace::AsFastAsPossible)
+            .with_filter(TransactionFilter::new("pumpfun", vec![], Arc::new(EveryTransaction)));
+        let slots: Vec<u64> = replay.event_stream().map(|event| event.slot).collect().await;
+
+        assert_eq!(slots, vec![10, 12]);
+        let metrics = replay.get_metrics().await;
+        assert_eq!(metrics.transactions_processed, 3);
+        assert_eq!(metrics.tokens_detected, 2);
+        assert!(!metrics.is_connected);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
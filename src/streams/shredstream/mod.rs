@@ -0,0 +1,3 @@
+pub mod stream;
+
+pub use stream::{ShredStream, EntryTokenDetector};
\ No newline at end of file
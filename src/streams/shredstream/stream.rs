@@ -0,0 +1,422 @@
+use anyhow::Result;
+use backoff::future::retry;
+use backoff::ExponentialBackoff;
+use futures::Stream;
+use futures_util::StreamExt;
+use jito_protos::shredstream::{
+    shredstream_proxy_client::ShredstreamProxyClient, SubscribeEntriesRequest,
+};
+use log::{debug, info, warn, error};
+use solana_entry::entry::Entry as SolanaEntry;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::transport::{Channel, ClientTlsConfig, Endpoint};
+
+use crate::config::StreamShredConfig;
+use crate::processors::{LaunchpadInitializeAccounts, LaunchpadPlatforms, ProtocolEvent, TokenDetector, TokenEvent, TokenMetadata, TransactionData, TransactionProcessor};
+use crate::streams::source::{ConnectionStatus, SharedStreamMetrics, StreamMetrics, TokenEventSource};
+
+#[cfg(feature = "letsbonk")]
+use raydium_launchpad_interface::RaydiumLaunchpadProgramIx;
+
+// Raydium Launchpad Program ID
+const RAYDIUM_LAUNCHPAD_PROGRAM_ID: &str = "LanMV9sAd7wArD4vJFi2qDdfnVhFxYSUg6eADduJ3uj";
+
+/// 事件通道容量
+const EVENT_CHANNEL_CAPACITY: usize = 4096;
+
+/// 从 Jito ShredStream 代理读取 Entry，在交易执行前检测 PumpFun / LetsBonk 代币创建
+#[derive(Clone)]
+pub struct ShredStream {
+    config: StreamShredConfig,
+    metrics: SharedStreamMetrics,
+}
+
+impl ShredStream {
+    pub fn new(config: StreamShredConfig) -> Self {
+        Self {
+            config,
+            metrics: StreamMetrics::shared(),
+        }
+    }
+
+    pub async fn get_metrics(&self) -> StreamMetrics {
+        self.metrics.read().await.clone()
+    }
+
+    /// Get connection status information
+    pub async fn get_connection_status(&self) -> ConnectionStatus {
+        self.metrics.read().await.connection_status()
+    }
+
+    pub async fn start_streaming<F>(&self, callback: F) -> Result<()>
+    where
+        F: Fn(TokenEvent) -> Result<()> + Send + Sync + 'static,
+    {
+        let (sender, mut receiver) = mpsc::channel(EVENT_CHANNEL_CAPACITY);
+
+        let consumer = async move {
+            while let Some(event) = receiver.recv().await {
+                if let Err(e) = callback(event) {
+                    warn!("Callback error: {}", e);
+                }
+            }
+        };
+
+        let (result, _) = tokio::join!(self.run(sender), consumer);
+        result
+    }
+
+    /// 带指数退避重连的订阅循环，事件写入 `sender`
+    async fn run(&self, sender: mpsc::Sender<TokenEvent>) -> Result<()> {
+        info!("🚀 Starting Jito ShredStream entry stream...");
+
+        // 事件源需要与进程同生命周期，不限制总重试时长
+        let backoff = ExponentialBackoff { max_elapsed_time: None, ..Default::default() };
+        let mut attempt = 0u64;
+        retry(backoff, || {
+            attempt += 1;
+            let attempt = attempt;
+            let sender = sender.clone();
+
+            async move {
+                if attempt > 1 {
+                    info!("Retry to connect to the ShredStream proxy");
+                }
+
+                {
+                    let mut metrics = self.metrics.write().await;
+                    metrics.reconnection_attempts += 1;
+                    metrics.last_connection_time = Some(Instant::now());
+                }
+                let mut client = connect_client(&self.config).await.map_err(backoff::Error::transient)?;
+                info!("Connected to ShredStream proxy: {}", self.config.endpoint);
+
+                let result = self.subscribe_entries(&mut client, &sender).await;
+                self.metrics.write().await.is_connected = false;
+
+                match result {
+                    // 接收端已关闭，不再重连
+                    Err(e) if sender.is_closed() => Err(backoff::Error::Permanent(e)),
+                    Err(e) => Err(backoff::Error::transient(e)),
+                    Ok(()) => Ok(()),
+                }
+            }
+        })
+        .await
+    }
+
+    async fn subscribe_entries(
+        &self,
+        client: &mut ShredstreamProxyClient<Channel>,
+        sender: &mpsc::Sender<TokenEvent>,
+    ) -> Result<()> {
+        let detector = EntryTokenDetector::new()?;
+        let mut stream = client
+            .subscribe_entries(SubscribeEntriesRequest {})
+            .await?
+            .into_inner();
+        self.metrics.write().await.is_connected = true;
+        info!("✅ ShredStream opened, listening for entries...");
+
+        // 看门狗：连接未断但长时间没有 Entry 时强制重连
+        let stall_timeout = Duration::from_secs(self.config.stall_timeout_seconds.max(1));
+        loop {
+            let message = match tokio::time::timeout(stall_timeout, stream.next()).await {
+                Ok(Some(message)) => message,
+                Ok(None) => break,
+                Err(_) => {
+                    warn!("⏰ ShredStream 超过 {:?} 未收到任何 Entry，强制重连", stall_timeout);
+                    self.metrics.write().await.stall_reconnects += 1;
+                    return Err(anyhow::anyhow!("ShredStream stalled"));
+                }
+            };
+
+            match message {
+                Ok(entry) => {
+                    self.metrics.write().await.record_message();
+                    let entries: Vec<SolanaEntry> = match bincode::deserialize(&entry.entries) {
+                        Ok(entries) => entries,
+                        Err(e) => {
+                            warn!("❌ Entry 反序列化失败 (Slot: {}): {}", entry.slot, e);
+                            self.metrics.write().await.decode_errors += 1;
+                            continue;
+                        }
+                    };
+
+                    let transactions: Vec<VersionedTransaction> = entries
+                        .into_iter()
+                        .flat_map(|entry| entry.transactions)
+                        .collect();
+
+                    self.metrics.write().await.transactions_processed += transactions.len() as u64;
+                    debug!("📦 收到Entry: Slot {}, {} 笔交易", entry.slot, transactions.len());
+
+                    for transaction in transactions {
+                        let tx_data = TransactionData { transaction, slot: entry.slot };
+                        for token_event in detector.detect(&tx_data) {
+                            if token_event.is_creation() {
+                                info!("🚀 NEW TOKEN (shred) | {} | Slot: {}",
+                                     token_event.mint(),
+                                     token_event.slot);
+                            }
+                            self.metrics.write().await.record_event(&token_event);
+
+                            if sender.send(token_event).await.is_err() {
+                                return Err(anyhow::anyhow!("ShredStream event receiver dropped"));
+                            }
+                        }
+                    }
+                }
+                Err(error) => {
+                    error!("ShredStream error: {:?}", error);
+                    self.metrics.write().await.connection_errors += 1;
+                    return Err(anyhow::anyhow!("ShredStream error: {:?}", error));
+                }
+            }
+        }
+
+        info!("ShredStream closed");
+        Ok(())
+    }
+}
+
+impl TokenEventSource for ShredStream {
+    fn name(&self) -> &str {
+        "ShredStream"
+    }
+
+    fn metrics(&self) -> SharedStreamMetrics {
+        Arc::clone(&self.metrics)
+    }
+
+    fn event_stream(&self) -> impl Stream<Item = TokenEvent> + Send + 'static {
+        let (sender, receiver) = mpsc::channel(EVENT_CHANNEL_CAPACITY);
+        let this = self.clone();
+
+        tokio::spawn(async move {
+            if let Err(e) = this.run(sender).await {
+                error!("❌ ShredStream 事件源终止: {}", e);
+            }
+        });
+
+        ReceiverStream::new(receiver)
+    }
+}
+
+/// 在未执行的交易上检测代币事件（只使用静态账户）
+pub struct EntryTokenDetector {
+    processor: TransactionProcessor,
+    pumpfun_detector: TokenDetector,
+    raydium_launchpad_program_id: Pubkey,
+    launchpad_platforms: Arc<LaunchpadPlatforms>,
+}
+
+impl EntryTokenDetector {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            processor: TransactionProcessor::new()?,
+            pumpfun_detector: TokenDetector::new()?,
+            raydium_launchpad_program_id: Pubkey::from_str(RAYDIUM_LAUNCHPAD_PROGRAM_ID)?,
+            launchpad_platforms: LaunchpadPlatforms::global(),
+        })
+    }
+
+    /// 检测一笔 Entry 交易中的代币事件
+    pub fn detect(&self, tx_data: &TransactionData) -> Vec<TokenEvent> {
+        let mut events = Vec::new();
+
+        // 优先使用IDL无meta解码（含开发者买入金额），IDL不可用时退化为判别器检测
+        if let Some(event) = self.processor.process_unconfirmed_transaction(&tx_data.transaction, tx_data.slot, None) {
+            events.push(event);
+        } else if let Some(creation) = self.pumpfun_detector.detect_token_creation(tx_data) {
+            events.extend(self.build_event(tx_data, |creator| ProtocolEvent::PumpFunCreate {
+                mint: creation.mint,
+                creator,
+                metadata: None,
+                dev_buy: None,
+            }));
+        }
+
+        if let Some((accounts, metadata)) = self.detect_launchpad_initialize(&tx_data.transaction) {
+            // 只输出白名单平台的池；创建者取自 Initialize 的命名账户，而不是交易付款人
+            if let Some(platform) = self.launchpad_platforms.classify(&accounts.platform_config) {
+                let event = self.build_event(tx_data, |_| ProtocolEvent::LaunchpadInitialize {
+                    mint: accounts.base_mint,
+                    creator: accounts.creator,
+                    metadata: Some(metadata),
+                    dev_buy: None,
+                    pool: Some(accounts.pool()),
+                    platform: platform.name(),
+                });
+                events.extend(event.map(|event| TokenEvent { token_program: accounts.base_token_program, ..event }));
+            }
+        }
+
+        events
+    }
+
+    /// 未执行的交易没有 meta，查找表中的账户无法解析，此时放弃该指令
+    #[cfg(feature = "letsbonk")]
+    fn detect_launchpad_initialize(&self, tx: &VersionedTransaction) -> Option<(LaunchpadInitializeAccounts, TokenMetadata)> {
+        let keys = tx.message.static_account_keys();
+
+        for instr in tx.message.instructions() {
+            if keys.get(instr.program_id_index as usize) != Some(&self.raydium_launchpad_program_id) {
+                continue;
+            }
+            let Ok(ix) = RaydiumLaunchpadProgramIx::deserialize(&instr.data) else {
+                continue;
+            };
+            if let Some(mint_param) = ix.get_token_info() {
+                let accounts: Vec<Pubkey> = instr
+                    .accounts
+                    .iter()
+                    .map(|&index| keys.get(index as usize).copied())
+                    .collect::<Option<_>>()?;
+                let accounts = LaunchpadInitializeAccounts::for_instruction(&ix, &accounts)?;
+                let metadata = TokenMetadata {
+                    name: mint_param.name.clone(),
+                    symbol: mint_param.symbol.clone(),
+                    uri: mint_param.uri.clone(),
+                };
+                return Some((accounts, metadata));
+            }
+        }
+
+        None
+    }
+
+    #[cfg(not(feature = "letsbonk"))]
+    fn detect_launchpad_initialize(&self, _tx: &VersionedTransaction) -> Option<(LaunchpadInitializeAccounts, TokenMetadata)> {
+        None
+    }
+
+    /// 以交易的付款人作为创建者构建未确认事件
+    fn build_event(&self, tx_data: &TransactionData, kind: impl FnOnce(Pubkey) -> ProtocolEvent) -> Option<TokenEvent> {
+        let account_keys = tx_data.transaction.message.static_account_keys().to_vec();
+        let signature = tx_data.transaction.signatures
+            .first()
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+
+        let mut event = TokenEvent::new(signature, tx_data.slot, kind(*account_keys.first()?));
+        event.account_keys = account_keys;
+        event.timestamp = Some(chrono::Utc::now().timestamp());
+        event.unconfirmed = true;
+        Some(event)
+    }
+}
+
+/// Create a ShredStream proxy client from config
+async fn connect_client(config: &StreamShredConfig) -> Result<ShredstreamProxyClient<Channel>> {
+    let mut endpoint = Endpoint::from_shared(config.endpoint.clone())?
+        .connect_timeout(Duration::from_secs(config.timeout_seconds));
+
+    if config.endpoint.starts_with("https") {
+        endpoint = endpoint.tls_config(ClientTlsConfig::new().with_native_roots())?;
+    }
+
+    let channel = endpoint.connect().await?;
+    Ok(ShredstreamProxyClient::new(channel))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jito_protos::shredstream::{
+        shredstream_proxy_server::{ShredstreamProxy, ShredstreamProxyServer},
+        Entry as ProtoEntry,
+    };
+    use solana_sdk::hash::Hash;
+    use solana_sdk::instruction::CompiledInstruction;
+    use solana_sdk::message::{Message, VersionedMessage};
+    use solana_sdk::signature::Signature;
+    use std::pin::Pin;
+    use tokio_stream::wrappers::TcpListenerStream;
+    use tonic::{Request, Response, Status};
+
+    struct RecordedProxy {
+        entries: Vec<ProtoEntry>,
+    }
+
+    #[tonic::async_trait]
+    impl ShredstreamProxy for RecordedProxy {
+        type SubscribeEntriesStream =
+            Pin<Box<dyn futures::Stream<Item = std::result::Result<ProtoEntry, Status>> + Send>>;
+
+        async fn subscribe_entries(
+            &self,
+            _request: Request<SubscribeEntriesRequest>,
+        ) -> std::result::Result<Response<Self::SubscribeEntriesStream>, Status> {
+            let entries = self.entries.clone().into_iter().map(Ok);
+            Ok(Response::new(Box::pin(futures::stream::iter(entries))))
+        }
+    }
+
+    fn pumpfun_create_transaction(mint: Pubkey, creator: Pubkey) -> VersionedTransaction {
+        let pumpfun = Pubkey::from_str(crate::processors::token_detector::PUMP_PROGRAM_PUBKEY).unwrap();
+        let message = Message {
+            header: Default::default(),
+            account_keys: vec![creator, mint, pumpfun],
+            recent_blockhash: Hash::default(),
+            instructions: vec![CompiledInstruction {
+                program_id_index: 2,
+                accounts: vec![1, 0],
+                data: vec![24, 30, 200, 40, 5, 28, 7, 119],
+            }],
+        };
+
+        VersionedTransaction {
+            signatures: vec![Signature::default()],
+            message: VersionedMessage::Legacy(message),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_streams_token_creation_from_recorded_entries() {
+        let mint = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+        let entry = SolanaEntry {
+            num_hashes: 1,
+            hash: Hash::default(),
+            transactions: vec![pumpfun_create_transaction(mint, creator)],
+        };
+        let recorded = ProtoEntry {
+            slot: 42,
+            entries: bincode::serialize(&vec![entry]).unwrap(),
+        };
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(ShredstreamProxyServer::new(RecordedProxy { entries: vec![recorded] }))
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let events_clone = Arc::clone(&events);
+        let stream = ShredStream::new(StreamShredConfig::new(format!("http://{}", addr)));
+        stream
+            .start_streaming(move |event| {
+                events_clone.lock().unwrap().push(event);
+                Ok(())
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(stream.get_metrics().await.tokens_detected, 1);
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].slot, 42);
+        assert_eq!(events[0].mint(), mint);
+        assert_eq!(events[0].wallet(), Some(creator));
+    }
+}
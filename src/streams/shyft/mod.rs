@@ -1,3 +1,3 @@
 pub mod stream;
 
-pub use stream::ShyftStream;
\ No newline at end of file
+pub use stream::{ShyftStream, pumpfun_filter};
\ No newline at end of file
@@ -0,0 +1,227 @@
+use futures::Stream;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+use crate::processors::TokenEvent;
+
+/// 消息速率的统计窗口
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// 所有事件源共享的运行指标
+#[derive(Debug, Clone, Default)]
+pub struct StreamMetrics {
+    pub transactions_processed: u64,
+    /// 检测到的新代币创建数
+    pub tokens_detected: u64,
+    /// 发出的事件总数（创建+买卖）
+    pub events_emitted: u64,
+    pub decode_errors: u64,
+    pub connection_errors: u64,
+    pub reconnection_attempts: u64,
+    pub last_connection_time: Option<Instant>,
+    pub is_connected: bool,
+    /// 当前打开的订阅数（多端点竞速时可能大于1）
+    pub active_connections: u32,
+    /// 最近完整处理的slot
+    pub last_processed_slot: Option<u64>,
+    /// 通过 `from_slot` 成功重放续订的次数
+    pub resumed_subscriptions: u64,
+    /// 断线期间无法重放、可能错过事件的slot总数
+    pub unreplayed_slots: u64,
+    /// 最近一次收到任何消息（交易、slot、ping等）的时间
+    pub last_message_at: Option<Instant>,
+    /// 最近一次收到服务端ping的时间
+    pub last_ping_at: Option<Instant>,
+    /// 最近统计窗口内的消息速率
+    pub messages_per_sec: f64,
+    /// 订阅静默超时被强制重连的次数
+    pub stall_reconnects: u64,
+    rate_window_start: Option<Instant>,
+    rate_window_messages: u64,
+}
+
+/// 连接状态快照
+#[derive(Debug, Clone)]
+pub struct ConnectionStatus {
+    pub is_connected: bool,
+    pub reconnection_attempts: u64,
+    pub last_connection_time: Option<Instant>,
+    pub connection_errors: u64,
+    pub last_message_at: Option<Instant>,
+    pub messages_per_sec: f64,
+}
+
+/// 数据新鲜度：策略层据此判断事件流是否可信
+#[derive(Debug, Clone)]
+pub struct FeedFreshness {
+    pub is_connected: bool,
+    /// 距最近一条消息的时长，从未收到消息时为 None
+    pub last_message_age: Option<Duration>,
+    pub messages_per_sec: f64,
+}
+
+impl FeedFreshness {
+    /// 已连接且最近 `max_age` 内收到过消息
+    pub fn is_fresh(&self, max_age: Duration) -> bool {
+        self.is_connected && self.last_message_age.is_some_and(|age| age <= max_age)
+    }
+}
+
+pub type SharedStreamMetrics = Arc<RwLock<StreamMetrics>>;
+
+impl StreamMetrics {
+    pub fn shared() -> SharedStreamMetrics {
+        Arc::new(RwLock::new(Self::default()))
+    }
+
+    /// 记录一个即将发出的事件
+    pub fn record_event(&mut self, event: &TokenEvent) {
+        self.events_emitted += 1;
+        if event.is_creation() {
+            self.tokens_detected += 1;
+        }
+    }
+
+    /// 一个订阅打开
+    pub fn connection_opened(&mut self) {
+        self.active_connections += 1;
+        self.is_connected = true;
+    }
+
+    /// 一个订阅关闭；仍有其他订阅时保持已连接
+    pub fn connection_closed(&mut self) {
+        self.active_connections = self.active_connections.saturating_sub(1);
+        self.is_connected = self.active_connections > 0;
+    }
+
+    /// 记录收到一条消息，更新最近消息时间与速率
+    pub fn record_message(&mut self) {
+        self.record_message_at(Instant::now());
+    }
+
+    fn record_message_at(&mut self, now: Instant) {
+        self.last_message_at = Some(now);
+        self.rate_window_messages += 1;
+
+        let window_start = *self.rate_window_start.get_or_insert(now);
+        let elapsed = now.saturating_duration_since(window_start);
+        if elapsed >= RATE_WINDOW {
+            self.messages_per_sec = self.rate_window_messages as f64 / elapsed.as_secs_f64();
+            self.rate_window_start = Some(now);
+            self.rate_window_messages = 0;
+        }
+    }
+
+    /// 当前的数据新鲜度
+    pub fn freshness(&self) -> FeedFreshness {
+        self.freshness_at(Instant::now())
+    }
+
+    fn freshness_at(&self, now: Instant) -> FeedFreshness {
+        let last_message_age = self.last_message_at.map(|at| now.saturating_duration_since(at));
+        // 超过两个统计窗口没有消息时速率视为0
+        let messages_per_sec = match last_message_age {
+            Some(age) if age <= RATE_WINDOW * 2 => self.messages_per_sec,
+            _ => 0.0,
+        };
+
+        FeedFreshness {
+            is_connected: self.is_connected,
+            last_message_age,
+            messages_per_sec,
+        }
+    }
+
+    pub fn connection_status(&self) -> ConnectionStatus {
+        ConnectionStatus {
+            is_connected: self.is_connected,
+            reconnection_attempts: self.reconnection_attempts,
+            last_connection_time: self.last_connection_time,
+            connection_errors: self.connection_errors,
+            last_message_at: self.last_message_at,
+            messages_per_sec: self.freshness().messages_per_sec,
+        }
+    }
+}
+
+/// 代币事件源：统一 gRPC / ShredStream 等数据源的接口
+///
+/// `event_stream` 在后台启动连接（含重连），返回的流在源永久失败或被丢弃时结束，
+/// 因此多个源可以直接用 `futures::stream::select` 等组合、过滤和测试。
+pub trait TokenEventSource: Send + Sync {
+    /// 事件源名称，用于日志
+    fn name(&self) -> &str;
+
+    /// 共享的运行指标
+    fn metrics(&self) -> SharedStreamMetrics;
+
+    /// 启动事件源并返回事件流
+    fn event_stream(&self) -> impl Stream<Item = TokenEvent> + Send + 'static;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processors::{ProtocolEvent, TradeDirection};
+    use solana_sdk::pubkey::Pubkey;
+
+    #[test]
+    fn test_record_event_counts_creations() {
+        let mut metrics = StreamMetrics::default();
+        let (mint, wallet) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut event = TokenEvent::new(
+            "sig",
+            1,
+            ProtocolEvent::PumpFunCreate { mint, creator: wallet, metadata: None, dev_buy: None },
+        );
+
+        metrics.record_event(&event);
+        event.kind = ProtocolEvent::PumpFunTrade {
+            mint,
+            trader: wallet,
+            direction: TradeDirection::Buy,
+            sol_amount: 1,
+            token_amount: 1,
+        };
+        metrics.record_event(&event);
+
+        assert_eq!(metrics.events_emitted, 2);
+        assert_eq!(metrics.tokens_detected, 1);
+        assert!(!metrics.connection_status().is_connected);
+    }
+
+    #[test]
+    fn test_connection_tracking_with_multiple_subscriptions() {
+        let mut metrics = StreamMetrics::default();
+        metrics.connection_opened();
+        metrics.connection_opened();
+        metrics.connection_closed();
+        assert!(metrics.is_connected);
+        metrics.connection_closed();
+        assert!(!metrics.is_connected);
+        metrics.connection_closed();
+        assert_eq!(metrics.active_connections, 0);
+    }
+
+    #[test]
+    fn test_message_rate_and_freshness() {
+        let mut metrics = StreamMetrics::default();
+        let start = Instant::now();
+        assert!(!metrics.freshness_at(start).is_fresh(Duration::from_secs(5)));
+
+        metrics.connection_opened();
+        for i in 0..=10 {
+            metrics.record_message_at(start + Duration::from_millis(i * 100));
+        }
+        let now = start + Duration::from_millis(1500);
+        let freshness = metrics.freshness_at(now);
+        assert!((freshness.messages_per_sec - 11.0).abs() < 1e-9);
+        assert!(freshness.is_fresh(Duration::from_secs(1)));
+        assert!(!freshness.is_fresh(Duration::from_millis(100)));
+
+        let stale = metrics.freshness_at(start + Duration::from_secs(10));
+        assert_eq!(stale.messages_per_sec, 0.0);
+        assert!(!stale.is_fresh(Duration::from_secs(5)));
+    }
+}
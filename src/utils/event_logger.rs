@@ -54,37 +54,23 @@ impl EventLogger {
         info!("🎉 ===== 新代币创建检测 =====");
         info!("   🆔 交易签名: {}", event.signature);
         info!("   📍 Slot: {}", event.slot);
+        info!("   🏷️ 协议: {:?}", event.protocol());
+        info!("   🪙 代币地址: {}", event.mint());
         
-        if let Some(mint) = &event.mint {
-            info!("   🪙 代币地址: {}", mint);
-        }
-        
-        if let Some(creator) = &event.creator_wallet {
+        if let Some(creator) = event.wallet() {
             info!("   👤 创建者钱包: {}", creator);
         }
+
+        if let Some(metadata) = event.kind.metadata() {
+            info!("   📛 名称: {} ({})", metadata.name, metadata.symbol);
+            info!("   🔗 URI: {}", metadata.uri);
+        }
         
-        // 检查是否包含买入信息（创建后立即买入）
-        let has_buy_info = event.sol_amount.is_some() && event.token_amount.is_some();
-        
-        if has_buy_info {
+        // 创建后立即买入
+        if let Some(dev_buy) = event.dev_buy() {
             info!("   💫 **创建后立即买入**");
-            
-            if let Some(sol_amount) = event.sol_amount {
-                info!("   💰 买入金额: {:.4} SOL", sol_amount as f64 / 1_000_000_000.0);
-            }
-            
-            if let Some(token_amount) = event.token_amount {
-                info!("   🎯 最小代币输出: {} tokens", Self::format_large_number(token_amount));
-            }
-        } else {
-            // 没有买入信息时，可能是创建成本信息
-            if let Some(sol_amount) = event.sol_amount {
-                info!("   💰 创建成本: {:.4} SOL", sol_amount as f64 / 1_000_000_000.0);
-            }
-            
-            if let Some(token_amount) = event.token_amount {
-                info!("   🪙 初始供应量: {} tokens", Self::format_large_number(token_amount));
-            }
+            info!("   💰 买入金额: {:.4} SOL", dev_buy.sol_amount as f64 / 1_000_000_000.0);
+            info!("   🎯 代币数量: {} tokens", Self::format_large_number(dev_buy.token_amount));
         }
         
         if let Some(timestamp) = event.timestamp {
@@ -92,10 +78,10 @@ impl EventLogger {
             info!("   ⏰ 时间: {}", dt.format("%Y-%m-%d %H:%M:%S UTC"));
         }
         
-        info!("   🔍 检测方法: {}", event.detection_method);
+        info!("   🔍 事件类型: {}", event.kind.name());
         
         // 如果包含买入信息，提供额外的提示
-        if has_buy_info {
+        if event.dev_buy().is_some() {
             info!("   ⚡ 这是一个创建+买入组合交易！");
         }
         
@@ -106,20 +92,17 @@ impl EventLogger {
     pub fn print_buy_event(&self, event: &TokenEvent) {
         info!("💰 ===== 代币买入交易 =====");
         info!("   🆔 交易签名: {}", event.signature);
+        info!("   🪙 代币地址: {}", event.mint());
         
-        if let Some(mint) = &event.mint {
-            info!("   🪙 代币地址: {}", mint);
-        }
-        
-        if let Some(trader) = &event.creator_wallet {
+        if let Some(trader) = event.wallet() {
             info!("   👤 买入者钱包: {}", trader);
         }
         
-        if let Some(sol_amount) = event.sol_amount {
+        if let Some(sol_amount) = event.sol_amount() {
             info!("   💸 买入金额: {:.9} SOL", sol_amount as f64 / 1_000_000_000.0);
         }
         
-        if let Some(token_amount) = event.token_amount {
+        if let Some(token_amount) = event.token_amount() {
             info!("   🪙 获得代币: {} tokens", Self::format_large_number(token_amount));
         }
         
@@ -129,7 +112,7 @@ impl EventLogger {
         }
         
         info!("   📍 Slot: {}", event.slot);
-        info!("   🔍 检测方法: {}", event.detection_method);
+        info!("   🔍 事件类型: {}", event.kind.name());
         info!("============================");
     }
 
@@ -137,20 +120,17 @@ impl EventLogger {
     pub fn print_sell_event(&self, event: &TokenEvent) {
         info!("💸 ===== 代币卖出交易 =====");
         info!("   🆔 交易签名: {}", event.signature);
+        info!("   🪙 代币地址: {}", event.mint());
         
-        if let Some(mint) = &event.mint {
-            info!("   🪙 代币地址: {}", mint);
-        }
-        
-        if let Some(trader) = &event.creator_wallet {
+        if let Some(trader) = event.wallet() {
             info!("   👤 卖出者钱包: {}", trader);
         }
         
-        if let Some(token_amount) = event.token_amount {
+        if let Some(token_amount) = event.token_amount() {
             info!("   🪙 卖出代币: {} tokens", Self::format_large_number(token_amount));
         }
         
-        if let Some(sol_amount) = event.sol_amount {
+        if let Some(sol_amount) = event.sol_amount() {
             info!("   💰 获得SOL: {:.4} SOL", sol_amount as f64 / 1_000_000_000.0);
         }
         
@@ -160,7 +140,7 @@ impl EventLogger {
         }
         
         info!("   📍 Slot: {}", event.slot);
-        info!("   🔍 检测方法: {}", event.detection_method);
+        info!("   🔍 事件类型: {}", event.kind.name());
         info!("============================");
     }
 
@@ -172,7 +152,7 @@ impl EventLogger {
         }
 
         // 根据事件类型打印格式化信息
-        match event.transaction_type {
+        match event.transaction_type() {
             TransactionType::TokenCreation => {
                 self.print_token_creation_event(event);
             }
@@ -183,7 +163,7 @@ impl EventLogger {
                 // self.print_sell_event(event);
             }
             TransactionType::Unknown => {
-                info!("🔍 检测到未知类型交易: {}", event.signature);
+                info!("🔍 检测到{}事件: {}", event.kind.name(), event.signature);
             }
         }
 